@@ -1,7 +1,7 @@
 #![deny(warnings)]
 
 use embedded_hal::prelude::*;
-use epd_waveshare::{epd1in54::Epd1in54, prelude::*};
+use epd_waveshare::{epd1in54::Epd1in54, error::Error, prelude::*};
 use linux_embedded_hal::{
     spidev::{self, SpidevOptions},
     sysfs_gpio::Direction,
@@ -12,7 +12,7 @@ use linux_embedded_hal::{
 // needs to be run with sudo because of some sysfs_gpio permission problems and follow-up timing problems
 // see https://github.com/rust-embedded/rust-sysfs-gpio/issues/5 and follow-up issues
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), Error<std::io::Error>> {
     // Configure SPI
     // SPI settings are from eink-waveshare-rs documenation
     let mut spi = Spidev::open("/dev/spidev0.0")?;
@@ -69,13 +69,13 @@ fn main() -> Result<(), std::io::Error> {
     let small_buffer = [Color::Black.get_byte_value(); 32]; //16x16
     let number_of_runs = 1;
     for i in 0..number_of_runs {
-        let offset = i * 8 % 150;
+        let offset = i * 8 % 144;
         epd.update_partial_frame(
             &mut spi,
             &mut delay,
             &small_buffer,
-            25 + offset,
-            25 + offset,
+            24 + offset,
+            24 + offset,
             16,
             16,
         )?;
@@ -88,10 +88,10 @@ fn main() -> Result<(), std::io::Error> {
 
     // Draw some squares
     let small_buffer = [Color::Black.get_byte_value(); 3200]; //160x160
-    epd.update_partial_frame(&mut spi, &mut delay, &small_buffer, 20, 20, 160, 160)?;
+    epd.update_partial_frame(&mut spi, &mut delay, &small_buffer, 24, 24, 160, 160)?;
 
     let small_buffer = [Color::White.get_byte_value(); 800]; //80x80
-    epd.update_partial_frame(&mut spi, &mut delay, &small_buffer, 60, 60, 80, 80)?;
+    epd.update_partial_frame(&mut spi, &mut delay, &small_buffer, 64, 64, 80, 80)?;
 
     let small_buffer = [Color::Black.get_byte_value(); 8]; //8x8
     epd.update_partial_frame(&mut spi, &mut delay, &small_buffer, 96, 96, 8, 8)?;
@@ -10,6 +10,7 @@ use embedded_hal::prelude::*;
 use epd_waveshare::{
     color::*,
     epd4in2::{Display4in2, Epd4in2},
+    error::Error,
     graphics::DisplayRotation,
     prelude::*,
 };
@@ -23,7 +24,7 @@ use linux_embedded_hal::{
 // needs to be run with sudo because of some sysfs_gpio permission problems and follow-up timing problems
 // see https://github.com/rust-embedded/rust-sysfs-gpio/issues/5 and follow-up issues
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), Error<std::io::Error>> {
     // Configure SPI
     // Settings are taken from
     let mut spi = Spidev::open("/dev/spidev0.0").expect("spidev directory");
@@ -79,7 +80,9 @@ fn main() -> Result<(), std::io::Error> {
     display.set_rotation(DisplayRotation::Rotate270);
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
-    epd4in2.update_frame(&mut spi, display.buffer(), &mut delay)?;
+    epd4in2
+        .update_frame(&mut spi, display.buffer(), &mut delay)
+        .expect("update frame");
     epd4in2
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
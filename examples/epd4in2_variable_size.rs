@@ -1,5 +1,4 @@
 #![deny(warnings)]
-#![deny(warnings)]
 
 use embedded_graphics::{
     mono_font::MonoTextStyleBuilder,
@@ -11,6 +10,7 @@ use embedded_hal::prelude::*;
 use epd_waveshare::{
     color::*,
     epd4in2::{self, Epd4in2},
+    error::Error,
     graphics::{DisplayRotation, VarDisplay},
     prelude::*,
 };
@@ -24,7 +24,7 @@ use linux_embedded_hal::{
 // needs to be run with sudo because of some sysfs_gpio permission problems and follow-up timing problems
 // see https://github.com/rust-embedded/rust-sysfs-gpio/issues/5 and follow-up issues
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> Result<(), Error<std::io::Error>> {
     // Configure SPI
     // Settings are taken from
     let mut spi = Spidev::open("/dev/spidev0.0").expect("spidev directory");
@@ -67,7 +67,7 @@ fn main() -> Result<(), std::io::Error> {
 
     println!("Test all the rotations");
 
-    let (x, y, width, height) = (50, 50, 250, 250);
+    let (x, y, width, height) = (48, 48, 248, 248);
 
     let mut buffer = [epd4in2::DEFAULT_BACKGROUND_COLOR.get_byte_value(); 62500]; //250*250
     let mut display = VarDisplay::new(width, height, &mut buffer, false).unwrap();
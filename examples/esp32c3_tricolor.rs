@@ -0,0 +1,97 @@
+#![deny(warnings)]
+#![cfg_attr(target_os = "none", no_std, no_main)]
+
+// Drives a 2.13" (B/C) three color display from an ESP32-C3:
+// cargo build --example esp32c3_tricolor --target riscv32imc-unknown-none-elf
+//
+// Draws black text and a red box with white text, sends both planes, refreshes the display
+// once and puts it to sleep.
+//
+// On other targets the example only prints how to build it.
+
+#[cfg(all(target_arch = "riscv32", target_os = "none"))]
+mod esp32c3 {
+    use core::panic::PanicInfo;
+    use embedded_graphics::{
+        mono_font::{ascii::FONT_10X20, MonoTextStyle, MonoTextStyleBuilder},
+        prelude::*,
+        primitives::{PrimitiveStyle, Rectangle},
+        text::{Baseline, Text},
+    };
+    use epd_waveshare::{
+        color::*,
+        epd2in13bc::{Display2in13bc, Epd2in13bc},
+        graphics::DisplayRotation,
+        prelude::*,
+    };
+    use esp32c3_hal::{
+        clock::ClockControl,
+        entry,
+        gpio::IO,
+        peripherals::Peripherals,
+        prelude::*,
+        spi::{master::Spi, SpiMode},
+        Delay,
+    };
+
+    #[entry]
+    fn main() -> ! {
+        let peripherals = Peripherals::take();
+        let system = peripherals.SYSTEM.split();
+        let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+        let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+        let busy = io.pins.gpio3.into_floating_input();
+        let dc = io.pins.gpio4.into_push_pull_output();
+        let rst = io.pins.gpio5.into_push_pull_output();
+        let cs = io.pins.gpio10.into_push_pull_output();
+        let mut spi = Spi::new(peripherals.SPI2, 4u32.MHz(), SpiMode::Mode0, &clocks)
+            .with_sck(io.pins.gpio6)
+            .with_mosi(io.pins.gpio7);
+        let mut delay = Delay::new(&clocks);
+
+        let mut epd = Epd2in13bc::new(&mut spi, cs, busy, dc, rst, &mut delay, None).unwrap();
+
+        let mut display = Display2in13bc::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        display.clear(TriColor::White).ok();
+
+        let style = MonoTextStyle::new(&FONT_10X20, TriColor::Black);
+        let _ = Text::with_baseline("ESP32-C3", Point::new(10, 10), style, Baseline::Top)
+            .draw(&mut display);
+
+        // Chromatic pixels end up in the second plane and are shown red or yellow
+        let _ = Rectangle::new(Point::new(10, 50), Size::new(180, 40))
+            .into_styled(PrimitiveStyle::with_fill(TriColor::Chromatic))
+            .draw(&mut display);
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_10X20)
+            .text_color(TriColor::White)
+            .background_color(TriColor::Chromatic)
+            .build();
+        let _ = Text::with_baseline("three colors", Point::new(20, 60), style, Baseline::Top)
+            .draw(&mut display);
+
+        epd.update_color_frame(
+            &mut spi,
+            &mut delay,
+            display.bw_buffer(),
+            display.chromatic_buffer(),
+        )
+        .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        loop {}
+    }
+
+    #[panic_handler]
+    fn panic(_: &PanicInfo) -> ! {
+        loop {}
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+fn main() {
+    println!("Build this example for the ESP32-C3 with --target riscv32imc-unknown-none-elf");
+}
@@ -0,0 +1,76 @@
+#![deny(warnings)]
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_10X20, MonoTextStyle},
+    prelude::*,
+    text::{Baseline, Text},
+};
+use embedded_hal::prelude::*;
+use epd_waveshare::{
+    color::*,
+    epd4in2::{Display4in2, Epd4in2},
+    graphics::VarDisplay,
+    linux::{Hat, HatError},
+    prelude::*,
+};
+
+// Uses the `linux` feature to open a 4.2" e-Paper HAT with the default Waveshare pinout:
+// cargo run --example hat_sleep_wake --features linux
+//
+// activate spi, gpio in raspi-config
+// needs to be run with sudo because of some sysfs_gpio permission problems and follow-up timing problems
+//
+// Shows a full refresh, counts up in a small window with partial updates, puts the display to
+// sleep in between and wakes it up again for the next round.
+
+// The window of the counter, x and the width need to be multiples of 8
+const X: u32 = 160;
+const Y: u32 = 136;
+const WIDTH: u32 = 80;
+const HEIGHT: u32 = 24;
+
+fn main() -> Result<(), HatError> {
+    let mut hat: Hat<Epd4in2<_, _, _, _, _, _>> = Hat::open_default()?;
+
+    // Full refresh with a frame
+    let mut display = Display4in2::default();
+    display.clear(Color::White).ok();
+    let style = MonoTextStyle::new(&FONT_10X20, Color::Black);
+    let _ = Text::with_baseline("Sleep and wake", Point::new(130, 100), style, Baseline::Top)
+        .draw(&mut display);
+    hat.epd
+        .update_and_display_frame(&mut hat.spi, display.buffer(), &mut hat.delay)?;
+
+    let mut buffer = [0u8; epd_waveshare::buffer_len(WIDTH as usize, HEIGHT as usize)];
+    for round in 0..3 {
+        // Partial refreshes of the counter window
+        for count in 0..5 {
+            let mut window = VarDisplay::new(WIDTH, HEIGHT, &mut buffer, false).unwrap();
+            window.clear(Color::White).ok();
+            let text = format!("{}.{}", round, count);
+            let _ = Text::with_baseline(&text, Point::new(4, 2), style, Baseline::Top)
+                .draw(&mut window);
+
+            hat.epd.update_partial_frame(
+                &mut hat.spi,
+                &mut hat.delay,
+                window.buffer(),
+                X,
+                Y,
+                WIDTH,
+                HEIGHT,
+            )?;
+            hat.epd.display_frame(&mut hat.spi, &mut hat.delay)?;
+        }
+
+        // The panel keeps the image while sleeping
+        println!("Round {} done - going to sleep for 10s", round);
+        hat.epd.sleep(&mut hat.spi, &mut hat.delay)?;
+        hat.delay.delay_ms(10_000u16);
+        hat.epd.wake_up(&mut hat.spi, &mut hat.delay)?;
+    }
+
+    println!("Finished - going to sleep");
+    hat.epd.sleep(&mut hat.spi, &mut hat.delay)?;
+    Ok(())
+}
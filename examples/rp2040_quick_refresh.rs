@@ -0,0 +1,130 @@
+#![deny(warnings)]
+#![cfg_attr(target_os = "none", no_std, no_main)]
+
+// Drives the Pico-ePaper-2.9 module, a 2.9" V2 display plugged onto a Raspberry Pi Pico:
+// cargo build --example rp2040_quick_refresh --target thumbv6m-none-eabi
+//
+// Shows a full refresh, counts up with quick refreshes of the changed pixels, puts the display
+// to sleep in between and wakes it up again for the next round.
+//
+// On other targets the example only prints how to build it.
+
+#[cfg(all(target_arch = "arm", target_os = "none"))]
+mod pico {
+    use cortex_m::delay::Delay;
+    use embedded_graphics::{
+        mono_font::{ascii::FONT_10X20, MonoTextStyle},
+        prelude::*,
+        text::{Baseline, Text},
+    };
+    use epd_waveshare::{
+        color::*,
+        epd2in9_v2::{Display2in9, Epd2in9},
+        graphics::DisplayRotation,
+        prelude::*,
+        SPI_MODE,
+    };
+    use panic_halt as _;
+    use rp2040_hal::{
+        clocks::init_clocks_and_plls,
+        entry,
+        fugit::RateExtU32,
+        gpio::{FunctionSpi, Pins},
+        pac, Clock, Sio, Spi, Watchdog,
+    };
+
+    #[link_section = ".boot2"]
+    #[used]
+    static BOOT2_FIRMWARE: [u8; 256] = rp2040_boot2::BOOT_LOADER_GENERIC_03H;
+
+    const XTAL_FREQ_HZ: u32 = 12_000_000;
+
+    const COUNTS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+
+    #[entry]
+    fn main() -> ! {
+        let mut pac = pac::Peripherals::take().unwrap();
+        let core = pac::CorePeripherals::take().unwrap();
+        let mut watchdog = Watchdog::new(pac.WATCHDOG);
+        let clocks = init_clocks_and_plls(
+            XTAL_FREQ_HZ,
+            pac.XOSC,
+            pac.CLOCKS,
+            pac.PLL_SYS,
+            pac.PLL_USB,
+            &mut pac.RESETS,
+            &mut watchdog,
+        )
+        .ok()
+        .unwrap();
+        let sio = Sio::new(pac.SIO);
+        let pins = Pins::new(
+            pac.IO_BANK0,
+            pac.PADS_BANK0,
+            sio.gpio_bank0,
+            &mut pac.RESETS,
+        );
+
+        // The pinout of the Pico-ePaper modules
+        let dc = pins.gpio8.into_push_pull_output();
+        let cs = pins.gpio9.into_push_pull_output();
+        let sclk = pins.gpio10.into_function::<FunctionSpi>();
+        let mosi = pins.gpio11.into_function::<FunctionSpi>();
+        let rst = pins.gpio12.into_push_pull_output();
+        let busy = pins.gpio13.into_floating_input();
+
+        let mut spi = Spi::<_, _, _, 8>::new(pac.SPI1, (mosi, sclk)).init(
+            &mut pac.RESETS,
+            clocks.peripheral_clock.freq(),
+            4.MHz(),
+            SPI_MODE,
+        );
+        let mut delay = Delay::new(core.SYST, clocks.system_clock.freq().to_Hz());
+
+        let mut epd = Epd2in9::new(&mut spi, cs, busy, dc, rst, &mut delay, None).unwrap();
+
+        // Full refresh
+        let mut display = Display2in9::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        let mut previous = COUNTS[COUNTS.len() - 1];
+        draw_count(&mut display, previous);
+        epd.update_frame(&mut spi, display.buffer(), &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+
+        loop {
+            for count in COUNTS {
+                // Only the pixels which differ between the old and the new frame are driven
+                draw_count(&mut display, previous);
+                epd.update_old_frame(&mut spi, display.buffer(), &mut delay)
+                    .unwrap();
+                draw_count(&mut display, count);
+                epd.update_and_display_new_frame(&mut spi, display.buffer(), &mut delay)
+                    .unwrap();
+                previous = count;
+
+                epd.sleep(&mut spi, &mut delay).unwrap();
+                delay.delay_ms(5_000);
+                epd.wake_up(&mut spi, &mut delay).unwrap();
+            }
+        }
+    }
+
+    fn draw_count(display: &mut Display2in9, count: &str) {
+        display.clear(Color::White).ok();
+        let style = MonoTextStyle::new(&FONT_10X20, Color::Black);
+        let _ = Text::with_baseline(
+            "Pico quick refresh",
+            Point::new(20, 20),
+            style,
+            Baseline::Top,
+        )
+        .draw(display);
+        let _ = Text::with_baseline(count, Point::new(140, 70), style, Baseline::Top).draw(display);
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+fn main() {
+    println!("Build this example for the RP2040 with --target thumbv6m-none-eabi");
+}
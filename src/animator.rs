@@ -0,0 +1,229 @@
+//! Showing a sequence of frames with quick refreshes and regular full refreshes
+//!
+//! Quick refreshes are fast but leave ghosting behind which adds up over the frames, so
+//! animations insert a full refresh every few frames. The [`Animator`] does this for any
+//! driver implementing [`QuickRefresh`], and postpones the full refresh while the minimum
+//! interval of the panel, see [`DisplayCapabilities`], hasn't elapsed yet.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use epd_waveshare::{animator::*, epd4in2::*, prelude::*, refresh_guard::Clock};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# struct Rtc;
+//!# impl Clock for Rtc { fn now_ms(&mut self) -> u64 { 0 } }
+//!# let rtc = Rtc;
+//!# let frames = [Display4in2::default(), Display4in2::default()];
+//!
+//!let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+//!let mut previous = [0u8; buffer_len(WIDTH as usize, HEIGHT as usize)];
+//!// A full refresh every 10 frames
+//!let mut animator = Animator::new(rtc, epd.capabilities(), &mut previous, 10);
+//!
+//!for frame in &frames {
+//!    animator.push_frame(&mut epd, &mut spi, frame.buffer(), &mut delay)?;
+//!}
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::error::Error;
+use crate::refresh_guard::{Clock, IntervalPolicy, RefreshGuard};
+use crate::traits::{DisplayCapabilities, EpdRefresh, EpdUpdate, QuickRefresh};
+
+/// Pushes successive frames to a display, see the [module documentation](self)
+pub struct Animator<'a, CLOCK> {
+    guard: RefreshGuard<CLOCK>,
+    quick_refresh: bool,
+    /// The frame shown, which the next quick refresh compares against
+    previous: &'a mut [u8],
+    full_refresh_interval: u16,
+    /// Frames shown since the last full refresh, `None` before the first one
+    frames: Option<u16>,
+}
+
+impl<'a, CLOCK: Clock> Animator<'a, CLOCK> {
+    /// Creates an animator for a display with the given capabilities
+    ///
+    /// `previous` keeps a copy of the frame shown and needs the size of a frame.
+    /// A full refresh follows after `full_refresh_interval` quick refreshes, at least 1,
+    /// or as soon as the minimum interval of the panel allows it.
+    pub fn new(
+        clock: CLOCK,
+        capabilities: DisplayCapabilities,
+        previous: &'a mut [u8],
+        full_refresh_interval: u16,
+    ) -> Self {
+        Animator {
+            guard: RefreshGuard::new(
+                clock,
+                capabilities.min_refresh_interval_ms.into(),
+                IntervalPolicy::Error,
+            ),
+            quick_refresh: capabilities.quick_refresh,
+            previous,
+            full_refresh_interval: full_refresh_interval.max(1),
+            frames: None,
+        }
+    }
+
+    /// Shows the next frame, with a quick or a full refresh
+    ///
+    /// The first frame is always shown with a full refresh. Panels without quick refreshes
+    /// only get full refreshes, which fail with [`Error::TooSoon`] while the minimum
+    /// interval hasn't elapsed, so the frame can be skipped or retried later.
+    pub fn push_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        frame: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+            + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+            + QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        crate::check_buffer_len(frame, self.previous.len())?;
+
+        if self.full_refresh_due() {
+            self.guard.check(delay)?;
+            epd.update_and_display_frame(spi, frame, delay)?;
+            self.guard.mark_refreshed();
+            self.frames = Some(0);
+        } else {
+            epd.update_old_frame(spi, self.previous, delay)?;
+            epd.update_and_display_new_frame(spi, frame, delay)?;
+            self.frames = self.frames.map(|frames| frames.saturating_add(1));
+        }
+        self.previous.copy_from_slice(frame);
+        Ok(())
+    }
+
+    /// Makes the next frame a full refresh, as soon as the minimum interval allows it
+    pub fn request_full_refresh(&mut self) {
+        if self.frames.is_some() {
+            self.frames = Some(self.full_refresh_interval);
+        }
+    }
+
+    /// Number of quick refreshes since the last full refresh
+    pub fn frames_since_full_refresh(&self) -> u16 {
+        self.frames.unwrap_or(0)
+    }
+
+    /// Returns the clock and the buffer of the previous frame
+    pub fn release(self) -> (CLOCK, &'a mut [u8]) {
+        (self.guard.release(), self.previous)
+    }
+
+    fn full_refresh_due(&mut self) -> bool {
+        match self.frames {
+            None => true,
+            Some(_) if !self.quick_refresh => true,
+            Some(frames) => frames >= self.full_refresh_interval && self.guard.remaining_ms() == 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    struct TestClock<'a>(&'a Cell<u64>);
+
+    impl Clock for TestClock<'_> {
+        fn now_ms(&mut self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    /// Simulates the frames pushed, returning whether they are full refreshes
+    fn refreshes(animator: &mut Animator<TestClock>, now: &Cell<u64>, count: usize) -> [bool; 8] {
+        let mut full = [false; 8];
+        for refresh in full.iter_mut().take(count) {
+            *refresh = animator.full_refresh_due();
+            if *refresh {
+                animator.guard.mark_refreshed();
+                animator.frames = Some(0);
+            } else {
+                animator.frames = animator.frames.map(|frames| frames + 1);
+            }
+            now.set(now.get() + 1_000);
+        }
+        full
+    }
+
+    #[test]
+    fn full_refresh_interval() {
+        let now = Cell::new(0);
+        let mut previous = [0u8; 4];
+        let mut animator = Animator::new(
+            TestClock(&now),
+            crate::epd2in9_v2::CAPABILITIES,
+            &mut previous,
+            3,
+        );
+        assert_eq!(
+            refreshes(&mut animator, &now, 8),
+            [true, false, false, false, true, false, false, false]
+        );
+        assert_eq!(animator.frames_since_full_refresh(), 3);
+        animator.request_full_refresh();
+        assert!(animator.full_refresh_due());
+    }
+
+    #[test]
+    fn postponed_full_refresh() {
+        let now = Cell::new(0);
+        let mut previous = [0u8; 4];
+        let capabilities = DisplayCapabilities {
+            min_refresh_interval_ms: 4_500,
+            ..crate::epd2in9_v2::CAPABILITIES
+        };
+        let mut animator = Animator::new(TestClock(&now), capabilities, &mut previous, 2);
+        // the full refresh waits for the minimum interval
+        assert_eq!(
+            refreshes(&mut animator, &now, 8),
+            [true, false, false, false, false, true, false, false]
+        );
+    }
+
+    #[test]
+    fn only_full_refreshes() {
+        let now = Cell::new(0);
+        let mut previous = [0u8; 4];
+        let mut animator = Animator::new(
+            TestClock(&now),
+            crate::epd2in13bc::CAPABILITIES,
+            &mut previous,
+            5,
+        );
+        assert_eq!(
+            refreshes(&mut animator, &now, 2),
+            [true, true, false, false, false, false, false, false]
+        );
+    }
+}
@@ -0,0 +1,355 @@
+//! Selecting the driver at runtime, e.g. for firmware supporting several panels
+//!
+//! [`AnyEpd`] wraps one of the black/white drivers and implements [`EpdInit`], [`EpdUpdate`],
+//! [`EpdRefresh`] and [`EpdPower`] by dispatching to it. As it is created with
+//! [`AnyEpd::with_panel`] instead of [`EpdNew`], it isn't a
+//! [`WaveshareDisplay`](crate::prelude::WaveshareDisplay), generic code taking it uses the
+//! individual traits like [`EpdArray`](crate::epd_array::EpdArray) and
+//! [`DynEpd`](crate::dyn_epd::DynEpd) do. Every driver
+//! is only included with its own feature, e.g. `any_epd2in13_v2` and `any_epd2in9_v2`,
+//! to keep the unused ones out of the binary.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use epd_waveshare::{any_epd::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# let panel_from_config = || Panel::Epd2in9V2;
+//!
+//!// E.g. read from the provisioning data
+//!let panel: Panel = panel_from_config();
+//!
+//!let mut epd = AnyEpd::with_panel(panel, &mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+//!
+//!// The buffer needs to match the size of the selected panel
+//!let buffer = [0xFF; epd_waveshare::buffer_len(128, 296)];
+//!epd.update_and_display_frame(&mut spi, &buffer, &mut delay)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::color::Color;
+use crate::error::Error;
+use crate::interface::Hooks;
+use crate::traits::{
+    sealed::HasHooks, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh, EpdUpdate,
+    LutSource, RefreshLut,
+};
+
+// `$d` is a literal `$`, to be able to define the `dispatch` macro inside of this one
+macro_rules! any_epd {
+    ($d:tt $($feature:literal, $variant:ident, $module:ident :: $epd:ident;)*) => {
+        /// The panels which can be selected at runtime
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Panel {
+            $(
+                #[cfg(feature = $feature)]
+                #[doc = concat!("See [`", stringify!($module), "`](crate::", stringify!($module), ")")]
+                $variant,
+            )*
+        }
+
+        /// One of the drivers, selected at runtime
+        pub enum AnyEpd<SPI, CS, BUSY, DC, RST, DELAY> {
+            $(
+                #[cfg(feature = $feature)]
+                #[doc = concat!("See [`", stringify!($module), "`](crate::", stringify!($module), ")")]
+                $variant(crate::$module::$epd<SPI, CS, BUSY, DC, RST, DELAY>),
+            )*
+        }
+
+        $(
+            #[cfg(feature = $feature)]
+            impl<SPI, CS, BUSY, DC, RST, DELAY> From<crate::$module::$epd<SPI, CS, BUSY, DC, RST, DELAY>>
+                for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+            {
+                fn from(epd: crate::$module::$epd<SPI, CS, BUSY, DC, RST, DELAY>) -> Self {
+                    AnyEpd::$variant(epd)
+                }
+            }
+        )*
+
+        impl<SPI, CS, BUSY, DC, RST, DELAY> AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+        where
+            SPI: Write<u8>,
+            CS: OutputPin,
+            BUSY: InputPin,
+            DC: OutputPin,
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+        {
+            /// Creates and initialises the driver of `panel`
+            #[allow(clippy::too_many_arguments)]
+            pub fn with_panel(
+                panel: Panel,
+                spi: &mut SPI,
+                cs: CS,
+                busy: BUSY,
+                dc: DC,
+                rst: RST,
+                delay: &mut DELAY,
+                delay_us: Option<u32>,
+            ) -> Result<Self, Error<SPI::Error>> {
+                let mut epd = Self::with_panel_uninitialized(panel, cs, busy, dc, rst, delay_us);
+                epd.init(spi, delay)?;
+                Ok(epd)
+            }
+
+            /// Creates the driver of `panel` without initialising it,
+            /// see [`EpdNew::new_uninitialized`]
+            pub fn with_panel_uninitialized(
+                panel: Panel,
+                cs: CS,
+                busy: BUSY,
+                dc: DC,
+                rst: RST,
+                delay_us: Option<u32>,
+            ) -> Self {
+                match panel {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Panel::$variant => AnyEpd::$variant(crate::$module::$epd::new_uninitialized(
+                            cs, busy, dc, rst, delay_us,
+                        )),
+                    )*
+                }
+            }
+
+            /// The selected panel
+            pub fn panel(&self) -> Panel {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        AnyEpd::$variant(_) => Panel::$variant,
+                    )*
+                }
+            }
+        }
+
+        macro_rules! dispatch {
+            ($d self_:expr, $d epd:ident => $d call:expr) => {
+                match $d self_ {
+                    $(
+                        #[cfg(feature = $feature)]
+                        AnyEpd::$variant($d epd) => $d call,
+                    )*
+                }
+            };
+        }
+    };
+}
+
+any_epd! {
+    $
+    "any_epd1in54", Epd1in54, epd1in54::Epd1in54;
+    "any_epd1in54_v2", Epd1in54V2, epd1in54_v2::Epd1in54;
+    "any_epd1in54b", Epd1in54b, epd1in54b::Epd1in54b;
+    "any_epd1in54c", Epd1in54c, epd1in54c::Epd1in54c;
+    "any_epd2in13_v2", Epd2in13V2, epd2in13_v2::Epd2in13;
+    "any_epd2in7b", Epd2in7b, epd2in7b::Epd2in7b;
+    "any_epd2in9", Epd2in9, epd2in9::Epd2in9;
+    "any_epd2in9_v2", Epd2in9V2, epd2in9_v2::Epd2in9;
+    "any_epd2in9bc", Epd2in9bc, epd2in9bc::Epd2in9bc;
+    "any_epd3in7", Epd3in7, epd3in7::EPD3in7;
+    "any_epd4in2", Epd4in2, epd4in2::Epd4in2;
+    "any_epd5in83b_v2", Epd5in83bV2, epd5in83b_v2::Epd5in83;
+    "any_epd7in5", Epd7in5, epd7in5::Epd7in5;
+    "any_epd7in5_hd", Epd7in5Hd, epd7in5_hd::Epd7in5;
+    "any_epd7in5_v2", Epd7in5V2, epd7in5_v2::Epd7in5;
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.init(spi, delay))
+    }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.probe(spi, delay))
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        dispatch!(self, epd => epd.free())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = Color;
+
+    fn set_background_color(&mut self, color: Color) {
+        dispatch!(self, epd => epd.set_background_color(color))
+    }
+
+    fn background_color(&self) -> &Color {
+        dispatch!(self, epd => epd.background_color())
+    }
+
+    fn width(&self) -> u32 {
+        dispatch!(self, epd => epd.width())
+    }
+
+    fn height(&self) -> u32 {
+        dispatch!(self, epd => epd.height())
+    }
+
+    fn capabilities(&self) -> DisplayCapabilities {
+        dispatch!(self, epd => epd.capabilities())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.update_frame(spi, buffer, delay))
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.update_partial_frame(spi, delay, buffer, x, y, width, height))
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.clear_frame(spi, delay))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        dispatch!(self, epd => epd.hooks())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.display_frame(spi, delay))
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.update_and_display_frame(spi, buffer, delay))
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.set_lut(spi, delay, refresh_rate))
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        dispatch!(self, epd => epd.refresh_mode())
+    }
+
+    fn lut_source(&self) -> LutSource {
+        dispatch!(self, epd => epd.lut_source())
+    }
+
+    fn is_busy(&self) -> bool {
+        dispatch!(self, epd => epd.is_busy())
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.wait_until_idle_with(spi, delay, on_poll))
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.wait_until_idle_timeout(spi, delay, timeout_ms))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.sleep(spi, delay))
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        dispatch!(self, epd => epd.wake_up(spi, delay))
+    }
+}
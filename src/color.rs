@@ -10,6 +10,7 @@ use embedded_graphics_core::pixelcolor::PixelColor;
 
 /// When trying to parse u8 to one of the color types
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct OutOfColorRangeParseError(u8);
 impl core::fmt::Display for OutOfColorRangeParseError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -17,6 +18,9 @@ impl core::fmt::Display for OutOfColorRangeParseError {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for OutOfColorRangeParseError {}
+
 impl OutOfColorRangeParseError {
     fn _new(size: u8) -> OutOfColorRangeParseError {
         OutOfColorRangeParseError(size)
@@ -26,6 +30,8 @@ impl OutOfColorRangeParseError {
 /// Only for the Black/White-Displays
 // TODO : 'color' is not a good name for black and white, rename it to BiColor/BWColor ?
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// Black color
     Black,
@@ -35,6 +41,8 @@ pub enum Color {
 
 /// Only for the Black/White/Color-Displays
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TriColor {
     /// Black color
     Black,
@@ -44,8 +52,113 @@ pub enum TriColor {
     Chromatic,
 }
 
+/// The chromatic color of a three color panel, shown for [`TriColor::Chromatic`]
+///
+/// Returned by
+/// [`WaveshareThreeColorDisplay::chromatic_kind`](crate::prelude::WaveshareThreeColorDisplay::chromatic_kind)
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriColorKind {
+    /// Black, white and red, e.g. the (B) panels
+    #[default]
+    Red,
+    /// Black, white and yellow, e.g. the (C) panels
+    Yellow,
+}
+
+impl TriColorKind {
+    /// Gets the RGB value of the chromatic color
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            TriColorKind::Red => (0xff, 0x00, 0x00),
+            TriColorKind::Yellow => (0xff, 0xff, 0x00),
+        }
+    }
+}
+
+/// Backgrounds of the two planes of the three color displays
+///
+/// Used by `clear_frame`, see
+/// [`WaveshareThreeColorDisplay::set_plane_backgrounds`](crate::prelude::WaveshareThreeColorDisplay::set_plane_backgrounds)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlaneBackgrounds {
+    /// Fill of the black/white plane
+    pub achromatic: Color,
+    /// Fill the chromatic plane with the chromatic color
+    pub chromatic: bool,
+}
+
+impl PlaneBackgrounds {
+    /// Byte filling the chromatic plane of a controller using `off` for no chromatic pixels
+    pub(crate) fn chromatic_byte(self, off: u8) -> u8 {
+        if self.chromatic {
+            !off
+        } else {
+            off
+        }
+    }
+}
+
+impl From<Color> for PlaneBackgrounds {
+    fn from(color: Color) -> PlaneBackgrounds {
+        PlaneBackgrounds {
+            achromatic: color,
+            chromatic: false,
+        }
+    }
+}
+
+impl From<TriColor> for PlaneBackgrounds {
+    fn from(color: TriColor) -> PlaneBackgrounds {
+        match color {
+            TriColor::Black => Color::Black.into(),
+            TriColor::White => Color::White.into(),
+            TriColor::Chromatic => PlaneBackgrounds {
+                achromatic: Color::White,
+                chromatic: true,
+            },
+        }
+    }
+}
+
+/// The black/white plane of a three color frame
+///
+/// Together with [`ChromaticPlane`] this makes swapping the planes passed to
+/// [`WaveshareThreeColorDisplay::update_color_frame`](crate::prelude::WaveshareThreeColorDisplay::update_color_frame)
+/// a compile error. Returned by the `bw_buffer` function of the three color displays, a raw
+/// buffer is wrapped with `BwPlane(buffer)`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BwPlane<'a>(pub &'a [u8]);
+
+/// The chromatic plane of a three color frame, see [`BwPlane`]
+///
+/// Returned by the `chromatic_buffer` function of the three color displays.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChromaticPlane<'a>(pub &'a [u8]);
+
+impl core::ops::Deref for BwPlane<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl core::ops::Deref for ChromaticPlane<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
 /// For the 5in65 7 Color Display
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OctColor {
     /// Black Color
     Black = 0x00,
@@ -65,7 +178,40 @@ pub enum OctColor {
     HiZ = 0x07,
 }
 
+/// For the displays supporting 4 gray levels
+///
+/// Stored with 2 bits per pixel, the value being the brightness of the pixel
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Gray2 {
+    /// Black color
+    Black = 0b00,
+    /// Dark gray color
+    DarkGray = 0b01,
+    /// Light gray color
+    LightGray = 0b10,
+    /// White color
+    White = 0b11,
+}
+
+/// How the bits of the pixels of a [`ColorType`] are laid out in a buffer
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Packing {
+    /// All bits of a pixel are next to each other in a single buffer, the first pixel of a
+    /// byte in its upper bits
+    Packed,
+    /// The bits of a pixel are split over `BUFFER_COUNT` buffers (planes) following each
+    /// other, `BITS_PER_PIXEL_PER_BUFFER` bits in every one of them
+    Planar,
+}
+
 /// Color trait for use in `Display`s
+///
+/// A color type declares how many bits a pixel takes, how they are packed into the buffer
+/// and its [`pixel_bits`](ColorType::pixel_bits). The masks placing them in a byte,
+/// [`bitmask`](ColorType::bitmask) and [`from_bitmask`](ColorType::from_bitmask), follow
+/// from that, so `Display` and `VarDisplay` work for any number of bits per pixel.
 pub trait ColorType: PixelColor {
     /// Number of bit used to represent this color type in a single buffer.
     /// To get the real number of bits per pixel you should multiply this by `BUFFER_COUNT`
@@ -75,51 +221,126 @@ pub trait ColorType: PixelColor {
     /// splitted buffer like tricolo is 2, otherwise this should be 1.
     const BUFFER_COUNT: usize;
 
+    /// Number of bits of a pixel over all buffers
+    const BITS_PER_PIXEL: usize = Self::BITS_PER_PIXEL_PER_BUFFER * Self::BUFFER_COUNT;
+
+    /// How the pixels are packed, [`Packing::Planar`] for split buffers
+    const PACKING: Packing = if Self::BUFFER_COUNT > 1 {
+        Packing::Planar
+    } else {
+        Packing::Packed
+    };
+
+    /// The `BITS_PER_PIXEL` bits of the color in the lowest bits
+    ///
+    /// With [`Packing::Planar`] the lowest `BITS_PER_PIXEL_PER_BUFFER` bits go to the first
+    /// buffer, the next ones to the second buffer and so on.
+    fn pixel_bits(&self) -> u8;
+
+    /// Converts the bits returned by [`pixel_bits`](ColorType::pixel_bits) back
+    ///
+    /// Returns `None` if the bits are no valid color of this type.
+    fn from_pixel_bits(bits: u8) -> Option<Self>;
+
     /// Return the data used to set a pixel color
     ///
     /// * bwrbit is used to tell the value of the unused bit when a chromatic
-    /// color is set (TriColor only as for now)
+    ///   color is set (TriColor only as for now)
     /// * pos is the pixel position in the line, used to know which pixels must be set
     ///
     /// Return values are :
     /// * .0 is the mask used to exclude this pixel from the byte (eg: 0x7F in BiColor)
     /// * .1 are the bits used to set the color in the byte (eg: 0x80 in BiColor)
-    ///      this is u16 because we set 2 bytes in case of split buffer
-    fn bitmask(&self, bwrbit: bool, pos: u32) -> (u8, u16);
+    ///   this is u16 because we set 2 bytes in case of split buffer
+    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
+        let (mask, shift) = pixel_mask::<Self>(pos);
+        let value = self.pixel_bits() as u16;
+        let bits = match Self::PACKING {
+            Packing::Packed => (value & mask) << shift,
+            Packing::Planar => (0..Self::BUFFER_COUNT).fold(0, |bits, plane| {
+                let plane_bits = (value >> (plane * Self::BITS_PER_PIXEL_PER_BUFFER)) & mask;
+                bits | plane_bits << (shift + 8 * plane as u32)
+            }),
+        };
+        (!((mask << shift) as u8), bits)
+    }
+
+    /// Reads the color set with [`bitmask`](ColorType::bitmask) back
+    ///
+    /// * pos is the pixel position in the line
+    /// * bits is the byte containing the pixel, the high byte is the one of the second buffer
+    ///   in case of split buffer
+    ///
+    /// Returns `None` if the bits are no valid color of this type.
+    fn from_bitmask(pos: u32, bits: u16) -> Option<Self> {
+        let (mask, shift) = pixel_mask::<Self>(pos);
+        let value = match Self::PACKING {
+            Packing::Packed => (bits >> shift) & mask,
+            Packing::Planar => (0..Self::BUFFER_COUNT).fold(0, |value, plane| {
+                let plane_bits = (bits >> (shift + 8 * plane as u32)) & mask;
+                value | plane_bits << (plane * Self::BITS_PER_PIXEL_PER_BUFFER)
+            }),
+        };
+        Self::from_pixel_bits(value as u8)
+    }
+}
+
+/// Mask of the bits of a pixel in a single buffer and their shift for the pixel `pos`
+fn pixel_mask<COLOR: ColorType>(pos: u32) -> (u16, u32) {
+    let bits = COLOR::BITS_PER_PIXEL_PER_BUFFER as u32;
+    let pixels_per_byte = 8 / bits;
+    ((1 << bits) - 1, 8 - bits - (pos % pixels_per_byte) * bits)
 }
 
 impl ColorType for Color {
     const BITS_PER_PIXEL_PER_BUFFER: usize = 1;
     const BUFFER_COUNT: usize = 1;
-    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
-        let bit = 0x80 >> (pos % 8);
-        match self {
-            Color::Black => (!bit, 0u16),
-            Color::White => (!bit, bit as u16),
-        }
+
+    fn pixel_bits(&self) -> u8 {
+        self.get_bit_value()
+    }
+
+    fn from_pixel_bits(bits: u8) -> Option<Self> {
+        Some(Color::from_bit(bits))
     }
 }
 
 impl ColorType for TriColor {
     const BITS_PER_PIXEL_PER_BUFFER: usize = 1;
     const BUFFER_COUNT: usize = 2;
-    fn bitmask(&self, bwrbit: bool, pos: u32) -> (u8, u16) {
-        let bit = 0x80 >> (pos % 8);
-        match self {
-            TriColor::Black => (!bit, u16::from_le_bytes([0x00, bit])),
-            TriColor::White => (!bit, u16::from_le_bytes([bit, bit])),
-            TriColor::Chromatic => (!bit, u16::from_le_bytes([bit, 0x00])),
-        }
+
+    fn pixel_bits(&self) -> u8 {
+        self.get_bw_bit() | self.get_chromatic_bit() << 1
+    }
+
+    fn from_pixel_bits(bits: u8) -> Option<Self> {
+        Some(TriColor::from_planes(bits & 1, (bits >> 1) & 1))
     }
 }
 
 impl ColorType for OctColor {
     const BITS_PER_PIXEL_PER_BUFFER: usize = 4;
     const BUFFER_COUNT: usize = 1;
-    fn bitmask(&self, _bwrbit: bool, pos: u32) -> (u8, u16) {
-        let mask = !(0xF0 >> (pos % 2));
-        let bits = self.get_nibble() as u16;
-        (mask, if pos % 2 == 1 { bits } else { bits << 4 })
+
+    fn pixel_bits(&self) -> u8 {
+        self.get_nibble()
+    }
+
+    fn from_pixel_bits(bits: u8) -> Option<Self> {
+        OctColor::from_nibble(bits).ok()
+    }
+}
+
+impl ColorType for Gray2 {
+    const BITS_PER_PIXEL_PER_BUFFER: usize = 2;
+    const BUFFER_COUNT: usize = 1;
+
+    fn pixel_bits(&self) -> u8 {
+        self.get_bits()
+    }
+
+    fn from_pixel_bits(bits: u8) -> Option<Self> {
+        Some(Gray2::from_bits(bits))
     }
 }
 
@@ -233,6 +454,61 @@ impl OctColor {
         }
     }
 }
+impl Gray2 {
+    /// Gets the 2 bits representation of the Color as needed by the display
+    pub fn get_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Takes the lower 2 bits and converts them to a Gray2
+    pub fn from_bits(bits: u8) -> Gray2 {
+        match bits & 0b11 {
+            0b00 => Gray2::Black,
+            0b01 => Gray2::DarkGray,
+            0b10 => Gray2::LightGray,
+            _ => Gray2::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for Gray2 {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU2;
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU2> for Gray2 {
+    fn from(b: embedded_graphics_core::pixelcolor::raw::RawU2) -> Self {
+        use embedded_graphics_core::prelude::RawData;
+        Gray2::from_bits(b.into_inner())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for Gray2 {
+    fn from(b: BinaryColor) -> Gray2 {
+        match b {
+            BinaryColor::On => Gray2::Black,
+            BinaryColor::Off => Gray2::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray2> for Gray2 {
+    fn from(g: embedded_graphics_core::pixelcolor::Gray2) -> Gray2 {
+        use embedded_graphics_core::pixelcolor::GrayColor;
+        Gray2::from_bits(g.luma())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Gray2> for embedded_graphics_core::pixelcolor::Gray2 {
+    fn from(g: Gray2) -> Self {
+        Self::new(g.get_bits())
+    }
+}
+
 //TODO: Rename get_bit_value to bit() and get_byte_value to byte() ?
 
 impl Color {
@@ -252,6 +528,17 @@ impl Color {
         }
     }
 
+    /// Color of a pixel in a buffer from its bit, only the lowest bit is used
+    ///
+    /// The inverse of [`get_bit_value`](Color::get_bit_value), a set bit is white.
+    pub fn from_bit(bit: u8) -> Color {
+        if bit & 1 == 1 {
+            Color::White
+        } else {
+            Color::Black
+        }
+    }
+
     /// Parses from u8 to Color
     fn from_u8(val: u8) -> Self {
         match val {
@@ -327,6 +614,37 @@ impl From<Color> for embedded_graphics_core::pixelcolor::Rgb888 {
 }
 
 impl TriColor {
+    /// [`TriColor::Chromatic`] of a panel showing red
+    pub const RED: TriColor = TriColor::Chromatic;
+    /// [`TriColor::Chromatic`] of a panel showing yellow
+    pub const YELLOW: TriColor = TriColor::Chromatic;
+
+    /// Gets the RGB value of the color on a panel with the chromatic color `kind`
+    pub fn rgb(self, kind: TriColorKind) -> (u8, u8, u8) {
+        match self {
+            TriColor::Black => (0x00, 0x00, 0x00),
+            TriColor::White => (0xff, 0xff, 0xff),
+            TriColor::Chromatic => kind.rgb(),
+        }
+    }
+
+    /// Picks the color nearest to the RGB value on a panel with the chromatic color `kind`
+    ///
+    /// Unlike the `From<Rgb888>` conversion, which maps everything that is neither black
+    /// nor white to `Chromatic`, e.g. red becomes black on a yellow panel.
+    pub fn from_rgb(rgb: (u8, u8, u8), kind: TriColorKind) -> TriColor {
+        let distance = |color: TriColor| {
+            let (r, g, b) = color.rgb(kind);
+            (i32::from(r) - i32::from(rgb.0)).pow(2)
+                + (i32::from(g) - i32::from(rgb.1)).pow(2)
+                + (i32::from(b) - i32::from(rgb.2)).pow(2)
+        };
+        [TriColor::Black, TriColor::White, TriColor::Chromatic]
+            .into_iter()
+            .min_by_key(|color| distance(*color))
+            .unwrap_or(TriColor::White)
+    }
+
     /// Get the color encoding of the color for one bit
     pub fn get_bit_value(self) -> u8 {
         match self {
@@ -342,6 +660,41 @@ impl TriColor {
             TriColor::Black | TriColor::Chromatic => 0x00,
         }
     }
+
+    /// Bit of the color in the black/white plane of a buffer
+    ///
+    /// A set bit is white. Chromatic pixels are white in this plane, so the chromatic color
+    /// is shown on a white background if a controller ignores the chromatic plane.
+    pub fn get_bw_bit(self) -> u8 {
+        match self {
+            TriColor::White | TriColor::Chromatic => 1,
+            TriColor::Black => 0,
+        }
+    }
+
+    /// Bit of the color in the chromatic plane of a buffer
+    ///
+    /// A cleared bit is chromatic.
+    pub fn get_chromatic_bit(self) -> u8 {
+        match self {
+            TriColor::Chromatic => 0,
+            TriColor::White | TriColor::Black => 1,
+        }
+    }
+
+    /// Color of a pixel from its bits in the two planes of a buffer, only the lowest bits
+    /// are used
+    ///
+    /// The inverse of [`get_bw_bit`](TriColor::get_bw_bit) and
+    /// [`get_chromatic_bit`](TriColor::get_chromatic_bit). The chromatic bit takes precedence
+    /// over the black/white one.
+    pub fn from_planes(bw_bit: u8, chromatic_bit: u8) -> TriColor {
+        match (bw_bit & 1 == 1, chromatic_bit & 1 == 1) {
+            (_, false) => TriColor::Chromatic,
+            (false, true) => TriColor::Black,
+            (true, true) => TriColor::White,
+        }
+    }
 }
 
 #[cfg(feature = "graphics")]
@@ -367,7 +720,8 @@ impl From<embedded_graphics_core::pixelcolor::Rgb888> for TriColor {
         } else if rgb == RgbColor::WHITE {
             TriColor::White
         } else {
-            // there is no good approximation here since we don't know which color is 'chromatic'
+            // there is no good approximation here since we don't know which color is 'chromatic',
+            // see `TriColor::from_rgb`
             TriColor::Chromatic
         }
     }
@@ -379,7 +733,7 @@ impl From<TriColor> for embedded_graphics_core::pixelcolor::Rgb888 {
         match tri_color {
             TriColor::Black => embedded_graphics_core::pixelcolor::Rgb888::BLACK,
             TriColor::White => embedded_graphics_core::pixelcolor::Rgb888::WHITE,
-            // assume chromatic is red
+            // assume chromatic is red, see `TriColor::rgb` for yellow panels
             TriColor::Chromatic => embedded_graphics_core::pixelcolor::Rgb888::new(255, 0, 0),
         }
     }
@@ -398,7 +752,7 @@ mod tests {
     // test all values aside from 0 and 1 which all should panic
     #[test]
     fn from_u8_panic() {
-        for val in 2..=u8::max_value() {
+        for val in 2..=u8::MAX {
             extern crate std;
             let result = std::panic::catch_unwind(|| Color::from(val));
             assert!(result.is_err());
@@ -417,6 +771,55 @@ mod tests {
         assert_eq!(Color::from(1u8).get_bit_value(), 1u8);
     }
 
+    #[test]
+    fn bit_helpers() {
+        for color in [Color::Black, Color::White] {
+            assert_eq!(Color::from_bit(color.get_bit_value()), color);
+        }
+        assert_eq!(Color::from_bit(0xFE), Color::Black);
+
+        for color in [TriColor::Black, TriColor::White, TriColor::Chromatic] {
+            assert_eq!(
+                TriColor::from_planes(color.get_bw_bit(), color.get_chromatic_bit()),
+                color
+            );
+            // the same bits as in the planes of a `Display`
+            let bits = color.bitmask(false, 0).1.to_le_bytes();
+            assert_eq!(
+                bits,
+                [color.get_bw_bit() << 7, color.get_chromatic_bit() << 7]
+            );
+        }
+        assert_eq!(TriColor::from_planes(0, 0), TriColor::Chromatic);
+    }
+
+    #[test]
+    fn tri_color_kind() {
+        assert_eq!(TriColor::RED, TriColor::Chromatic);
+        assert_eq!(TriColor::YELLOW, TriColor::Chromatic);
+        assert_eq!(
+            TriColor::Chromatic.rgb(TriColorKind::Yellow),
+            (0xff, 0xff, 0x00)
+        );
+
+        let orange = (0xff, 0x80, 0x00);
+        assert_eq!(TriColor::from_rgb(orange, TriColorKind::Red), TriColor::RED);
+        assert_eq!(
+            TriColor::from_rgb(orange, TriColorKind::Yellow),
+            TriColor::YELLOW
+        );
+        let red = (0xc0, 0x00, 0x00);
+        assert_eq!(
+            TriColor::from_rgb(red, TriColorKind::Yellow),
+            TriColor::Black
+        );
+        let light_yellow = (0xff, 0xff, 0xc0);
+        assert_eq!(
+            TriColor::from_rgb(light_yellow, TriColorKind::Red),
+            TriColor::White
+        );
+    }
+
     #[test]
     fn test_oct() {
         let left = OctColor::Red;
@@ -426,4 +829,57 @@ mod tests {
             Ok((left, right))
         );
     }
+
+    #[test]
+    fn plane_backgrounds() {
+        let red = PlaneBackgrounds::from(TriColor::Chromatic);
+        assert_eq!(red.achromatic, Color::White);
+        assert_eq!(red.chromatic_byte(0xFF), 0x00);
+
+        let black = PlaneBackgrounds::from(TriColor::Black);
+        assert_eq!(black.achromatic, Color::Black);
+        assert_eq!(black.chromatic_byte(0xFF), 0xFF);
+    }
+
+    #[test]
+    fn gray2_bitmask() {
+        assert_eq!(Gray2::White.bitmask(false, 0), (0b0011_1111, 0b1100_0000));
+        assert_eq!(
+            Gray2::LightGray.bitmask(false, 1),
+            (0b1100_1111, 0b0010_0000)
+        );
+        assert_eq!(
+            Gray2::DarkGray.bitmask(false, 6),
+            (0b1111_0011, 0b0000_0100)
+        );
+        assert_eq!(Gray2::Black.bitmask(false, 3), (0b1111_1100, 0b0000_0000));
+    }
+
+    #[test]
+    fn packings() {
+        assert_eq!(Color::PACKING, Packing::Packed);
+        assert_eq!(TriColor::PACKING, Packing::Planar);
+        assert_eq!(TriColor::BITS_PER_PIXEL, 2);
+        assert_eq!(OctColor::BITS_PER_PIXEL, 4);
+
+        assert_eq!(Color::White.bitmask(false, 10), (0b1101_1111, 0b0010_0000));
+        assert_eq!(TriColor::Chromatic.bitmask(false, 7), (0b1111_1110, 0x0001));
+        assert_eq!(OctColor::Red.bitmask(false, 0), (0x0F, 0x40));
+        assert_eq!(OctColor::Red.bitmask(false, 1), (0xF0, 0x04));
+        assert_eq!(OctColor::from_bitmask(1, 0x47), Some(OctColor::HiZ));
+        assert_eq!(OctColor::from_bitmask(0, 0x80), None);
+        assert_eq!(TriColor::from_bitmask(2, 0xDF20), Some(TriColor::Chromatic));
+    }
+
+    #[test]
+    fn gray2_bits() {
+        for color in [
+            Gray2::Black,
+            Gray2::DarkGray,
+            Gray2::LightGray,
+            Gray2::White,
+        ] {
+            assert_eq!(Gray2::from_bits(color.get_bits()), color);
+        }
+    }
 }
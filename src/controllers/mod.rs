@@ -0,0 +1,18 @@
+//! Controllers supported by the generic driver [`Epd`](crate::generic::Epd)
+
+mod type_a;
+pub mod uc8179;
+
+pub use self::type_a::{TypeA, TypeAInit};
+pub use self::uc8179::{Uc8179, Uc8179Color, Uc8179Init};
+
+/// A display controller, implemented by the marker types of this module
+///
+/// The generic driver implements [`WaveshareDisplay`](crate::prelude::WaveshareDisplay)
+/// for every controller.
+pub trait Controller {
+    /// Values of the initialisation which depend on the panel
+    type InitConfig;
+    /// What the driver keeps for this controller besides the common state
+    type State;
+}
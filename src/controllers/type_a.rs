@@ -0,0 +1,398 @@
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use super::Controller;
+use crate::color::Color;
+use crate::error::Error;
+use crate::generic::{Epd, PanelConfig};
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::traits::{
+    sealed::HasHooks, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh, EpdUpdate,
+    LutSource, RefreshLut,
+};
+use crate::type_a::command::Command;
+
+/// The controller of the 1.54" and 2.9" panels, see [`epd1in54`](crate::epd1in54)
+/// and [`epd2in9`](crate::epd2in9)
+pub struct TypeA;
+
+impl Controller for TypeA {
+    type InitConfig = TypeAInit;
+    type State = ();
+}
+
+/// Values of the [`TypeA`] initialisation which depend on the panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeAInit {
+    /// Data of DriverOutputControl (0x01): number of gates and scanning order
+    pub driver_output_control: [u8; 3],
+    /// Data of BoosterSoftStartControl (0x0C)
+    pub booster_soft_start: [u8; 3],
+    /// VCOM register (0x2C)
+    pub vcom: u8,
+    /// Dummy line period (0x3A)
+    pub dummy_line_period: u8,
+    /// Gate line width (0x3B)
+    pub gate_line_width: u8,
+}
+
+impl TypeAInit {
+    /// The values used by the 1.54" and 2.9" panels, [`driver_output_control`](Self::driver_output_control)
+    /// needs to be set to the number of gates of the panel
+    pub const DEFAULT: TypeAInit = TypeAInit {
+        driver_output_control: [0x27, 0x01, 0x00],
+        booster_soft_start: [0xD7, 0xD6, 0x9D],
+        vcom: 0xA8,
+        dummy_line_period: 0x1A,
+        gate_line_width: 0x08,
+    };
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        start_x: u32,
+        start_y: u32,
+        end_x: u32,
+        end_y: u32,
+    ) -> Result<(), SPI::Error> {
+        debug_assert!(start_x < end_x);
+        debug_assert!(start_y < end_y);
+
+        // x is positioned in bytes
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[(start_x >> 3) as u8, (end_x >> 3) as u8],
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[
+                start_y as u8,
+                (start_y >> 8) as u8,
+                end_y as u8,
+                (end_y >> 8) as u8,
+            ],
+        )
+    }
+
+    fn set_ram_counter(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        x: u32,
+        y: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )?;
+        Ok(())
+    }
+
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_ram_area(spi, 0, 0, P::WIDTH - 1, P::HEIGHT - 1)?;
+        self.set_ram_counter(spi, delay, 0, 0)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        Epd {
+            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
+            background_color: P::DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            state: (),
+            config: PhantomData,
+        }
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.wait_until_idle(spi, delay)?;
+
+        let init = P::INIT;
+        self.interface.cmd_with_data(
+            spi,
+            Command::DriverOutputControl,
+            &init.driver_output_control,
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::BoosterSoftStartControl,
+            &init.booster_soft_start,
+        )?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteVcomRegister, &[init.vcom])?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetDummyLinePeriod,
+            &[init.dummy_line_period],
+        )?;
+        self.interface
+            .cmd_with_data(spi, Command::SetGateLineWidth, &[init.gate_line_width])?;
+
+        // x increment, y increment, address counter is updated in x direction
+        self.interface
+            .cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
+
+        self.set_lut(spi, delay, None)
+    }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, P::IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = Color;
+
+    fn set_background_color(&mut self, color: Color) {
+        self.background_color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        P::WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        P::HEIGHT
+    }
+
+    fn capabilities(&self) -> DisplayCapabilities {
+        P::CAPABILITIES
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+        Ok(())
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
+        self.wait_until_idle(spi, delay)?;
+        self.set_ram_area(spi, x, y, x + width, y + height)?;
+        self.set_ram_counter(spi, delay, x, y)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+        Ok(())
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+
+        let color = self.background_color.get_byte_value();
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface
+            .data_x_times(spi, color, P::WIDTH / 8 * P::HEIGHT)?;
+        Ok(())
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        // enable clock signal, enable cp, display pattern
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC4])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        // MASTER Activation should not be interupted to avoid currption of panel images
+        self.interface.cmd(spi, Command::Nop)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        let lut = match self.refresh {
+            RefreshLut::Full => P::LUT_FULL,
+            RefreshLut::Quick => P::LUT_QUICK,
+        };
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegister, lut)?;
+        Ok(())
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(P::IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .poll_until_idle(delay, P::IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, P::IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<TypeA, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<TypeA, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x00])?;
+        Ok(())
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+}
@@ -0,0 +1,1247 @@
+//! The UltraChip UC8179, the controller of the large panels
+//!
+//! It drives the 7.5" V2 and the 7.5" B V2, whose drivers [`epd7in5_v2`](crate::epd7in5_v2)
+//! and [`epd7in5b_v2`](crate::epd7in5b_v2) are the generic driver with the panel
+//! configurations [`Panel7in5V2`](crate::generic::Panel7in5V2) and
+//! [`Panel7in5bV2`](crate::generic::Panel7in5bV2), as well as most of the upcoming panels of up
+//! to 800x600 pixels. The waveforms come from the OTP of the controller, so a panel is
+//! configured with a [`Uc8179Init`] and the LUTs of its [`PanelConfig`] are left empty.
+//!
+//! Black/white panels use [`Color`], the controller runs in its black/white mode (KW) then.
+//! Black/white/red panels use [`TriColor`] and the black/white/red mode (KWR), their frames
+//! are the black/white plane followed by the chromatic plane.
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
+    digital::v2::*,
+};
+
+use super::Controller;
+use crate::color::{Color, ColorType, PlaneBackgrounds, TriColor};
+use crate::error::Error;
+use crate::generic::{Epd, PanelConfig};
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::traits::{
+    self, sealed::HasHooks, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh, EpdUpdate,
+    LutSource, PanelHealth, QuickRefresh, RefreshLut,
+};
+use crate::transport::Transport;
+
+/// The commands of the UC8179 used by the generic driver, with the abbreviations of the
+/// datasheet
+#[derive(Copy, Clone)]
+pub(crate) enum Command {
+    /// PSR: resolution, LUT selection, black/white or black/white/red mode, scan directions
+    PanelSetting = 0x00,
+    /// PWR: internal or external power and the driving voltages
+    PowerSetting = 0x01,
+    /// POF: turns the charge pump, the drivers and the temperature sensor off
+    PowerOff = 0x02,
+    /// PON: turns the charge pump and the drivers on, BUSY is low until it's done
+    PowerOn = 0x04,
+    /// BTST: the soft start of the boosters
+    BoosterSoftStart = 0x06,
+    /// DSLP: enters the deep sleep, which only a hardware reset ends, with the check code 0xA5
+    DeepSleep = 0x07,
+    /// DTM1: black/white data or the old data for quick refreshes
+    DataStartTransmission1 = 0x10,
+    /// DRF: refreshes the display with the data of the RAM
+    DisplayRefresh = 0x12,
+    /// DTM2: red data or the new data for quick refreshes
+    DataStartTransmission2 = 0x13,
+    /// DUSPI: dual SPI mode, used by transports sending the frames over two data lines
+    DualSpi = 0x15,
+    /// PLL: frame rate
+    PllControl = 0x30,
+    /// TSC: selects the internal or an external temperature sensor and the offset added to
+    /// the measured temperature
+    TemperatureCalibration = 0x41,
+    /// PBC: checks the panel for breaks, the result is read with the same command
+    PanelBreakCheck = 0x44,
+    /// CDI: border output and data polarity, and the interval between VCOM and data
+    VcomAndDataIntervalSetting = 0x50,
+    /// LVD: reads whether the supply voltage is too low
+    LowPowerDetection = 0x51,
+    /// TCON: non-overlap period of gate and source
+    TconSetting = 0x60,
+    /// TRES: horizontal and vertical resolution
+    TconResolution = 0x61,
+    /// SPI flash control: whether the controller accesses an external flash
+    SpiFlashControl = 0x65,
+    /// FLG: reads the status, used to poll BUSY
+    GetStatus = 0x71,
+    /// PTL: the partial window
+    PartialWindow = 0x90,
+    /// PTIN: enters the partial mode
+    PartialIn = 0x91,
+    /// PTOUT: leaves the partial mode
+    PartialOut = 0x92,
+    /// CCSET: TSFIX makes the controller use the temperature of `ForceTemperature`
+    CascadeSetting = 0xE0,
+    /// TSSET: temperature used for the waveform selection while TSFIX is set
+    ForceTemperature = 0xE5,
+}
+
+impl traits::Command for Command {
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+/// The UC8179 controller, see the [module documentation](self)
+pub struct Uc8179;
+
+impl Controller for Uc8179 {
+    type InitConfig = Uc8179Init;
+    type State = Uc8179State;
+}
+
+/// What the driver keeps for the [`Uc8179`], set with the methods of the driver
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Uc8179State {
+    /// Offset in °C added to the measured temperature
+    temperature_offset: i8,
+    /// Window of the refreshes as x, y, width and height, `None` for the whole panel
+    active_window: Option<(u32, u32, u32, u32)>,
+}
+
+/// Values of the [`Uc8179`] initialisation which depend on the panel
+///
+/// The resolution (TRES) is taken from the [`PanelConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Uc8179Init {
+    /// Time in µs before the reset pulse
+    pub reset_delay_us: u32,
+    /// Data of PowerSetting (PWR, 0x01)
+    pub power_setting: [u8; 4],
+    /// Data of PanelSetting (PSR, 0x00)
+    pub panel_setting: u8,
+    /// Data of PllControl (PLL, 0x30), `None` keeps the default of the controller
+    pub pll: Option<u8>,
+    /// Data of VcomAndDataIntervalSetting (CDI, 0x50) for full refreshes
+    pub vcom_and_data_interval: [u8; 2],
+    /// Data of TconSetting (TCON, 0x60)
+    pub tcon: u8,
+    /// Data of BoosterSoftStart (BTST, 0x06) for full refreshes, `None` keeps the default
+    /// of the controller
+    pub booster_soft_start: Option<[u8; 4]>,
+    /// Data of BoosterSoftStart for quick refreshes
+    pub quick_booster_soft_start: [u8; 4],
+    /// Temperature forced for quick refreshes, whose waveform is faster than the one of the
+    /// measured temperature
+    ///
+    /// `None` if the panel has no fast waveform, quick refreshes are full refreshes then.
+    pub quick_temperature: Option<u8>,
+    /// Temperature forced for the partial refreshes of
+    /// [`display_new_frame`](QuickRefresh::display_new_frame), which only redraw the changed
+    /// pixels
+    ///
+    /// `None` if the panel has no partial waveform.
+    pub partial_temperature: Option<u8>,
+    /// Disables the access of the controller to an external flash with SpiFlashControl (0x65)
+    pub disable_spi_flash: bool,
+}
+
+impl Uc8179Init {
+    /// The values used by the 7.5" V2 panel
+    pub const DEFAULT: Uc8179Init = Uc8179Init {
+        reset_delay_us: 10_000,
+        power_setting: [0x07, 0x17, 0x3F, 0x3F],
+        panel_setting: 0x1F,
+        pll: Some(0x06),
+        vcom_and_data_interval: [0x10, 0x07],
+        tcon: 0x22,
+        booster_soft_start: Some([0x17, 0x17, 0x27, 0x17]),
+        quick_booster_soft_start: [0x27, 0x27, 0x18, 0x17],
+        quick_temperature: Some(0x5A),
+        partial_temperature: Some(0x6E),
+        disable_spi_flash: false,
+    };
+}
+
+/// Colors of the panels of the [`Uc8179`]
+pub trait Uc8179Color: ColorType + Copy {
+    /// Bytes filling the RAM of DataStartTransmission1 and DataStartTransmission2 to clear
+    /// the panel to this color
+    fn clear_bytes(self) -> [u8; 2];
+}
+
+/// In the black/white mode the panels show a cleared bit as white
+impl Uc8179Color for Color {
+    fn clear_bytes(self) -> [u8; 2] {
+        let byte = !self.get_byte_value();
+        [byte, byte]
+    }
+}
+
+/// In the black/white/red mode a cleared bit of the red RAM isn't red
+impl Uc8179Color for TriColor {
+    fn clear_bytes(self) -> [u8; 2] {
+        let planes = PlaneBackgrounds::from(self);
+        [
+            planes.achromatic.get_byte_value(),
+            planes.chromatic_byte(0x00),
+        ]
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+    Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    pub(crate) fn from_interface(
+        interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>,
+    ) -> Self {
+        Epd {
+            interface,
+            background_color: P::DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            state: Uc8179State::default(),
+            config: PhantomData,
+        }
+    }
+
+    /// Creates and initialises a driver sending the frames with `transport`, see
+    /// [`transport`](crate::transport)
+    ///
+    /// The controller takes the frames over one or two data lines, with two it's switched to
+    /// its dual spi mode. Returns [`Error::Unsupported`] for transports using more lines.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_transport(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+        delay_us: Option<u32>,
+        transport: TRANSPORT,
+    ) -> Result<Self, Error<SPI::Error>> {
+        if !matches!(transport.data_lines(), 1 | 2) {
+            return Err(Error::Unsupported);
+        }
+        let interface = DisplayInterface::with_transport(cs, busy, dc, rst, delay_us, transport);
+        let mut epd = Self::from_interface(interface);
+        epd.init(spi, delay)?;
+        Ok(epd)
+    }
+
+    /// Sets the offset in °C which is added to the temperature measured by the internal
+    /// sensor before it is used to select the waveform of the following refreshes.
+    ///
+    /// The controller supports offsets from -4°C to +3°C, others are rejected with
+    /// [`Error::OutOfRange`].
+    pub fn set_temperature_offset(&mut self, offset: i8) -> Result<(), Error<SPI::Error>> {
+        if !(-4..=3).contains(&offset) {
+            return Err(Error::OutOfRange);
+        }
+        self.state.temperature_offset = offset;
+        Ok(())
+    }
+
+    /// Returns the configured temperature offset in °C
+    pub fn temperature_offset(&self) -> i8 {
+        self.state.temperature_offset
+    }
+
+    /// Restricts the following refreshes to a window, `x` and `width` must be multiples of 8
+    ///
+    /// The controller only drives the gates (rows) and sources (columns) of the window, so
+    /// the rest of the panel doesn't flicker. Used by `display_frame`, `display_new_frame`
+    /// and `update_and_display_frame` until
+    /// [`reset_active_window`](Epd::reset_active_window).
+    pub fn set_active_window(
+        &mut self,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        self.state.active_window = Some((x, y, width, height));
+        Ok(())
+    }
+
+    /// Refreshes the whole panel again
+    pub fn reset_active_window(&mut self) {
+        self.state.active_window = None;
+    }
+
+    /// Returns the window set with [`set_active_window`](Epd::set_active_window)
+    pub fn active_window(&self) -> Option<(u32, u32, u32, u32)> {
+        self.state.active_window
+    }
+
+    /// Starts a refresh of the active window or the whole panel
+    fn refresh(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.send_temperature_offset(spi)?;
+        let Some((x, y, width, height)) = self.state.active_window else {
+            self.interface.cmd(spi, Command::DisplayRefresh)?;
+            return Ok(());
+        };
+        self.interface.cmd(spi, Command::PartialIn)?;
+        self.send_partial_window(spi, x, y, width, height, true)?;
+        self.interface.cmd(spi, Command::DisplayRefresh)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PartialOut)?;
+        Ok(())
+    }
+
+    /// Selects the internal temperature sensor with the configured offset
+    fn send_temperature_offset(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        // TSE = 0 (internal sensor), TO[3:0] is a 4-bit two's complement in half °C
+        let offset = ((self.state.temperature_offset * 2) as u8) & 0x0F;
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureCalibration, &[offset])
+    }
+
+    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(
+            spi,
+            Command::TconResolution,
+            &[
+                (P::WIDTH >> 8) as u8,
+                P::WIDTH as u8,
+                (P::HEIGHT >> 8) as u8,
+                P::HEIGHT as u8,
+            ],
+        )
+    }
+
+    fn send_booster_soft_start(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        let init = P::INIT;
+        let booster = match self.refresh {
+            RefreshLut::Full => init.booster_soft_start,
+            RefreshLut::Quick => Some(init.quick_booster_soft_start),
+        };
+        match booster {
+            Some(booster) => self
+                .interface
+                .cmd_with_data(spi, Command::BoosterSoftStart, &booster),
+            None => Ok(()),
+        }
+    }
+
+    /// The quick refresh forces the temperature of
+    /// [`quick_temperature`](Uc8179Init::quick_temperature), the full refresh uses the
+    /// temperature sensor
+    fn send_forced_temperature(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        let Some(temperature) = P::INIT.quick_temperature else {
+            return Ok(());
+        };
+        match self.refresh {
+            RefreshLut::Full => self
+                .interface
+                .cmd_with_data(spi, Command::CascadeSetting, &[0x00]),
+            RefreshLut::Quick => {
+                self.interface
+                    .cmd_with_data(spi, Command::CascadeSetting, &[0x02])?;
+                self.interface
+                    .cmd_with_data(spi, Command::ForceTemperature, &[temperature])
+            }
+        }
+    }
+
+    /// During partial refreshes the controller copies the new data to the old data after
+    /// the refresh (N2OCP), so only the new data has to be sent for the next one, and the
+    /// border is left floating to not flash it.
+    fn send_vcom_and_data_interval(
+        &mut self,
+        spi: &mut SPI,
+        partial: bool,
+    ) -> Result<(), SPI::Error> {
+        let [mut data, interval] = P::INIT.vcom_and_data_interval;
+        if partial {
+            data |= 0x80 | 0x08;
+        }
+        self.interface
+            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[data, interval])
+    }
+
+    /// x should be the multiple of 8, the last 3 bit will always be ignored
+    ///
+    /// The gates outside of the window are scanned as well, except with `inside_only`.
+    fn send_partial_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        inside_only: bool,
+    ) -> Result<(), SPI::Error> {
+        let x_start = x & !0x07;
+        let x_end = (x_start + width - 1) | 0x07;
+        let y_end = y + height - 1;
+
+        self.interface.cmd_with_data(
+            spi,
+            Command::PartialWindow,
+            &[
+                (x_start >> 8) as u8,
+                x_start as u8,
+                (x_end >> 8) as u8,
+                x_end as u8,
+                (y >> 8) as u8,
+                y as u8,
+                (y_end >> 8) as u8,
+                y_end as u8,
+                // PT_SCAN: gates scan both inside and outside of the partial window
+                if inside_only { 0x00 } else { 0x01 },
+            ],
+        )
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        Self::from_interface(DisplayInterface::new(cs, busy, dc, rst, delay_us))
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        let init = P::INIT;
+        self.interface.reset(delay, init.reset_delay_us, 2_000);
+
+        // V2 procedure as described here:
+        // https://github.com/waveshare/e-Paper/blob/master/RaspberryPi%26JetsonNano/python/lib/waveshare_epd/epd7in5bc_V2.py
+        // and as per specs:
+        // https://www.waveshare.com/w/upload/6/60/7.5inch_e-Paper_V2_Specification.pdf
+
+        self.send_booster_soft_start(spi)?;
+        self.interface
+            .cmd_with_data(spi, Command::PowerSetting, &init.power_setting)?;
+        self.interface.cmd(spi, Command::PowerOn)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::PanelSetting, &[init.panel_setting])?;
+        if let Some(pll) = init.pll {
+            self.interface
+                .cmd_with_data(spi, Command::PllControl, &[pll])?;
+        }
+        self.send_resolution(spi)?;
+        // DUSPI_EN for transports sending the frames over two data lines
+        let dual_spi = if self.interface.frame_data_lines() == 2 {
+            0x10
+        } else {
+            0x00
+        };
+        self.interface
+            .cmd_with_data(spi, Command::DualSpi, &[dual_spi])?;
+        self.interface
+            .cmd_with_data(spi, Command::TconSetting, &[init.tcon])?;
+        self.send_vcom_and_data_interval(spi, false)?;
+        if init.disable_spi_flash {
+            self.interface.cmd_with_data(
+                spi,
+                Command::SpiFlashControl,
+                &[0x00, 0x00, 0x00, 0x00],
+            )?;
+        }
+        self.send_forced_temperature(spi)?;
+        self.wait_until_idle(spi, delay)?;
+        Ok(())
+    }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, P::INIT.reset_delay_us, 2_000);
+        self.interface.wait_until_idle_with_cmd_timeout(
+            spi,
+            delay,
+            P::IS_BUSY_LOW,
+            Command::GetStatus,
+            PROBE_TIMEOUT_MS,
+        )?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    type DisplayColor = P::DisplayColor;
+
+    fn set_background_color(&mut self, color: P::DisplayColor) {
+        self.background_color = color;
+    }
+
+    fn background_color(&self) -> &P::DisplayColor {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        P::WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        P::HEIGHT
+    }
+
+    fn capabilities(&self) -> DisplayCapabilities {
+        P::CAPABILITIES
+    }
+
+    /// Transmits a frame, the black/white plane followed by the chromatic plane for
+    /// black/white/red panels
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        let plane_len = self.plane_len();
+        crate::check_buffer_len(buffer, plane_len * P::DisplayColor::BUFFER_COUNT)?;
+        self.wait_until_idle(spi, delay)?;
+        if P::DisplayColor::BUFFER_COUNT == 2 {
+            let (bw, chromatic) = buffer.split_at(plane_len);
+            self.interface
+                .cmd_with_frame(spi, Command::DataStartTransmission1, bw)?;
+            self.interface
+                .cmd_with_frame(spi, Command::DataStartTransmission2, chromatic)?;
+        } else {
+            self.interface
+                .cmd_with_frame(spi, Command::DataStartTransmission2, buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Transmits the new data of a window, the rest of the frame stays unchanged
+    ///
+    /// `x` and `width` must be multiples of 8. Use `display_new_frame` of [`QuickRefresh`] to
+    /// only redraw the changed pixels, or `display_frame` for a full refresh. Returns
+    /// [`Error::Unsupported`] for panels without partial refresh.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        if !P::CAPABILITIES.partial_refresh {
+            return Err(Error::Unsupported);
+        }
+        crate::check_partial_window(buffer, x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PartialIn)?;
+        self.send_partial_window(spi, x, y, width, height, false)?;
+        self.interface
+            .cmd_with_frame(spi, Command::DataStartTransmission2, buffer)?;
+        self.interface.cmd(spi, Command::PartialOut)?;
+        Ok(())
+    }
+
+    /// Clears the frame to the background color and refreshes the panel
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.send_resolution(spi)?;
+
+        let [old, new] = self.background_color.clear_bytes();
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        self.interface
+            .data_x_times(spi, old, P::WIDTH / 8 * P::HEIGHT)?;
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface
+            .data_x_times(spi, new, P::WIDTH / 8 * P::HEIGHT)?;
+
+        self.interface.cmd(spi, Command::DisplayRefresh)?;
+        Ok(())
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> HasHooks
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.refresh(spi, delay)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.refresh(spi, delay)?;
+        Ok(())
+    }
+
+    /// Selects the refresh mode
+    ///
+    /// There are no custom LUTs for this controller, `RefreshLut::Quick` forces the
+    /// temperature of [`quick_temperature`](Uc8179Init::quick_temperature) instead, which
+    /// selects a faster waveform of the OTP. It shows more ghosting, so a full refresh should
+    /// be done from time to time. Panels without a fast waveform stay at `RefreshLut::Full`.
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        if let Some(refresh_lut) = refresh_rate {
+            if P::INIT.quick_temperature.is_some() {
+                self.refresh = refresh_lut;
+            }
+        }
+        self.wait_until_idle(spi, delay)?;
+        self.send_booster_soft_start(spi)?;
+        self.send_forced_temperature(spi)?;
+        Ok(())
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Otp
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(P::IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle_with_cmd(
+            spi,
+            delay,
+            P::IS_BUSY_LOW,
+            Command::GetStatus,
+            on_poll,
+        )?;
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.wait_until_idle_with_cmd_timeout(
+            spi,
+            delay,
+            P::IS_BUSY_LOW,
+            Command::GetStatus,
+            timeout_ms,
+        )
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PowerOff)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        Ok(())
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179, DisplayColor = Color>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    /// Replaces the data the new frame is compared against
+    ///
+    /// Usually not needed, as the controller keeps the last displayed frame as old data.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, self.plane_len())?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_frame(spi, Command::DataStartTransmission1, buffer)?;
+        Ok(())
+    }
+
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)
+    }
+
+    /// Only redraws the pixels which differ between the old and the new frame
+    ///
+    /// Uses the partial waveform the controller selects for the temperature of
+    /// [`partial_temperature`](Uc8179Init::partial_temperature), the refresh mode chosen with
+    /// `set_lut` is restored afterwards. Returns [`Error::Unsupported`] for panels without
+    /// partial waveform.
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        let Some(temperature) = P::INIT.partial_temperature else {
+            return Err(Error::Unsupported);
+        };
+        self.wait_until_idle(spi, delay)?;
+        self.send_vcom_and_data_interval(spi, true)?;
+        self.interface
+            .cmd_with_data(spi, Command::CascadeSetting, &[0x02])?;
+        self.interface
+            .cmd_with_data(spi, Command::ForceTemperature, &[temperature])?;
+        self.refresh(spi, delay)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.send_vcom_and_data_interval(spi, false)?;
+        if P::INIT.quick_temperature.is_some() {
+            self.send_forced_temperature(spi)?;
+        } else {
+            self.interface
+                .cmd_with_data(spi, Command::CascadeSetting, &[0x00])?;
+        }
+        Ok(())
+    }
+
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PartialIn)?;
+        self.send_partial_window(spi, x, y, width, height, false)?;
+        self.interface
+            .cmd_with_frame(spi, Command::DataStartTransmission1, buffer)?;
+        self.interface.cmd(spi, Command::PartialOut)?;
+        Ok(())
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_partial_frame(spi, delay, buffer, x, y, width, height)
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PartialIn)?;
+        self.send_partial_window(spi, x, y, width, height, false)?;
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        let [_, color] = self.background_color.clear_bytes();
+        self.interface
+            .data_x_times(spi, color, width / 8 * height)?;
+        self.interface.cmd(spi, Command::PartialOut)?;
+        Ok(())
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+    Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179, DisplayColor = TriColor>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    /// Transmits both planes of a window and refreshes it
+    ///
+    /// `buffer` holds the black/white plane of the window followed by its chromatic plane.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_partial_frame2(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        crate::check_buffer_len(buffer, 2 * (width / 8 * height) as usize)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface.cmd(spi, Command::PartialIn)?;
+        self.send_partial_window(spi, x, y, width, height, false)?;
+        let (bw, chromatic) = buffer.split_at(buffer.len() / 2);
+        self.interface
+            .cmd_with_frame(spi, Command::DataStartTransmission1, bw)?;
+        self.interface
+            .cmd_with_frame(spi, Command::DataStartTransmission2, chromatic)?;
+
+        self.interface.cmd(spi, Command::DisplayRefresh)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface.cmd(spi, Command::PartialOut)?;
+        Ok(())
+    }
+}
+
+impl<P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+    Epd<Uc8179, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    P: PanelConfig<Uc8179>,
+    P::DisplayColor: Uc8179Color,
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    /// Reads the panel-break and low-voltage flags of the controller
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the status registers.
+    pub fn health(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<PanelHealth, Error<<SPI as Write<u8>>::Error>> {
+        let mut psta = [0u8];
+        let mut lvd = [0u8];
+
+        self.wait_until_idle(spi, delay)?;
+        self.interface.cmd(spi, Command::PanelBreakCheck)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_read(spi, Command::PanelBreakCheck, &mut psta)?;
+
+        self.interface
+            .cmd_read(spi, Command::LowPowerDetection, &mut lvd)?;
+
+        Ok(PanelHealth {
+            // PSTA: 0 = panel check fail, 1 = panel check pass
+            panel_break: Some(psta[0] & 0x01 == 0),
+            // LVD: 0 = low power input, 1 = normal
+            low_voltage: lvd[0] & 0x01 == 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic::{Panel7in5V2, Panel7in5bV2};
+    use crate::traits::Command as CommandTrait;
+    extern crate std;
+    use embedded_hal_mock::{
+        delay::MockNoop,
+        pin::{Mock, State, Transaction},
+        spi,
+    };
+    use std::vec;
+
+    #[test]
+    fn command_addr() {
+        assert_eq!(Command::PanelSetting.address(), 0x00);
+        assert_eq!(Command::DisplayRefresh.address(), 0x12);
+    }
+
+    #[test]
+    fn health() {
+        use std::vec::Vec;
+
+        let read_health = |psta: u8, lvd: u8| {
+            let mut spi = spi::Mock::new(&[
+                spi::Transaction::write(vec![0x71]),
+                spi::Transaction::write(vec![0x44]),
+                spi::Transaction::write(vec![0x71]),
+                spi::Transaction::write(vec![0x44]),
+                spi::Transaction::transfer(vec![0x00], vec![psta]),
+                spi::Transaction::write(vec![0x51]),
+                spi::Transaction::transfer(vec![0x00], vec![lvd]),
+            ]);
+            let toggles = (0..7)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<Vec<_>>();
+            let mut cs = Mock::new(&toggles);
+            let dc_states = [0, 0, 0, 0, 1, 0, 1]
+                .map(|high| Transaction::set(if high == 1 { State::High } else { State::Low }));
+            let mut dc = Mock::new(&dc_states);
+            let mut busy = Mock::new(&vec![Transaction::get(State::High); 2]);
+            let mut epd: Epd<Uc8179, Panel7in5V2, spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+                Epd::new_uninitialized(cs.clone(), busy.clone(), dc.clone(), Mock::new(&[]), None);
+
+            let health = epd.health(&mut spi, &mut MockNoop::new()).unwrap();
+
+            spi.done();
+            cs.done();
+            dc.done();
+            busy.done();
+            health
+        };
+
+        // PSTA and LVD report a pass and a normal voltage with a set bit 0
+        let health = read_health(0x01, 0x01);
+        assert_eq!(health.panel_break, Some(false));
+        assert!(!health.low_voltage);
+        assert!(health.is_ok());
+
+        let health = read_health(0x00, 0x01);
+        assert_eq!(health.panel_break, Some(true));
+        assert!(!health.low_voltage);
+
+        let health = read_health(0x01, 0x00);
+        assert_eq!(health.panel_break, Some(false));
+        assert!(health.low_voltage);
+        assert!(!health.is_ok());
+    }
+
+    /// A step of the expected traffic
+    enum Step {
+        Cmd(u8),
+        Data(&'static [u8]),
+        /// Polling the status until the busy pin is released
+        Wait,
+    }
+    use Step::*;
+
+    type TestEpd<P> = Epd<Uc8179, P, spi::Mock, Mock, Mock, Mock, Mock, MockNoop>;
+
+    /// Mocks of the spi bus and the cs, dc and busy pins expecting `steps`
+    fn mocks(steps: &[Step]) -> (spi::Mock, Mock, Mock, Mock) {
+        let (mut writes, mut cs, mut dc, mut busy) = (vec![], vec![], vec![], vec![]);
+        for step in steps {
+            let (data, is_data) = match step {
+                Cmd(command) => (vec![*command], false),
+                Data(data) => (data.to_vec(), true),
+                Wait => {
+                    busy.push(Transaction::get(State::High));
+                    (vec![0x71], false)
+                }
+            };
+            writes.push(spi::Transaction::write(data));
+            cs.extend([Transaction::set(State::Low), Transaction::set(State::High)]);
+            dc.push(Transaction::set(if is_data {
+                State::High
+            } else {
+                State::Low
+            }));
+        }
+        (
+            spi::Mock::new(&writes),
+            Mock::new(&cs),
+            Mock::new(&dc),
+            Mock::new(&busy),
+        )
+    }
+
+    /// Runs `run` on a driver of the panel `P`, checking the traffic against `steps`
+    fn expect<P>(
+        steps: &[Step],
+        rst: &[Transaction],
+        run: impl FnOnce(&mut TestEpd<P>, &mut spi::Mock),
+    ) where
+        P: PanelConfig<Uc8179>,
+        P::DisplayColor: Uc8179Color,
+    {
+        let (mut spi, mut cs, mut dc, mut busy) = mocks(steps);
+        let mut rst = Mock::new(rst);
+        let mut epd: TestEpd<P> =
+            Epd::new_uninitialized(cs.clone(), busy.clone(), dc.clone(), rst.clone(), None);
+
+        run(&mut epd, &mut spi);
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+        rst.done();
+    }
+
+    fn reset() -> [Transaction; 3] {
+        [
+            Transaction::set(State::High),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]
+    }
+
+    #[test]
+    fn init_7in5_v2() {
+        // The sequence of the released epd7in5_v2 driver
+        let steps = [
+            Cmd(0x06),
+            Data(&[0x17, 0x17, 0x27, 0x17]),
+            Cmd(0x01),
+            Data(&[0x07, 0x17, 0x3F, 0x3F]),
+            Cmd(0x04),
+            Wait,
+            Cmd(0x00),
+            Data(&[0x1F]),
+            Cmd(0x30),
+            Data(&[0x06]),
+            Cmd(0x61),
+            Data(&[0x03, 0x20, 0x01, 0xE0]),
+            Cmd(0x15),
+            Data(&[0x00]),
+            Cmd(0x60),
+            Data(&[0x22]),
+            Cmd(0x50),
+            Data(&[0x10, 0x07]),
+            Cmd(0xE0),
+            Data(&[0x00]),
+            Wait,
+        ];
+        expect::<Panel7in5V2>(&steps, &reset(), |epd, spi| {
+            epd.init(spi, &mut MockNoop::new()).unwrap()
+        });
+    }
+
+    #[test]
+    fn init_7in5b_v2() {
+        // The sequence of the released epd7in5b_v2 driver, which sent TCON after CDI. Both
+        // only write a register, so the order doesn't matter to the controller.
+        let steps = [
+            Cmd(0x01),
+            Data(&[0x07, 0x07, 0x3F, 0x3F]),
+            Cmd(0x04),
+            Wait,
+            Cmd(0x00),
+            Data(&[0x0F]),
+            Cmd(0x61),
+            Data(&[0x03, 0x20, 0x01, 0xE0]),
+            Cmd(0x15),
+            Data(&[0x00]),
+            Cmd(0x60),
+            Data(&[0x22]),
+            Cmd(0x50),
+            Data(&[0x11, 0x07]),
+            Cmd(0x65),
+            Data(&[0x00, 0x00, 0x00, 0x00]),
+            Wait,
+        ];
+        expect::<Panel7in5bV2>(&steps, &reset(), |epd, spi| {
+            epd.init(spi, &mut MockNoop::new()).unwrap()
+        });
+    }
+
+    #[test]
+    fn partial_window() {
+        // PTL with the high bytes of the coordinates, scanning the gates outside of it
+        let steps = [
+            Wait,
+            Cmd(0x91),
+            Cmd(0x90),
+            Data(&[0x01, 0x00, 0x01, 0x0F, 0x01, 0x2C, 0x01, 0x2D, 0x01]),
+            Cmd(0x13),
+            Data(&[0xAA, 0x55, 0xAA, 0x55]),
+            Cmd(0x92),
+        ];
+        expect::<Panel7in5V2>(&steps, &[], |epd, spi| {
+            epd.update_partial_frame(
+                spi,
+                &mut MockNoop::new(),
+                &[0xAA, 0x55, 0xAA, 0x55],
+                256,
+                300,
+                16,
+                2,
+            )
+            .unwrap()
+        });
+
+        // The refresh of the active window only scans its gates
+        let steps = [
+            Wait,
+            Cmd(0x41),
+            Data(&[0x00]),
+            Cmd(0x91),
+            Cmd(0x90),
+            Data(&[0x01, 0x00, 0x01, 0x3F, 0x01, 0x2C, 0x01, 0x3F, 0x00]),
+            Cmd(0x12),
+            Wait,
+            Cmd(0x92),
+        ];
+        expect::<Panel7in5V2>(&steps, &[], |epd, spi| {
+            epd.set_active_window(256, 300, 64, 20).unwrap();
+            epd.display_frame(spi, &mut MockNoop::new()).unwrap()
+        });
+    }
+
+    #[test]
+    fn display_new_frame() {
+        // CDI with N2OCP and the floating border, the forced temperature of the partial
+        // waveform and afterwards the settings of the full refresh again
+        let steps = [
+            Wait,
+            Cmd(0x50),
+            Data(&[0x98, 0x07]),
+            Cmd(0xE0),
+            Data(&[0x02]),
+            Cmd(0xE5),
+            Data(&[0x6E]),
+            Cmd(0x41),
+            Data(&[0x00]),
+            Cmd(0x12),
+            Wait,
+            Cmd(0x50),
+            Data(&[0x10, 0x07]),
+            Cmd(0xE0),
+            Data(&[0x00]),
+        ];
+        expect::<Panel7in5V2>(&steps, &[], |epd, spi| {
+            epd.display_new_frame(spi, &mut MockNoop::new()).unwrap()
+        });
+    }
+
+    #[test]
+    fn clear_bytes() {
+        assert_eq!(Color::White.clear_bytes(), [0x00, 0x00]);
+        assert_eq!(Color::Black.clear_bytes(), [0xFF, 0xFF]);
+        assert_eq!(TriColor::White.clear_bytes(), [0xFF, 0x00]);
+        assert_eq!(TriColor::Black.clear_bytes(), [0x00, 0x00]);
+        assert_eq!(TriColor::Chromatic.clear_bytes(), [0xFF, 0xFF]);
+    }
+}
@@ -0,0 +1,407 @@
+//! Object-safe version of [`WaveshareDisplay`](crate::traits::WaveshareDisplay)
+//!
+//! [`WaveshareDisplay`](crate::traits::WaveshareDisplay) can't be used as trait object because of its constructor and
+//! the error type of the spi bus. [`DynWaveshareDisplay`] leaves out the constructor and
+//! erases the spi error to `Error<()>`, [`DynEpd`] implements it for every driver.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<()>> {
+//!use epd_waveshare::{dyn_epd::*, epd2in9_v2, epd4in2, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = || pin::Mock::new(&expectations);
+//!# let busy_in = || pin::Mock::new(&expectations);
+//!# let dc = || pin::Mock::new(&expectations);
+//!# let rst = || pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# let small = true;
+//!
+//!let mut epd_2in9;
+//!let mut epd_4in2;
+//!let epd: &mut dyn DynWaveshareDisplay<_, _, DisplayColor = Color> = if small {
+//!    let epd = epd2in9_v2::Epd2in9::new(&mut spi, cs_pin(), busy_in(), dc(), rst(), &mut delay, None)
+//!        .map_err(|_| epd_waveshare::error::Error::Spi(()))?;
+//!    epd_2in9 = DynEpd::new(epd);
+//!    &mut epd_2in9
+//!} else {
+//!    let epd = epd4in2::Epd4in2::new(&mut spi, cs_pin(), busy_in(), dc(), rst(), &mut delay, None)
+//!        .map_err(|_| epd_waveshare::error::Error::Spi(()))?;
+//!    epd_4in2 = DynEpd::new(epd);
+//!    &mut epd_4in2
+//!};
+//!
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::error::Error;
+use crate::traits::{
+    CommandObserver, DisplayCapabilities, EpdHooks, EpdInit, EpdPower, EpdRefresh, EpdUpdate,
+    LutSource, Orientation, RefreshLut,
+};
+
+/// Object-safe counterpart of [`WaveshareDisplay`](crate::traits::WaveshareDisplay)
+///
+/// Errors of the spi bus are returned as `Error::Spi(())`.
+pub trait DynWaveshareDisplay<SPI, DELAY> {
+    /// The Color Type used by the Display
+    type DisplayColor;
+
+    /// See [`EpdInit::init`]
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdInit::probe`]
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdPower::sleep`]
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdPower::wake_up`]
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdUpdate::set_background_color`]
+    fn set_background_color(&mut self, color: Self::DisplayColor);
+
+    /// See [`EpdUpdate::background_color`]
+    fn background_color(&self) -> &Self::DisplayColor;
+
+    /// See [`EpdUpdate::width`]
+    fn width(&self) -> u32;
+
+    /// See [`EpdUpdate::height`]
+    fn height(&self) -> u32;
+
+    /// See [`EpdUpdate::capabilities`]
+    fn capabilities(&self) -> DisplayCapabilities;
+
+    /// See [`EpdUpdate::native_orientation`]
+    fn native_orientation(&self) -> Orientation;
+
+    /// See [`EpdUpdate::row_bytes`]
+    fn row_bytes(&self) -> usize;
+
+    /// See [`EpdUpdate::padded_width`]
+    fn padded_width(&self) -> usize;
+
+    /// See [`EpdUpdate::plane_len`]
+    fn plane_len(&self) -> usize;
+
+    /// See [`EpdUpdate::update_frame`]
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<()>>;
+
+    /// See [`EpdUpdate::update_partial_frame`]
+    #[allow(clippy::too_many_arguments)]
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::display_frame`]
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::update_and_display_frame`]
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<()>>;
+
+    /// See [`EpdUpdate::clear_frame`]
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::set_lut`]
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::refresh_mode`]
+    fn refresh_mode(&self) -> RefreshLut;
+
+    /// See [`EpdRefresh::lut_source`]
+    fn lut_source(&self) -> LutSource;
+
+    /// See [`EpdRefresh::is_busy`]
+    fn is_busy(&self) -> bool;
+
+    /// See [`EpdHooks::set_command_observer`](crate::prelude::EpdHooks::set_command_observer)
+    fn set_command_observer(&mut self, observer: Option<&'static dyn CommandObserver>);
+
+    /// See [`EpdHooks::set_poll_hook`](crate::prelude::EpdHooks::set_poll_hook)
+    fn set_poll_hook(&mut self, hook: Option<&'static dyn Fn()>);
+
+    /// See [`EpdRefresh::wait_until_idle`]
+    fn wait_until_idle(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::wait_until_idle_with`]
+    fn wait_until_idle_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<()>>;
+
+    /// See [`EpdRefresh::wait_until_idle_timeout`]
+    fn wait_until_idle_timeout(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<()>>;
+
+    /// See [`WaveshareDisplay::recover`](crate::traits::WaveshareDisplay::recover)
+    fn recover(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>>;
+}
+
+/// Wraps a driver to implement [`DynWaveshareDisplay`] for it
+///
+/// The pin types are only needed to know which implementations of the driver traits to use.
+/// The driver doesn't need to implement [`EpdNew`](crate::traits::EpdNew), so `AnyEpd` of the
+/// `any_epd` module can be wrapped as well.
+pub struct DynEpd<EPD, CS, BUSY, DC, RST> {
+    epd: EPD,
+    _pins: PhantomData<(CS, BUSY, DC, RST)>,
+}
+
+impl<EPD, CS, BUSY, DC, RST> DynEpd<EPD, CS, BUSY, DC, RST> {
+    /// Wraps the driver
+    pub fn new(epd: EPD) -> Self {
+        DynEpd {
+            epd,
+            _pins: PhantomData,
+        }
+    }
+
+    /// Returns the driver, e.g. to use the driver specific functions
+    pub fn inner(&mut self) -> &mut EPD {
+        &mut self.epd
+    }
+
+    /// Returns the wrapped driver
+    pub fn release(self) -> EPD {
+        self.epd
+    }
+}
+
+impl<EPD, SPI, CS, BUSY, DC, RST, DELAY> DynWaveshareDisplay<SPI, DELAY>
+    for DynEpd<EPD, CS, BUSY, DC, RST>
+where
+    EPD: EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdHooks,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = EPD::DisplayColor;
+
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.init(spi, delay).map_err(erase_spi)
+    }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.probe(spi, delay).map_err(erase_spi)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.sleep(spi, delay).map_err(erase_spi)
+    }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.wake_up(spi, delay).map_err(erase_spi)
+    }
+
+    fn set_background_color(&mut self, color: Self::DisplayColor) {
+        self.epd.set_background_color(color)
+    }
+
+    fn background_color(&self) -> &Self::DisplayColor {
+        self.epd.background_color()
+    }
+
+    fn width(&self) -> u32 {
+        self.epd.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.epd.height()
+    }
+
+    fn capabilities(&self) -> DisplayCapabilities {
+        self.epd.capabilities()
+    }
+
+    fn native_orientation(&self) -> Orientation {
+        self.epd.native_orientation()
+    }
+
+    fn row_bytes(&self) -> usize {
+        self.epd.row_bytes()
+    }
+
+    fn padded_width(&self) -> usize {
+        self.epd.padded_width()
+    }
+
+    fn plane_len(&self) -> usize {
+        self.epd.plane_len()
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<()>> {
+        self.epd.update_frame(spi, buffer, delay).map_err(erase_spi)
+    }
+
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<()>> {
+        self.epd
+            .update_partial_frame(spi, delay, buffer, x, y, width, height)
+            .map_err(erase_spi)
+    }
+
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.display_frame(spi, delay).map_err(erase_spi)
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<()>> {
+        self.epd
+            .update_and_display_frame(spi, buffer, delay)
+            .map_err(erase_spi)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.clear_frame(spi, delay).map_err(erase_spi)
+    }
+
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<()>> {
+        self.epd
+            .set_lut(spi, delay, refresh_rate)
+            .map_err(erase_spi)
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.epd.refresh_mode()
+    }
+
+    fn lut_source(&self) -> LutSource {
+        self.epd.lut_source()
+    }
+
+    fn is_busy(&self) -> bool {
+        self.epd.is_busy()
+    }
+
+    fn set_command_observer(&mut self, observer: Option<&'static dyn CommandObserver>) {
+        self.epd.set_command_observer(observer)
+    }
+
+    fn set_poll_hook(&mut self, hook: Option<&'static dyn Fn()>) {
+        self.epd.set_poll_hook(hook)
+    }
+
+    fn wait_until_idle(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        self.epd.wait_until_idle(spi, delay).map_err(erase_spi)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<()>> {
+        self.epd
+            .wait_until_idle_with(spi, delay, on_poll)
+            .map_err(erase_spi)
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<()>> {
+        self.epd
+            .wait_until_idle_timeout(spi, delay, timeout_ms)
+            .map_err(erase_spi)
+    }
+
+    fn recover(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<()>> {
+        // The steps of `WaveshareDisplay::recover`, which needs `EpdNew` as well
+        self.epd.init(spi, delay).map_err(erase_spi)?;
+        self.epd.clear_frame(spi, delay).map_err(erase_spi)?;
+        self.epd.display_frame(spi, delay).map_err(erase_spi)?;
+        self.epd.wait_until_idle(spi, delay).map_err(erase_spi)
+    }
+}
+
+/// Erases only the spi error, keeping the other errors
+fn erase_spi<E>(error: Error<E>) -> Error<()> {
+    match error {
+        Error::Spi(_) => Error::Spi(()),
+        Error::TooSoon { remaining_ms } => Error::TooSoon { remaining_ms },
+        Error::BusyStuck => Error::BusyStuck,
+        Error::InvalidRegion => Error::InvalidRegion,
+        Error::InvalidBufferSize { expected, actual } => {
+            Error::InvalidBufferSize { expected, actual }
+        }
+        Error::Unsupported => Error::Unsupported,
+        Error::OutOfRange => Error::OutOfRange,
+        Error::VerificationFailed => Error::VerificationFailed,
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyleBuilder},
 //!};
@@ -38,6 +38,10 @@
 //!epd.update_frame(&mut spi, &display.buffer(), &mut delay)?;
 //!epd.display_frame(&mut spi, &mut delay)?;
 //!
+//!// Use the quick refresh LUT for the following updates
+//!epd.set_lut(&mut spi, &mut delay, Some(RefreshLut::Quick))?;
+//!epd.update_and_display_frame(&mut spi, &display.buffer(), &mut delay)?;
+//!
 //!// Set the EPD to sleep
 //!epd.sleep(&mut spi, &mut delay)?;
 //!# Ok(())
@@ -50,6 +54,16 @@ pub const WIDTH: u32 = 200;
 pub const HEIGHT: u32 = 200;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 2000,
+    min_refresh_interval_ms: 0,
+};
 //const DPI: u16 = 184;
 const IS_BUSY_LOW: bool = false;
 
@@ -65,10 +79,14 @@ use crate::type_a::{
 
 use crate::color::Color;
 
-use crate::traits::{RefreshLut, WaveshareDisplay};
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut,
+};
 
 use crate::buffer_len;
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
 
 /// Full size buffer for use with the 1in54b EPD
 #[cfg(feature = "graphics")]
@@ -90,16 +108,38 @@ pub struct Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
     refresh: RefreshLut,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
 where
-    SPI: Write<u8>,
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
+
+        Epd1in54 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
     CS: OutputPin,
     BUSY: InputPin,
     DC: OutputPin,
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.interface.reset(delay, 10_000, 10_000);
 
         // 3 Databytes:
@@ -143,9 +183,20 @@ where
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, E, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8, Error = E>,
@@ -156,6 +207,7 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
+
     fn width(&self) -> u32 {
         WIDTH
     }
@@ -164,39 +216,8 @@ where
         HEIGHT
     }
 
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-
-        let mut epd = Epd1in54 {
-            interface,
-            background_color: DEFAULT_BACKGROUND_COLOR,
-            refresh: RefreshLut::Full,
-        };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
-        //TODO: is 0x00 needed here or would 0x01 be even more efficient?
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleepMode, &[0x00])?;
-        Ok(())
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
     }
 
     fn update_frame(
@@ -204,7 +225,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.use_full_frame(spi, delay)?;
         self.interface
@@ -222,7 +243,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
         self.set_ram_area(spi, delay, x, y, x + width, y + height)?;
         self.set_ram_counter(spi, delay, x, y)?;
@@ -232,7 +254,53 @@ where
         Ok(())
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+
+        // clear the ram with the background color
+        let color = self.background_color.get_byte_value();
+
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> HasHooks for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // enable clock signal, enable cp, display pattern -> 0xC4 (tested with the arduino version)
         //TODO: test control_1 or control_2 with default value 0xFF (from the datasheet)
@@ -251,39 +319,18 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.use_full_frame(spi, delay)?;
-
-        // clear the ram with the background color
-        let color = self.background_color.get_byte_value();
-
-        self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface
-            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
-        Ok(())
-    }
-
-    fn set_background_color(&mut self, background_color: Color) {
-        self.background_color = background_color;
-    }
-
-    fn background_color(&self) -> &Color {
-        &self.background_color
-    }
-
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         if let Some(refresh_lut) = refresh_rate {
             self.refresh = refresh_lut;
         }
@@ -293,8 +340,59 @@ where
         }
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
+        //TODO: is 0x00 needed here or would 0x01 be even more efficient?
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x00])?;
         Ok(())
     }
 }
@@ -312,7 +410,7 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         // choose full frame/ram
         self.set_ram_area(spi, delay, 0, 0, WIDTH - 1, HEIGHT - 1)?;
 
@@ -328,10 +426,10 @@ where
         start_y: u32,
         end_x: u32,
         end_y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        assert!(start_x < end_x);
-        assert!(start_y < end_y);
+        debug_assert!(start_x < end_x);
+        debug_assert!(start_y < end_y);
 
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -361,7 +459,7 @@ where
         delay: &mut DELAY,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -382,9 +480,9 @@ where
         spi: &mut SPI,
         delay: &mut DELAY,
         buffer: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        assert!(buffer.len() == 30);
+        debug_assert!(buffer.len() == 30);
 
         self.interface
             .cmd_with_data(spi, Command::WriteLutRegister, buffer)?;
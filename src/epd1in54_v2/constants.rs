@@ -1,46 +1,52 @@
+use crate::lut_table::lut_table;
+
 #[rustfmt::skip]
 // Original Waveforms from Waveshare for 1in54_V2
-pub(crate) const LUT_FULL_UPDATE: [u8; 159] =[
-    0x80, 0x48, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x40, 0x48, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x80, 0x48, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x40, 0x48, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0xA, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x8, 0x1, 0x0, 0x8, 0x1, 0x0, 0x2, 
-    0xA, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x0, 0x0, 0x0, 
-    0x22, 0x17, 0x41, 0x0, 0x32, 0x20
-];
+lut_table! {
+    pub(crate) LUT_FULL_UPDATE: [u8; 159] = [
+        0x80, 0x48, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x40, 0x48, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x80, 0x48, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x40, 0x48, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0xA, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x8, 0x1, 0x0, 0x8, 0x1, 0x0, 0x2, 
+        0xA, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x0, 0x0, 0x0, 
+        0x22, 0x17, 0x41, 0x0, 0x32, 0x20
+    ];
+}
 
 #[rustfmt::skip]
-pub(crate) const LUT_PARTIAL_UPDATE: [u8; 159] =[
-    0x0, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x80, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0xF, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x1, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
-    0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x0, 0x0, 0x0, 
-    0x02, 0x17, 0x41, 0xB0, 0x32, 0x28, 
-];
+lut_table! {
+    pub(crate) LUT_PARTIAL_UPDATE: [u8; 159] = [
+        0x0, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x80, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0xF, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x1, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x0, 0x0, 0x0, 
+        0x02, 0x17, 0x41, 0xB0, 0x32, 0x28, 
+    ];
+}
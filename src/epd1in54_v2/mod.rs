@@ -8,6 +8,16 @@ pub const WIDTH: u32 = 200;
 pub const HEIGHT: u32 = 200;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: true,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 2000,
+    min_refresh_interval_ms: 0,
+};
 const IS_BUSY_LOW: bool = false;
 
 use embedded_hal::{
@@ -19,12 +29,17 @@ use crate::type_a::command::Command;
 
 mod constants;
 use crate::epd1in54_v2::constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE};
+use crate::type_a::constants::LUT_GRAY_UPDATE;
 
 use crate::color::Color;
 
-use crate::traits::{RefreshLut, WaveshareDisplay};
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut,
+};
 
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
 
 #[cfg(feature = "graphics")]
 pub use crate::epd1in54::Display1in54;
@@ -40,16 +55,38 @@ pub struct Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
     refresh: RefreshLut,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
 where
-    SPI: Write<u8>,
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
+
+        Epd1in54 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
     CS: OutputPin,
     BUSY: InputPin,
     DC: OutputPin,
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.interface.reset(delay, 10_000, 10_000);
         self.wait_until_idle(spi, delay)?;
         self.interface.cmd(spi, Command::SwReset)?;
@@ -88,9 +125,20 @@ where
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, E, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8, Error = E>,
@@ -101,6 +149,7 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
+
     fn width(&self) -> u32 {
         WIDTH
     }
@@ -109,37 +158,8 @@ where
         HEIGHT
     }
 
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-
-        let mut epd = Epd1in54 {
-            interface,
-            background_color: DEFAULT_BACKGROUND_COLOR,
-            refresh: RefreshLut::Full,
-        };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
-        Ok(())
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
     }
 
     fn update_frame(
@@ -147,7 +167,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.use_full_frame(spi, delay)?;
         self.interface
@@ -165,7 +185,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
         self.set_ram_area(spi, delay, x, y, x + width, y + height)?;
         self.set_ram_counter(spi, delay, x, y)?;
@@ -175,7 +196,56 @@ where
         Ok(())
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+
+        // clear the ram with the background color
+        let color = self.background_color.get_byte_value();
+
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> HasHooks for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         if self.refresh == RefreshLut::Full {
             self.interface
@@ -197,48 +267,24 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.use_full_frame(spi, delay)?;
-
-        // clear the ram with the background color
-        let color = self.background_color.get_byte_value();
-
-        self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface
-            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
-        self.interface.cmd(spi, Command::WriteRam2)?;
-        self.interface
-            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
-        Ok(())
-    }
-
-    fn set_background_color(&mut self, background_color: Color) {
-        self.background_color = background_color;
-    }
-
-    fn background_color(&self) -> &Color {
-        &self.background_color
-    }
-
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         if let Some(refresh_lut) = refresh_rate {
             self.refresh = refresh_lut;
         }
         match self.refresh {
-            RefreshLut::Full => self.set_lut_helper(spi, delay, &LUT_FULL_UPDATE),
-            RefreshLut::Quick => self.set_lut_helper(spi, delay, &LUT_PARTIAL_UPDATE),
+            RefreshLut::Full => self.set_lut_helper(spi, delay, &LUT_FULL_UPDATE.get()),
+            RefreshLut::Quick => self.set_lut_helper(spi, delay, &LUT_PARTIAL_UPDATE.get()),
         }?;
 
         // Additional configuration required only for partial updates
@@ -256,12 +302,65 @@ where
             // MASTER Activation should not be interupted to avoid currption of panel images
             // therefore a terminate command is send
             self.interface.cmd(spi, Command::Nop)?;
+        } else {
+            // Drive the border again, the quick refresh holds it
+            self.interface
+                .cmd_with_data(spi, Command::BorderWaveformControl, &[0x1])?;
         }
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
         Ok(())
     }
 }
@@ -275,11 +374,58 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
+    /// Transmits a frame with 4 gray levels
+    ///
+    /// The buffer holds 2 bits per pixel, 4 pixels per byte with the first pixel in the
+    /// upper bits: `0b00` is black, `0b01` dark gray, `0b10` light gray and `0b11` white.
+    /// It needs `WIDTH * HEIGHT / 4` bytes.
+    ///
+    /// The lower bits are written to the black/white RAM, the upper bits to the second
+    /// RAM, which is why it must be shown with [`display_frame_gray2`](Epd1in54::display_frame_gray2).
+    pub fn update_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, (WIDTH * HEIGHT / 4) as usize)?;
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.data_gray2_plane(spi, buffer, 0)?;
+
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.data_gray2_plane(spi, buffer, 1)?;
+        Ok(())
+    }
+
+    /// Displays the frame transmitted with [`update_frame_gray2`](Epd1in54::update_frame_gray2)
+    /// using the 4 gray levels LUT.
+    ///
+    /// The LUT of the selected refresh mode is loaded again afterwards.
+    pub fn display_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_lut_helper(spi, delay, &LUT_GRAY_UPDATE.get())?;
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC7])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        // MASTER Activation should not be interupted to avoid currption of panel images
+        // therefore a terminate command is send
+        self.interface.cmd(spi, Command::Nop)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.set_lut(spi, delay, None)
+    }
+
     pub(crate) fn use_full_frame(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         // choose full frame/ram
         self.set_ram_area(spi, delay, 0, 0, WIDTH - 1, HEIGHT - 1)?;
 
@@ -295,10 +441,10 @@ where
         start_y: u32,
         end_x: u32,
         end_y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        assert!(start_x < end_x);
-        assert!(start_y < end_y);
+        debug_assert!(start_x < end_x);
+        debug_assert!(start_y < end_y);
 
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -328,7 +474,7 @@ where
         delay: &mut DELAY,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -349,9 +495,9 @@ where
         spi: &mut SPI,
         delay: &mut DELAY,
         buffer: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        assert!(buffer.len() == 159);
+        debug_assert!(buffer.len() == 159);
 
         self.interface
             .cmd_with_data(spi, Command::WriteLutRegister, &buffer[0..153])?;
@@ -5,9 +5,11 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut, WaveshareThreeColorDisplay,
 };
 
 //The Lookup Tables for the Display
@@ -20,9 +22,19 @@ pub const WIDTH: u32 = 200;
 pub const HEIGHT: u32 = 200;
 /// Default Background Color (white)
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: false,
+    quick_refresh: false,
+    full_refresh_ms: 8000,
+    min_refresh_interval_ms: 180_000,
+};
 const IS_BUSY_LOW: bool = true;
 
-use crate::color::Color;
+use crate::color::{BwPlane, ChromaticPlane, Color, PlaneBackgrounds};
 
 pub(crate) mod command;
 use self::command::Command;
@@ -43,53 +55,8 @@ pub type Display1in54b = crate::graphics::Display<
 pub struct Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     color: Color,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.reset(delay, 10_000, 10_000);
-
-        // set the power settings
-        self.interface
-            .cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x00, 0x08, 0x00])?;
-
-        // start the booster
-        self.interface
-            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x07, 0x07, 0x07])?;
-
-        // power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_us(5000);
-        self.wait_until_idle(spi, delay)?;
-
-        // set the panel settings
-        self.cmd_with_data(spi, Command::PanelSetting, &[0xCF])?;
-
-        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x37])?;
-
-        // PLL
-        self.cmd_with_data(spi, Command::PllControl, &[0x39])?;
-
-        // set resolution
-        self.send_resolution(spi)?;
-
-        self.cmd_with_data(spi, Command::VcmDcSetting, &[0x0E])?;
-
-        self.set_lut(spi, delay, None)?;
-
-        self.wait_until_idle(spi, delay)?;
-
-        Ok(())
-    }
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
@@ -106,9 +73,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_achromatic_frame(spi, delay, black)?;
         self.update_chromatic_frame(spi, delay, chromatic)
     }
@@ -117,8 +84,8 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error> {
+        BwPlane(black): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.send_resolution(spi)?;
 
@@ -135,15 +102,19 @@ where
         &mut self,
         spi: &mut SPI,
         _delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface.data(spi, chromatic)?;
         Ok(())
     }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -153,52 +124,91 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
         let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
         let color = DEFAULT_BACKGROUND_COLOR;
 
-        let mut epd = Epd1in54b { interface, color };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
+        Epd1in54b {
+            interface,
+            color,
+            planes: color.into(),
+        }
     }
+}
 
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
 
+        // set the power settings
         self.interface
-            .cmd_with_data(spi, Command::VcmDcSetting, &[0x00])?; // Vcom to 0V
+            .cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x00, 0x08, 0x00])?;
 
+        // start the booster
         self.interface
-            .cmd_with_data(spi, Command::PowerSetting, &[0x02, 0x00, 0x00, 0x00])?; //VG&VS to 0V fast
+            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x07, 0x07, 0x07])?;
 
+        // power on
+        self.command(spi, Command::PowerOn)?;
+        delay.delay_us(5000);
         self.wait_until_idle(spi, delay)?;
 
-        //NOTE: The example code has a 1s delay here
+        // set the panel settings
+        self.cmd_with_data(spi, Command::PanelSetting, &[0xCF])?;
 
-        self.command(spi, Command::PowerOff)?;
+        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x37])?;
+
+        // PLL
+        self.cmd_with_data(spi, Command::PllControl, &[0x39])?;
+
+        // set resolution
+        self.send_resolution(spi)?;
+
+        self.cmd_with_data(spi, Command::VcmDcSetting, &[0x0E])?;
+
+        self.set_lut(spi, delay, None)?;
+
+        self.wait_until_idle(spi, delay)?;
 
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
         self.init(spi, delay)
     }
 
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = Color;
+
     fn set_background_color(&mut self, color: Color) {
         self.color = color;
+        self.planes = color.into();
     }
 
     fn background_color(&self) -> &Color {
@@ -213,22 +223,28 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.send_resolution(spi)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
-        for b in buffer {
-            // Two bits per pixel
-            let expanded = expand_bits(*b);
-            self.interface.data(spi, &expanded)?;
-        }
+        // Two bits per pixel
+        self.interface.begin_data();
+        let result = buffer
+            .iter()
+            .try_for_each(|b| self.interface.write(spi, &expand_bits(*b)));
+        self.interface.end();
+        result?;
 
         //NOTE: Example code has a delay here
 
@@ -253,32 +269,16 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!()
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.display_frame(spi, delay)?;
-        Ok(())
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.send_resolution(spi)?;
 
-        let color = DEFAULT_BACKGROUND_COLOR.get_byte_value();
+        let color = self.planes.achromatic.get_byte_value();
+        let chromatic = self.planes.chromatic_byte(0xFF);
 
         // Clear the black
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
@@ -290,7 +290,49 @@ where
         // Clear the red
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface
-            .data_x_times(spi, color, WIDTH * HEIGHT / 8)?;
+            .data_x_times(spi, chromatic, WIDTH * HEIGHT / 8)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.command(spi, Command::DisplayRefresh)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
         Ok(())
     }
 
@@ -299,7 +341,7 @@ where
         spi: &mut SPI,
         _delay: &mut DELAY,
         _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface
             .cmd_with_data(spi, Command::LutForVcom, LUT_VCOM0)?;
         self.interface
@@ -318,10 +360,72 @@ where
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn refresh_mode(&self) -> RefreshLut {
+        RefreshLut::Full
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
+
+        self.interface
+            .cmd_with_data(spi, Command::VcmDcSetting, &[0x00])?; // Vcom to 0V
+
+        self.interface
+            .cmd_with_data(spi, Command::PowerSetting, &[0x02, 0x00, 0x00, 0x00])?; //VG&VS to 0V fast
+
+        self.wait_until_idle(spi, delay)?;
+
+        //NOTE: The example code has a 1s delay here
+
+        self.command(spi, Command::PowerOff)?;
+
         Ok(())
     }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
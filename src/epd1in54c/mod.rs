@@ -5,9 +5,11 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut, WaveshareThreeColorDisplay,
 };
 
 /// Width of epd1in54 in pixels
@@ -16,10 +18,20 @@ pub const WIDTH: u32 = 152;
 pub const HEIGHT: u32 = 152;
 /// Default Background Color (white)
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: false,
+    quick_refresh: false,
+    full_refresh_ms: 15_000,
+    min_refresh_interval_ms: 180_000,
+};
 const IS_BUSY_LOW: bool = true;
 const NUM_DISPLAY_BITS: u32 = WIDTH * HEIGHT / 8;
 
-use crate::color::Color;
+use crate::color::{BwPlane, ChromaticPlane, Color, PlaneBackgrounds, TriColorKind};
 
 pub(crate) mod command;
 use self::command::Command;
@@ -40,43 +52,8 @@ pub type Display1in54c = crate::graphics::Display<
 pub struct Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     color: Color,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // Based on Reference Program Code from:
-        // https://www.waveshare.com/w/upload/a/ac/1.54inch_e-Paper_Module_C_Specification.pdf
-        // and:
-        // https://github.com/waveshare/e-Paper/blob/master/STM32/STM32-F103ZET6/User/e-Paper/EPD_1in54c.c
-        self.interface.reset(delay, 10_000, 2_000);
-
-        // start the booster
-        self.cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x17])?;
-
-        // power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_us(5000);
-        self.wait_until_idle(spi, delay)?;
-
-        // set the panel settings
-        self.cmd_with_data(spi, Command::PanelSetting, &[0x0f, 0x0d])?;
-
-        // set resolution
-        self.send_resolution(spi)?;
-
-        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x77])?;
-
-        Ok(())
-    }
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
@@ -93,9 +70,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_achromatic_frame(spi, delay, black)?;
         self.update_chromatic_frame(spi, delay, chromatic)
     }
@@ -104,8 +81,8 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error> {
+        BwPlane(black): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::DataStartTransmission1, black)?;
 
@@ -116,16 +93,24 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::DataStartTransmission2, chromatic)?;
 
         Ok(())
     }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
+
+    fn chromatic_kind(&self) -> TriColorKind {
+        TriColorKind::Yellow
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -135,42 +120,81 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
         let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
         let color = DEFAULT_BACKGROUND_COLOR;
 
-        let mut epd = Epd1in54c { interface, color };
+        Epd1in54c {
+            interface,
+            color,
+            planes: color.into(),
+        }
+    }
+}
 
-        epd.init(spi, delay)?;
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        // Based on Reference Program Code from:
+        // https://www.waveshare.com/w/upload/a/ac/1.54inch_e-Paper_Module_C_Specification.pdf
+        // and:
+        // https://github.com/waveshare/e-Paper/blob/master/STM32/STM32-F103ZET6/User/e-Paper/EPD_1in54c.c
+        self.interface.reset(delay, 10_000, 2_000);
 
-        Ok(epd)
-    }
+        // start the booster
+        self.cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x17])?;
 
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // power on
+        self.command(spi, Command::PowerOn)?;
+        delay.delay_us(5000);
         self.wait_until_idle(spi, delay)?;
 
-        self.command(spi, Command::PowerOff)?;
-        self.wait_until_idle(spi, delay)?;
-        self.cmd_with_data(spi, Command::DeepSleep, &[0xa5])?;
+        // set the panel settings
+        self.cmd_with_data(spi, Command::PanelSetting, &[0x0f, 0x0d])?;
+
+        // set resolution
+        self.send_resolution(spi)?;
+
+        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x77])?;
 
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 2_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
         self.init(spi, delay)
     }
 
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = Color;
+
     fn set_background_color(&mut self, color: Color) {
         self.color = color;
+        self.planes = color.into();
     }
 
     fn background_color(&self) -> &Color {
@@ -185,13 +209,17 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_achromatic_frame(spi, delay, buffer)?;
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_achromatic_frame(spi, delay, BwPlane(buffer))?;
 
         // Clear the chromatic layer
         let color = self.color.get_byte_value();
@@ -212,11 +240,53 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!()
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        let color = self.planes.achromatic.get_byte_value();
+        let chromatic = self.planes.chromatic_byte(0xFF);
+
+        // Clear the black
+        self.command(spi, Command::DataStartTransmission1)?;
+        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+
+        // Clear the chromatic
+        self.command(spi, Command::DataStartTransmission2)?;
+        self.interface
+            .data_x_times(spi, chromatic, NUM_DISPLAY_BITS)?;
+
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.command(spi, Command::DisplayRefresh)?;
         self.wait_until_idle(spi, delay)?;
 
@@ -228,41 +298,78 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
 
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        let color = DEFAULT_BACKGROUND_COLOR.get_byte_value();
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        Ok(())
+    }
 
-        // Clear the black
-        self.command(spi, Command::DataStartTransmission1)?;
-        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+    fn refresh_mode(&self) -> RefreshLut {
+        RefreshLut::Full
+    }
 
-        // Clear the chromatic
-        self.command(spi, Command::DataStartTransmission2)?;
-        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+    fn lut_source(&self) -> LutSource {
+        LutSource::Otp
+    }
 
-        Ok(())
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
     }
 
-    fn set_lut(
+    fn wait_until_idle_with(
         &mut self,
         _spi: &mut SPI,
-        _delay: &mut DELAY,
-        _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+
+        self.command(spi, Command::PowerOff)?;
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleep, &[0xa5])?;
+
         Ok(())
     }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54c<SPI, CS, BUSY, DC, RST, DELAY>
@@ -1,5 +1,6 @@
 //! SPI Commands for the Waveshare 2.13" v2
 
+use crate::error::Error;
 use crate::traits;
 
 extern crate bit_field;
@@ -89,7 +90,6 @@ impl DriverOutput {
 ///  | | `------------- load temp
 ///  | `--------------- enable clock
 ///  `----------------- enable analog
-
 pub(crate) struct DisplayUpdateControl2(pub u8);
 #[allow(dead_code)]
 impl DisplayUpdateControl2 {
@@ -133,6 +133,91 @@ impl DisplayUpdateControl2 {
     }
 }
 
+/// Frame rate of the refreshes, see [`Epd2in13::set_frame_timing`](crate::epd2in13_v2::Epd2in13::set_frame_timing)
+///
+/// A frame takes the time of the gate lines of the panel plus the dummy lines, each line as
+/// long as the gate line width. Shorter frames speed up the refresh, but the pixels may not
+/// reach their color and show more ghosting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameTiming {
+    /// Number of dummy lines per frame (0x3A), 0 to 127
+    pub dummy_line_period: u8,
+    /// Gate line width (0x3B), 0 to 15
+    pub gate_line_width: u8,
+}
+
+impl FrameTiming {
+    /// The timing of the Waveshare reference driver, about 50Hz
+    pub const DEFAULT: FrameTiming = FrameTiming {
+        dummy_line_period: 0x30,
+        gate_line_width: 10,
+    };
+}
+
+impl Default for FrameTiming {
+    fn default() -> Self {
+        FrameTiming::DEFAULT
+    }
+}
+
+/// Driving voltages of the panel in decivolts, see [`Epd2in13::set_driving_voltages`](crate::epd2in13_v2::Epd2in13::set_driving_voltages)
+///
+/// Higher voltages drive the pixels harder, which can give panels with washed out colors more
+/// contrast, at the cost of wearing the panel faster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DrivingVoltages {
+    /// Gate high voltage VGH (0x03), 100 to 210 in steps of 5
+    pub gate: i16,
+    /// Source high voltage VSH1 (0x04), 24 to 88 in steps of 1 or 90 to 180 in steps of 2
+    pub vsh1: i16,
+    /// Second source high voltage VSH2 (0x04), in the range of VSH1 and at most VSH1
+    pub vsh2: i16,
+    /// Source low voltage VSL (0x04), -90 to -180 in steps of 5
+    pub vsl: i16,
+}
+
+impl DrivingVoltages {
+    /// The voltages of the Waveshare reference driver
+    pub const DEFAULT: DrivingVoltages = DrivingVoltages {
+        gate: 190,
+        vsh1: 150,
+        vsh2: 50,
+        vsl: -150,
+    };
+
+    /// Checks the voltages against the ranges of the SSD1675B datasheet
+    pub fn is_valid(&self) -> bool {
+        let source_high =
+            |v: i16| (24..=88).contains(&v) || ((90..=180).contains(&v) && v % 2 == 0);
+        (100..=210).contains(&self.gate)
+            && self.gate % 5 == 0
+            && source_high(self.vsh1)
+            && source_high(self.vsh2)
+            && self.vsh2 <= self.vsh1
+            && (-180..=-90).contains(&self.vsl)
+            && self.vsl % 5 == 0
+    }
+}
+
+impl Default for DrivingVoltages {
+    fn default() -> Self {
+        DrivingVoltages::DEFAULT
+    }
+}
+
+/// A LUT for the full refreshes up to a temperature, see [`Epd2in13::set_temperature_luts`](crate::epd2in13_v2::Epd2in13::set_temperature_luts)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemperatureLut {
+    /// Highest temperature in °C the LUT is used for
+    pub max_temperature: i8,
+    /// The waveform, in the layout of [`LUT_FULL_UPDATE`](crate::epd2in13_v2::LUT_FULL_UPDATE)
+    pub lut: [u8; 70],
+}
+
 #[allow(dead_code, clippy::enum_variant_names)]
 pub(crate) enum DataEntryModeIncr {
     XDecrYDecr = 0x0,
@@ -153,6 +238,7 @@ pub(crate) enum BorderWaveFormVbd {
     Gs = 0x0,
     FixLevel = 0x1,
     Vcom = 0x2,
+    HiZ = 0x3,
 }
 
 #[allow(dead_code)]
@@ -205,16 +291,18 @@ pub(crate) struct SourceDrivingVoltage(pub u8);
 pub(crate) struct Vcom(pub u8);
 
 pub(crate) trait I32Ext {
-    fn vcom(self) -> Vcom;
-    fn gate_driving_decivolt(self) -> GateDrivingVoltage;
-    fn source_driving_decivolt(self) -> SourceDrivingVoltage;
+    fn vcom<E>(self) -> Result<Vcom, Error<E>>;
+    fn gate_driving_decivolt<E>(self) -> Result<GateDrivingVoltage, Error<E>>;
+    fn source_driving_decivolt<E>(self) -> Result<SourceDrivingVoltage, Error<E>>;
 }
 
 impl I32Ext for i32 {
     // This is really not very nice. Until I find something better, this will be
     // a placeholder.
-    fn vcom(self) -> Vcom {
-        assert!((-30..=-2).contains(&self));
+    fn vcom<E>(self) -> Result<Vcom, Error<E>> {
+        if !(-30..=-2).contains(&self) {
+            return Err(Error::OutOfRange);
+        }
         let u = match -self {
             2 => 0x08,
             3 => 0x0B,
@@ -247,24 +335,31 @@ impl I32Ext for i32 {
             30 => 0x78,
             _ => 0,
         };
-        Vcom(u)
+        Ok(Vcom(u))
     }
 
-    fn gate_driving_decivolt(self) -> GateDrivingVoltage {
-        assert!((100..=210).contains(&self) && self % 5 == 0);
-        GateDrivingVoltage(((self - 100) / 5 + 0x03) as u8)
+    fn gate_driving_decivolt<E>(self) -> Result<GateDrivingVoltage, Error<E>> {
+        if !((100..=210).contains(&self) && self % 5 == 0) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(GateDrivingVoltage(((self - 100) / 5 + 0x03) as u8))
     }
 
-    fn source_driving_decivolt(self) -> SourceDrivingVoltage {
-        assert!((24..=88).contains(&self) || (self % 5 == 0 && (90..=180).contains(&self.abs())));
+    fn source_driving_decivolt<E>(self) -> Result<SourceDrivingVoltage, Error<E>> {
+        if !((24..=88).contains(&self)
+            || (self % 2 == 0 && (90..=180).contains(&self))
+            || (self % 5 == 0 && (-180..=-90).contains(&self)))
+        {
+            return Err(Error::OutOfRange);
+        }
 
-        if (24..=88).contains(&self) {
+        Ok(if (24..=88).contains(&self) {
             SourceDrivingVoltage(((self - 24) + 0x8E) as u8)
         } else if (90..=180).contains(&self) {
             SourceDrivingVoltage(((self - 90) / 2 + 0x23) as u8)
         } else {
             SourceDrivingVoltage((((-self - 90) / 5) * 2 + 0x1A) as u8)
-        }
+        })
     }
 }
 
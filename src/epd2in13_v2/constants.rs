@@ -1,7 +1,8 @@
-#[rustfmt::skip]
-
 // Original Waveforms from Waveshare
-pub(crate) const LUT_FULL_UPDATE: [u8; 70] =[
+
+/// The full refresh LUT of Waveshare, used for `RefreshLut::Full`
+#[rustfmt::skip]
+pub const LUT_FULL_UPDATE: [u8; 70] =[
     0x80,0x60,0x40,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
     0x10,0x60,0x20,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
     0x80,0x60,0x40,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
@@ -17,8 +18,9 @@ pub(crate) const LUT_FULL_UPDATE: [u8; 70] =[
     0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
 ];
 
+/// The partial refresh LUT of Waveshare, used by default for `RefreshLut::Quick`
 #[rustfmt::skip]
-pub(crate) const LUT_PARTIAL_UPDATE: [u8; 70] =[
+pub const LUT_PARTIAL_UPDATE: [u8; 70] =[
     0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
     0x80,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
     0x40,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
@@ -33,3 +35,26 @@ pub(crate) const LUT_PARTIAL_UPDATE: [u8; 70] =[
     0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
     0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
 ];
+
+/// Partial refresh LUT with less ghosting than [`LUT_PARTIAL_UPDATE`], for use with
+/// `Epd2in13::set_partial_lut`
+///
+/// Changed pixels are driven for 4 more frames and unchanged pixels get a short pulse
+/// towards their current color, so the particles of pixels kept over many quick refreshes
+/// don't drift. A refresh takes about 40% longer.
+#[rustfmt::skip]
+pub const LUT_PARTIAL_UPDATE_LOW_GHOSTING: [u8; 70] =[
+    0x10,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
+    0xA0,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
+    0x50,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
+    0x20,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT3: WW:     VS 0 ~7
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT4: VCOM:   VS 0 ~7
+
+    0x0A,0x04,0x00,0x00,0x00,                       //  TP0 A~D RP0
+    0x00,0x00,0x00,0x00,0x00,                       //  TP1 A~D RP1
+    0x00,0x00,0x00,0x00,0x00,                       //  TP2 A~D RP2
+    0x00,0x00,0x00,0x00,0x00,                       //  TP3 A~D RP3
+    0x00,0x00,0x00,0x00,0x00,                       //  TP4 A~D RP4
+    0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
+    0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
+];
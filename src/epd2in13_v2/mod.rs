@@ -9,14 +9,22 @@
 //!
 
 use embedded_hal::{
-    blocking::{delay::*, spi::Write},
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
     digital::v2::{InputPin, OutputPin},
 };
 
 use crate::buffer_len;
 use crate::color::Color;
-use crate::interface::DisplayInterface;
-use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::lut_table::Runs;
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, OtpDiagnostics, PanelHealth, RefreshLut,
+};
 
 pub(crate) mod command;
 use self::command::{
@@ -24,9 +32,10 @@ use self::command::{
     DataEntryModeDir, DataEntryModeIncr, DeepSleepMode, DisplayUpdateControl2, DriverOutput,
     GateDrivingVoltage, I32Ext, SourceDrivingVoltage, Vcom,
 };
+pub use self::command::{DrivingVoltages, FrameTiming, TemperatureLut};
 
 pub(crate) mod constants;
-use self::constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE};
+pub use self::constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE, LUT_PARTIAL_UPDATE_LOW_GHOSTING};
 
 /// Full size buffer for use with the 2in13 v2 EPD
 #[cfg(feature = "graphics")]
@@ -46,6 +55,16 @@ pub const HEIGHT: u32 = 250;
 
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 2000,
+    min_refresh_interval_ms: 0,
+};
 const IS_BUSY_LOW: bool = false;
 
 /// Epd2in13 (V2) driver
@@ -56,12 +75,32 @@ pub struct Epd2in13<SPI, CS, BUSY, DC, RST, DELAY> {
 
     sleep_mode: DeepSleepMode,
 
+    /// The last deep sleep kept the RAM and registers, see [`Epd2in13::wake_up_warm`]
+    retained: bool,
+
     /// Background Color
     background_color: Color,
     refresh: RefreshLut,
+
+    /// Disable clock/analog after each quick refresh
+    power_down_between_updates: bool,
+
+    /// LUT used for quick refreshes
+    partial_lut: [u8; 70],
+
+    /// Hold the border at Hi-Z during quick refreshes
+    hold_border: bool,
+
+    /// Sent by `init`
+    frame_timing: FrameTiming,
+    /// Sent by `init`
+    driving_voltages: DrivingVoltages,
+
+    /// Full refresh LUTs selected by `display_frame_adaptive`, sorted by temperature
+    temperature_luts: &'static [TemperatureLut],
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -71,21 +110,56 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        Epd2in13 {
+            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
+            sleep_mode: DeepSleepMode::Mode1,
+            retained: false,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            power_down_between_updates: false,
+            partial_lut: LUT_PARTIAL_UPDATE,
+            hold_border: true,
+            frame_timing: FrameTiming::DEFAULT,
+            driving_voltages: DrivingVoltages::DEFAULT,
+            temperature_luts: &[],
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         // HW reset
         self.interface.reset(delay, 10_000, 10_000);
 
         if self.refresh == RefreshLut::Quick {
-            self.set_vcom_register(spi, (-9).vcom())?;
+            self.set_vcom_register(spi, (-9).vcom()?)?;
             self.wait_until_idle(spi, delay)?;
 
             self.set_lut(spi, delay, Some(self.refresh))?;
 
+            // The reference driver keeps the reset values here
+            if self.frame_timing != FrameTiming::DEFAULT {
+                self.send_frame_timing(spi)?;
+            }
+            if self.driving_voltages != DrivingVoltages::DEFAULT {
+                self.send_driving_voltages(spi)?;
+            }
+
             // Python code does this, not sure why
             // self.cmd_with_data(spi, Command::WriteOtpSelection, &[0, 0, 0, 0, 0x40, 0, 0])?;
 
             // During partial update, clock/analog are not disabled between 2
-            // updates.
+            // updates, unless `power_down_between_updates` is set.
             self.set_display_update_control_2(
                 spi,
                 DisplayUpdateControl2::new().enable_analog().enable_clock(),
@@ -93,14 +167,7 @@ where
             self.command(spi, Command::MasterActivation)?;
             self.wait_until_idle(spi, delay)?;
 
-            self.set_border_waveform(
-                spi,
-                BorderWaveForm {
-                    vbd: BorderWaveFormVbd::Gs,
-                    fix_level: BorderWaveFormFixLevel::Vss,
-                    gs_trans: BorderWaveFormGs::Lut1,
-                },
-            )?;
+            self.set_border_waveform(spi, self.border_waveform())?;
         } else {
             self.wait_until_idle(spi, delay)?;
             self.command(spi, Command::SwReset)?;
@@ -116,8 +183,8 @@ where
                 },
             )?;
 
-            // These 2 are the reset values
-            self.set_dummy_line_period(spi, 0x30)?;
+            // The gate scan start position is the reset value
+            self.set_dummy_line_period(spi, self.frame_timing.dummy_line_period)?;
             self.set_gate_scan_start_position(spi, 0)?;
 
             self.set_data_entry_mode(spi, DataEntryModeIncr::XIncrYIncr, DataEntryModeDir::XDir)?;
@@ -126,26 +193,13 @@ where
             self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
             self.set_ram_address_counters(spi, delay, 0, 0)?;
 
-            self.set_border_waveform(
-                spi,
-                BorderWaveForm {
-                    vbd: BorderWaveFormVbd::Gs,
-                    fix_level: BorderWaveFormFixLevel::Vss,
-                    gs_trans: BorderWaveFormGs::Lut3,
-                },
-            )?;
+            self.set_border_waveform(spi, self.border_waveform())?;
 
-            self.set_vcom_register(spi, (-21).vcom())?;
+            self.set_vcom_register(spi, (-21).vcom()?)?;
 
-            self.set_gate_driving_voltage(spi, 190.gate_driving_decivolt())?;
-            self.set_source_driving_voltage(
-                spi,
-                150.source_driving_decivolt(),
-                50.source_driving_decivolt(),
-                (-150).source_driving_decivolt(),
-            )?;
+            self.send_driving_voltages(spi)?;
 
-            self.set_gate_line_width(spi, 10)?;
+            self.set_gate_line_width(spi, self.frame_timing.gate_line_width)?;
 
             self.set_lut(spi, delay, Some(self.refresh))?;
         }
@@ -153,9 +207,20 @@ where
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -166,55 +231,14 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let mut epd = Epd2in13 {
-            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
-            sleep_mode: DeepSleepMode::Mode1,
-            background_color: DEFAULT_BACKGROUND_COLOR,
-            refresh: RefreshLut::Full,
-        };
-
-        epd.init(spi, delay)?;
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-
-        // All sample code enables and disables analog/clocks...
-        self.set_display_update_control_2(
-            spi,
-            DisplayUpdateControl2::new()
-                .enable_analog()
-                .enable_clock()
-                .disable_analog()
-                .disable_clock(),
-        )?;
-        self.command(spi, Command::MasterActivation)?;
-
-        self.set_sleep_mode(spi, self.sleep_mode)?;
-        Ok(())
-    }
 
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        assert!(buffer.len() == buffer_len(WIDTH as usize, HEIGHT as usize));
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
         self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
         self.set_ram_address_counters(spi, delay, 0, 0)?;
 
@@ -231,8 +255,8 @@ where
     }
 
     /// Updating only a part of the frame is not supported when using the
-    /// partial refresh feature. The function will panic if called when set to
-    /// use partial refresh.
+    /// partial refresh feature. The function returns [`Error::Unsupported`] if called
+    /// when set to use partial refresh.
     fn update_partial_frame(
         &mut self,
         spi: &mut SPI,
@@ -242,16 +266,17 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        assert!((width * height / 8) as usize == buffer.len());
-
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         // This should not be used when doing partial refresh. The RAM_RED must
         // be updated with the last buffer having been displayed. Doing partial
         // update directly in RAM makes this update impossible (we can't read
         // RAM content). Using this function will most probably make the actual
         // display incorrect as the controler will compare with something
         // incorrect.
-        assert!(self.refresh == RefreshLut::Full);
+        if self.refresh != RefreshLut::Full {
+            return Err(Error::Unsupported);
+        }
 
         self.set_ram_area(spi, x, y, x + width, y + height)?;
         self.set_ram_address_counters(spi, delay, x, y)?;
@@ -269,44 +294,7 @@ where
         Ok(())
     }
 
-    /// Never use directly this function when using partial refresh, or also
-    /// keep the base buffer in syncd using `set_partial_base_buffer` function.
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        if self.refresh == RefreshLut::Full {
-            self.set_display_update_control_2(
-                spi,
-                DisplayUpdateControl2::new()
-                    .enable_clock()
-                    .enable_analog()
-                    .display()
-                    .disable_analog()
-                    .disable_clock(),
-            )?;
-        } else {
-            self.set_display_update_control_2(spi, DisplayUpdateControl2::new().display())?;
-        }
-        self.command(spi, Command::MasterActivation)?;
-        self.wait_until_idle(spi, delay)?;
-
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.display_frame(spi, delay)?;
-
-        if self.refresh == RefreshLut::Quick {
-            self.set_partial_base_buffer(spi, delay, buffer)?;
-        }
-        Ok(())
-    }
-
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         let color = self.background_color.get_byte_value();
 
         self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
@@ -350,22 +338,150 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Never use directly this function when using partial refresh, or also
+    /// keep the base buffer in syncd using `set_partial_base_buffer` function.
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        if self.refresh == RefreshLut::Full || self.power_down_between_updates {
+            self.set_display_update_control_2(
+                spi,
+                DisplayUpdateControl2::new()
+                    .enable_clock()
+                    .enable_analog()
+                    .display()
+                    .disable_analog()
+                    .disable_clock(),
+            )?;
+        } else {
+            self.set_display_update_control_2(spi, DisplayUpdateControl2::new().display())?;
+        }
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+
+        if self.refresh == RefreshLut::Quick {
+            self.set_partial_base_buffer(spi, delay, buffer)?;
+        }
+        Ok(())
+    }
+
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         _delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         let buffer = match refresh_rate {
-            Some(RefreshLut::Full) | None => &LUT_FULL_UPDATE,
-            Some(RefreshLut::Quick) => &LUT_PARTIAL_UPDATE,
+            Some(RefreshLut::Full) | None => LUT_FULL_UPDATE,
+            Some(RefreshLut::Quick) => self.partial_lut,
         };
 
-        self.cmd_with_data(spi, Command::WriteLutRegister, buffer)
+        self.cmd_with_data(spi, Command::WriteLutRegister, &buffer)?;
+        Ok(())
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+
+        // All sample code enables and disables analog/clocks...
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .enable_analog()
+                .enable_clock()
+                .disable_analog()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+
+        self.set_sleep_mode(spi, self.sleep_mode)?;
+        self.retained = matches!(self.sleep_mode, DeepSleepMode::Mode1);
         Ok(())
     }
 }
@@ -386,8 +502,8 @@ where
         spi: &mut SPI,
         delay: &mut DELAY,
         buffer: &[u8],
-    ) -> Result<(), SPI::Error> {
-        assert!(buffer_len(WIDTH as usize, HEIGHT as usize) == buffer.len());
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
         self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
         self.set_ram_address_counters(spi, delay, 0, 0)?;
 
@@ -395,11 +511,115 @@ where
         Ok(())
     }
 
+    /// Same as [`update_frame`](EpdUpdate::update_frame) with a run length encoded frame,
+    /// see `graphics::encode_rle`
+    ///
+    /// The frame is expanded while it is sent, so it is never kept in memory as a whole.
+    pub fn update_frame_rle(
+        &mut self,
+        spi: &mut SPI,
+        encoded: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize);
+        crate::check_rle_len(encoded, len)?;
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        self.set_ram_address_counters(spi, delay, 0, 0)?;
+
+        self.command(spi, Command::WriteRam)?;
+        self.interface.data_rle(spi, &mut Runs::new(encoded), len)?;
+
+        if self.refresh == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+            self.set_ram_address_counters(spi, delay, 0, 0)?;
+
+            self.command(spi, Command::WriteRamRed)?;
+            self.interface.data_rle(spi, &mut Runs::new(encoded), len)?;
+        }
+        Ok(())
+    }
+
     /// Selects which sleep mode will be used when triggering the deep sleep.
     pub fn set_deep_sleep_mode(&mut self, mode: DeepSleepMode) {
         self.sleep_mode = mode;
     }
 
+    /// Wakes the display up from deep sleep without re-running the full initialisation.
+    ///
+    /// After a `DeepSleepMode::Mode1` sleep the controller keeps its RAM and registers, so
+    /// only the HW reset, VCOM, border waveform and LUT are sent, leaving out the SW reset,
+    /// driver output, RAM window and driving voltages. Falls back to
+    /// [`wake_up`](EpdPower::wake_up) if the display wasn't put to sleep in that mode.
+    pub fn wake_up_warm(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        if !self.retained {
+            return self.wake_up(spi, delay);
+        }
+        self.retained = false;
+
+        self.interface.reset(delay, 10_000, 10_000);
+        self.send_warm_settings(spi, delay)
+    }
+
+    /// Wakes the display up from a `DeepSleepMode::Mode1` sleep and refreshes it with the
+    /// frame kept in its RAM, without sending the frame again.
+    ///
+    /// This suits devices that periodically wake up only to re-drive the same image, e.g.
+    /// against temperature drift. The refresh always uses the full LUT, as the quick one
+    /// leaves unchanged pixels alone, and the refresh mode is restored afterwards. Returns
+    /// [`Error::Unsupported`] if the display wasn't put to sleep in that mode.
+    pub fn resume_and_refresh(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        if !self.retained {
+            return Err(Error::Unsupported);
+        }
+        self.retained = false;
+
+        self.interface.reset(delay, 10_000, 10_000);
+        let refresh = self.refresh;
+        self.refresh = RefreshLut::Full;
+        self.send_warm_settings(spi, delay)?;
+        self.display_frame(spi, delay)?;
+
+        self.refresh = refresh;
+        if refresh == RefreshLut::Quick {
+            self.send_warm_settings(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Sends the settings of the refresh mode which don't survive a HW reset
+    fn send_warm_settings(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        let vcom = match self.refresh {
+            RefreshLut::Full => (-21).vcom()?,
+            RefreshLut::Quick => (-9).vcom()?,
+        };
+        self.set_vcom_register(spi, vcom)?;
+        self.set_border_waveform(spi, self.border_waveform())?;
+        self.set_lut(spi, delay, Some(self.refresh))?;
+
+        if self.refresh == RefreshLut::Quick {
+            self.set_display_update_control_2(
+                spi,
+                DisplayUpdateControl2::new().enable_analog().enable_clock(),
+            )?;
+            self.command(spi, Command::MasterActivation)?;
+        }
+
+        self.wait_until_idle(spi, delay)
+    }
+
     /// Sets the refresh mode. When changing mode, the screen will be
     /// re-initialized accordingly.
     pub fn set_refresh(
@@ -407,7 +627,7 @@ where
         spi: &mut SPI,
         delay: &mut DELAY,
         refresh: RefreshLut,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         if self.refresh != refresh {
             self.refresh = refresh;
             self.init(spi, delay)?;
@@ -415,17 +635,172 @@ where
         Ok(())
     }
 
+    /// Replaces the LUT used for quick refreshes, [`LUT_PARTIAL_UPDATE`] by default.
+    ///
+    /// [`LUT_PARTIAL_UPDATE_LOW_GHOSTING`] can be used to reduce ghosting after many quick
+    /// refreshes. The LUT is loaded right away if the quick refresh mode is selected.
+    pub fn set_partial_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        lut: &[u8; 70],
+    ) -> Result<(), Error<SPI::Error>> {
+        self.partial_lut = *lut;
+        if self.refresh == RefreshLut::Quick {
+            self.wait_until_idle(spi, delay)?;
+            self.set_lut(spi, delay, Some(RefreshLut::Quick))?;
+        }
+        Ok(())
+    }
+
+    /// Selects whether the border is held at Hi-Z during quick refreshes, the default
+    ///
+    /// A held border keeps its color instead of flickering with every quick refresh. Full
+    /// refreshes always drive the border. Without holding it, quick refreshes drive the border
+    /// like the Waveshare reference driver does.
+    pub fn set_hold_border(&mut self, spi: &mut SPI, hold: bool) -> Result<(), SPI::Error> {
+        self.hold_border = hold;
+        if self.refresh == RefreshLut::Quick {
+            self.set_border_waveform(spi, self.border_waveform())?;
+        }
+        Ok(())
+    }
+
+    /// Border waveform of the refresh mode
+    fn border_waveform(&self) -> BorderWaveForm {
+        let (vbd, gs_trans) = match self.refresh {
+            RefreshLut::Full => (BorderWaveFormVbd::Gs, BorderWaveFormGs::Lut3),
+            RefreshLut::Quick if self.hold_border => {
+                (BorderWaveFormVbd::HiZ, BorderWaveFormGs::Lut1)
+            }
+            RefreshLut::Quick => (BorderWaveFormVbd::Gs, BorderWaveFormGs::Lut1),
+        };
+        BorderWaveForm {
+            vbd,
+            fix_level: BorderWaveFormFixLevel::Vss,
+            gs_trans,
+        }
+    }
+
+    /// Sets the frame rate of the following refreshes, trading speed for image quality
+    ///
+    /// The timing is kept over `init` and `wake_up`. Returns [`Error::OutOfRange`] without
+    /// sending anything if the dummy line period is above 127.
+    pub fn set_frame_timing(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timing: FrameTiming,
+    ) -> Result<(), Error<SPI::Error>> {
+        if timing.dummy_line_period > 127 {
+            return Err(Error::OutOfRange);
+        }
+        self.frame_timing = timing;
+        self.wait_until_idle(spi, delay)?;
+        self.send_frame_timing(spi)?;
+        Ok(())
+    }
+
+    /// Returns the timing set with [`set_frame_timing`](Epd2in13::set_frame_timing)
+    pub fn frame_timing(&self) -> FrameTiming {
+        self.frame_timing
+    }
+
+    fn send_frame_timing(&mut self, spi: &mut SPI) -> Result<(), Error<SPI::Error>> {
+        self.set_dummy_line_period(spi, self.frame_timing.dummy_line_period)?;
+        self.set_gate_line_width(spi, self.frame_timing.gate_line_width)?;
+        Ok(())
+    }
+
+    /// Sets the gate and source driving voltages, e.g. to give a washed out panel more contrast
+    ///
+    /// Returns [`Error::OutOfRange`] without sending anything if the voltages are outside of
+    /// the ranges of the controller, see [`DrivingVoltages::is_valid`]. The voltages are kept
+    /// over `init` and `wake_up`.
+    pub fn set_driving_voltages(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        voltages: DrivingVoltages,
+    ) -> Result<(), Error<SPI::Error>> {
+        if !voltages.is_valid() {
+            return Err(Error::OutOfRange);
+        }
+        self.driving_voltages = voltages;
+        self.wait_until_idle(spi, delay)?;
+        self.send_driving_voltages(spi)?;
+        Ok(())
+    }
+
+    /// Returns the voltages set with [`set_driving_voltages`](Epd2in13::set_driving_voltages)
+    pub fn driving_voltages(&self) -> DrivingVoltages {
+        self.driving_voltages
+    }
+
+    fn send_driving_voltages(&mut self, spi: &mut SPI) -> Result<(), Error<SPI::Error>> {
+        let voltages = self.driving_voltages;
+        self.set_gate_driving_voltage(spi, i32::from(voltages.gate).gate_driving_decivolt()?)?;
+        self.set_source_driving_voltage(
+            spi,
+            i32::from(voltages.vsh1).source_driving_decivolt()?,
+            i32::from(voltages.vsh2).source_driving_decivolt()?,
+            i32::from(voltages.vsl).source_driving_decivolt()?,
+        )?;
+        Ok(())
+    }
+
+    /// Sets the LUTs [`display_frame_adaptive`](Epd2in13::display_frame_adaptive) picks from
+    /// by the temperature of the panel
+    ///
+    /// The LUTs have to be sorted by their `max_temperature`. The first one covering the
+    /// measured temperature is used, the last one above all of them. With no LUTs, the
+    /// default, [`LUT_FULL_UPDATE`] is always used.
+    pub fn set_temperature_luts(&mut self, luts: &'static [TemperatureLut]) {
+        self.temperature_luts = luts;
+    }
+
+    /// Selects whether the clock and analog blocks are disabled after each quick refresh.
+    ///
+    /// Full refreshes always disable them. During quick refreshes they are kept running by
+    /// default, which makes successive updates faster but increases the idle current.
+    pub fn set_power_down_between_updates(&mut self, power_down: bool) {
+        self.power_down_between_updates = power_down;
+    }
+
+    /// Disables the clock and analog blocks of the controller without entering deep sleep.
+    ///
+    /// RAM and registers are kept and no reset is needed afterwards, the next refresh
+    /// enables the blocks again as needed.
+    pub fn low_power_idle(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .disable_analog()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
     fn set_gate_scan_start_position(
         &mut self,
         spi: &mut SPI,
         start: u16,
-    ) -> Result<(), SPI::Error> {
-        assert!(start <= 295);
+    ) -> Result<(), Error<SPI::Error>> {
+        if start > 295 {
+            return Err(Error::OutOfRange);
+        }
         self.cmd_with_data(
             spi,
             Command::GateScanStartPosition,
             &[(start & 0xFF) as u8, ((start >> 8) & 0x1) as u8],
-        )
+        )?;
+        Ok(())
     }
 
     fn set_border_waveform(
@@ -456,9 +831,12 @@ where
         &mut self,
         spi: &mut SPI,
         number_of_lines: u8,
-    ) -> Result<(), SPI::Error> {
-        assert!(number_of_lines <= 127);
-        self.cmd_with_data(spi, Command::SetDummyLinePeriod, &[number_of_lines])
+    ) -> Result<(), Error<SPI::Error>> {
+        if number_of_lines > 127 {
+            return Err(Error::OutOfRange);
+        }
+        self.cmd_with_data(spi, Command::SetDummyLinePeriod, &[number_of_lines & 0x7F])?;
+        Ok(())
     }
 
     fn set_gate_line_width(&mut self, spi: &mut SPI, width: u8) -> Result<(), SPI::Error> {
@@ -545,7 +923,7 @@ where
         delay: &mut DELAY,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
 
@@ -571,6 +949,178 @@ where
     }
 }
 
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Runs the VCI detection and reads the low-voltage flag of the controller
+    ///
+    /// The SSD1680 has no panel-break detection, so `panel_break` is always `None`.
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the status register.
+    pub fn health(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<PanelHealth, Error<<SPI as Write<u8>>::Error>> {
+        let mut status = [0u8];
+
+        self.wait_until_idle(spi, delay)?;
+        // Detection level of 2.3V
+        self.cmd_with_data(spi, Command::VciDetection, &[0x04])?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_read(spi, Command::StatusBitRead, &mut status)?;
+
+        Ok(PanelHealth {
+            panel_break: None,
+            // Bit 4 is set if VCI is lower than the detection level
+            low_voltage: status[0] & 0x10 != 0,
+        })
+    }
+
+    /// Reads the waveform version and the VCOM value stored in the OTP
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the OTP registers.
+    pub fn otp_diagnostics(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<OtpDiagnostics, Error<<SPI as Write<u8>>::Error>> {
+        let mut display_option = [0u8; 11];
+
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_read(spi, Command::OtpRegisterRead, &mut display_option)?;
+
+        Ok(otp_diagnostics(display_option))
+    }
+
+    /// Measures the temperature of the panel with the internal sensor, in °C rounded down
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the temperature register.
+    pub fn read_temperature(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<i8, Error<<SPI as Write<u8>>::Error>> {
+        let mut temperature = [0u8; 2];
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .enable_clock()
+                .load_temp()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_read(spi, Command::TemperatureSensorControlRead, &mut temperature)?;
+
+        Ok(degrees(temperature))
+    }
+
+    /// Refreshes like [`display_frame`](EpdRefresh::display_frame), after loading the LUT
+    /// for the temperature of the panel
+    ///
+    /// The LUT is picked from the ones set with
+    /// [`set_temperature_luts`](Epd2in13::set_temperature_luts) and stays loaded until the
+    /// next `init`. Quick refreshes keep their LUT and don't measure the temperature.
+    pub fn display_frame_adaptive(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<<SPI as Write<u8>>::Error>> {
+        if self.refresh == RefreshLut::Full && !self.temperature_luts.is_empty() {
+            let temperature = self.read_temperature(spi, delay)?;
+            let lut = select_lut(self.temperature_luts, temperature);
+            self.cmd_with_data(spi, Command::WriteLutRegister, lut)?;
+        }
+        self.display_frame(spi, delay)
+    }
+
+    /// Reads back the black/white RAM and compares it with `buffer`
+    ///
+    /// Returns [`Error::VerificationFailed`] if the RAM differs, e.g. after a transfer
+    /// corrupted by a noisy bus, so the frame can be sent again before refreshing.
+    /// The SSD1680 has no CRC of its RAM, so the whole frame is read back.
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the RAM.
+    pub fn verify_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<<SPI as Write<u8>>::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        self.wait_until_idle(spi, delay)?;
+        // Read the black/white RAM
+        self.cmd_with_data(spi, Command::ReadRamOption, &[0x00])?;
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        self.set_ram_address_counters(spi, delay, 0, 0)?;
+
+        // The first byte read after the command is a dummy byte
+        if self
+            .interface
+            .cmd_verify(spi, Command::ReadRam, 1, buffer)?
+        {
+            Ok(())
+        } else {
+            Err(Error::VerificationFailed)
+        }
+    }
+
+    /// Same as [`update_frame`](EpdUpdate::update_frame), reading the RAM back
+    /// afterwards with [`verify_frame`](Epd2in13::verify_frame)
+    pub fn update_frame_verified(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<<SPI as Write<u8>>::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.verify_frame(spi, buffer, delay)
+    }
+}
+
+/// Picks the values out of the display option registers
+///
+/// These are the VCOM OTP selection, the VCOM register, 5 bytes of display mode and
+/// 4 bytes of waveform version.
+fn otp_diagnostics(display_option: [u8; 11]) -> OtpDiagnostics {
+    let mut version = [0u8; 4];
+    version.copy_from_slice(&display_option[7..]);
+    OtpDiagnostics {
+        waveform_version: Some(u32::from_be_bytes(version)),
+        vcom: Some(display_option[1]),
+    }
+}
+
+/// Converts the 12 bit temperature register, in 1/16 °C, to °C
+fn degrees(register: [u8; 2]) -> i8 {
+    (i16::from_be_bytes(register) >> 8) as i8
+}
+
+/// The LUT of the first entry covering `temperature`, or of the last one
+fn select_lut(luts: &[TemperatureLut], temperature: i8) -> &[u8; 70] {
+    let lut = luts
+        .iter()
+        .find(|lut| temperature <= lut.max_temperature)
+        .or(luts.last());
+    lut.map_or(&LUT_FULL_UPDATE, |lut| &lut.lut)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -581,4 +1131,285 @@ mod tests {
         assert_eq!(HEIGHT, 250);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[test]
+    fn health() {
+        extern crate std;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::{vec, vec::Vec};
+
+        let read_health = |status: u8| {
+            let mut spi = spi::Mock::new(&[
+                spi::Transaction::write(vec![0x15]),
+                spi::Transaction::write(vec![0x04]),
+                spi::Transaction::write(vec![0x2F]),
+                spi::Transaction::transfer(vec![0x00], vec![status]),
+            ]);
+            let toggles = (0..4)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<Vec<_>>();
+            let mut cs = Mock::new(&toggles);
+            let mut dc = Mock::new(&[
+                Transaction::set(State::Low),
+                Transaction::set(State::High),
+                Transaction::set(State::Low),
+                Transaction::set(State::High),
+            ]);
+            let mut busy = Mock::new(&vec![Transaction::get(State::Low); 2]);
+            let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+                Epd2in13::new_uninitialized(
+                    cs.clone(),
+                    busy.clone(),
+                    dc.clone(),
+                    Mock::new(&[]),
+                    None,
+                );
+
+            let health = epd.health(&mut spi, &mut MockNoop::new()).unwrap();
+
+            spi.done();
+            cs.done();
+            dc.done();
+            busy.done();
+            health
+        };
+
+        let health = read_health(0x00);
+        assert_eq!(health.panel_break, None);
+        assert!(!health.low_voltage);
+        assert!(health.is_ok());
+
+        let health = read_health(0x10);
+        assert!(health.low_voltage);
+        assert!(!health.is_ok());
+
+        // Only bit 4 is the VCI flag
+        assert!(!read_health(0x2F).low_voltage);
+    }
+
+    #[test]
+    fn default_frame_timing() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(epd.frame_timing(), FrameTiming::default());
+        assert_eq!(FrameTiming::DEFAULT.dummy_line_period, 0x30);
+        assert_eq!(FrameTiming::DEFAULT.gate_line_width, 10);
+    }
+
+    #[test]
+    fn driving_voltages() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        assert!(DrivingVoltages::DEFAULT.is_valid());
+        for voltages in [
+            DrivingVoltages {
+                gate: 95,
+                ..DrivingVoltages::DEFAULT
+            },
+            DrivingVoltages {
+                gate: 192,
+                ..DrivingVoltages::DEFAULT
+            },
+            DrivingVoltages {
+                vsh1: 151,
+                ..DrivingVoltages::DEFAULT
+            },
+            DrivingVoltages {
+                vsh2: 160,
+                ..DrivingVoltages::DEFAULT
+            },
+            DrivingVoltages {
+                vsl: -152,
+                ..DrivingVoltages::DEFAULT
+            },
+            DrivingVoltages {
+                vsl: 150,
+                ..DrivingVoltages::DEFAULT
+            },
+        ] {
+            assert!(!voltages.is_valid(), "{:?}", voltages);
+        }
+
+        // rejected voltages aren't sent
+        let pin = || Mock::new(&[]);
+        let mut spi = spi::Mock::new(&[]);
+        let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        let voltages = DrivingVoltages {
+            gate: 220,
+            ..DrivingVoltages::DEFAULT
+        };
+        assert_eq!(
+            epd.set_driving_voltages(&mut spi, &mut MockNoop::new(), voltages),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(epd.driving_voltages(), DrivingVoltages::DEFAULT);
+        spi.done();
+    }
+
+    #[test]
+    fn out_of_range_values() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi, MockError};
+
+        assert_eq!(
+            (-1).vcom::<MockError>().map(|vcom| vcom.0),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!((-21).vcom::<MockError>().map(|vcom| vcom.0), Ok(0x54));
+        assert_eq!(
+            192.gate_driving_decivolt::<MockError>()
+                .map(|voltage| voltage.0),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            (-95)
+                .source_driving_decivolt::<MockError>()
+                .map(|voltage| voltage.0),
+            Ok(0x1C)
+        );
+        assert_eq!(
+            (-96)
+                .source_driving_decivolt::<MockError>()
+                .map(|voltage| voltage.0),
+            Err(Error::OutOfRange)
+        );
+
+        // rejected timings aren't sent
+        let pin = || Mock::new(&[]);
+        let mut spi = spi::Mock::new(&[]);
+        let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        let timing = FrameTiming {
+            dummy_line_period: 128,
+            ..FrameTiming::DEFAULT
+        };
+        assert_eq!(
+            epd.set_frame_timing(&mut spi, &mut MockNoop::new(), timing),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(epd.frame_timing(), FrameTiming::DEFAULT);
+        spi.done();
+    }
+
+    #[test]
+    fn otp_register() {
+        let display_option = [0x00, 0x50, 0, 0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78];
+        assert_eq!(
+            otp_diagnostics(display_option),
+            OtpDiagnostics {
+                waveform_version: Some(0x1234_5678),
+                vcom: Some(0x50),
+            }
+        );
+    }
+
+    #[test]
+    fn temperature_luts() {
+        assert_eq!(degrees([0x19, 0x00]), 25);
+        assert_eq!(degrees([0x19, 0xF0]), 25);
+        assert_eq!(degrees([0xF6, 0x00]), -10);
+
+        let cold = TemperatureLut {
+            max_temperature: 5,
+            lut: [1; 70],
+        };
+        let normal = TemperatureLut {
+            max_temperature: 30,
+            lut: LUT_FULL_UPDATE,
+        };
+        let luts = [cold, normal];
+        assert_eq!(select_lut(&luts, -10), &cold.lut);
+        assert_eq!(select_lut(&luts, 5), &cold.lut);
+        assert_eq!(select_lut(&luts, 6), &normal.lut);
+        assert_eq!(select_lut(&luts, 40), &normal.lut);
+        assert_eq!(select_lut(&[], 0), &LUT_FULL_UPDATE);
+    }
+
+    #[test]
+    fn poll_hook_runs_during_display_frame() {
+        extern crate std;
+        use crate::traits::EpdHooks;
+        use core::sync::atomic::{AtomicU32, Ordering};
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        static POLLS: AtomicU32 = AtomicU32::new(0);
+        fn feed_watchdog() {
+            POLLS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![0x22]),
+            spi::Transaction::write(vec![0xC7]),
+            spi::Transaction::write(vec![0x20]),
+        ]);
+        let toggles = |n| {
+            (0..n)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<vec::Vec<_>>()
+        };
+        let mut cs = Mock::new(&toggles(3));
+        let mut dc = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+            Transaction::set(State::Low),
+        ]);
+        let mut busy = Mock::new(&[
+            Transaction::get(State::High),
+            Transaction::get(State::High),
+            Transaction::get(State::High),
+            Transaction::get(State::Low),
+        ]);
+        let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(cs.clone(), busy.clone(), dc.clone(), Mock::new(&[]), None);
+        epd.set_poll_hook(Some(&feed_watchdog));
+
+        epd.display_frame(&mut spi, &mut MockNoop::new()).unwrap();
+        assert_eq!(POLLS.load(Ordering::Relaxed), 3);
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+    }
+
+    #[test]
+    fn border_waveform() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(epd.border_waveform().to_u8(), 0x03);
+        epd.refresh = RefreshLut::Quick;
+        assert_eq!(epd.border_waveform().to_u8(), 0xC1);
+        epd.hold_border = false;
+        assert_eq!(epd.border_waveform().to_u8(), 0x01);
+    }
+
+    #[test]
+    fn resume_needs_retained_ram() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut spi = spi::Mock::new(&[]);
+        let mut epd: Epd2in13<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(
+            epd.resume_and_refresh(&mut spi, &mut MockNoop::new()),
+            Err(Error::Unsupported)
+        );
+        spi.done();
+    }
 }
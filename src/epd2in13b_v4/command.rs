@@ -1,5 +1,6 @@
 //! SPI Commands for the Waveshare 2.13"B V4 E-Ink Display
 
+use crate::error::Error;
 use crate::traits;
 
 extern crate bit_field;
@@ -16,6 +17,8 @@ pub(crate) enum Command {
     DataEntryModeSetting = 0x11,
     SwReset = 0x12,
     TemperatureSensorRead = 0x18,
+    TemperatureSensorControlWrite = 0x1A,
+    TemperatureSensorControlRead = 0x1B,
     MasterActivation = 0x20,
     DisplayUpdateControl1 = 0x21,
     WriteRam = 0x24,
@@ -68,7 +71,6 @@ impl DriverOutput {
 ///  | | `------------- load temp
 ///  | `--------------- enable clock
 ///  `----------------- enable analog
-
 pub(crate) struct DisplayUpdateControl2(pub u8);
 #[allow(dead_code)]
 impl DisplayUpdateControl2 {
@@ -112,6 +114,66 @@ impl DisplayUpdateControl2 {
     }
 }
 
+/// How the content of a RAM is used by the next refresh, see [`DisplayUpdateControl1`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RamOption {
+    /// The content as it is
+    #[default]
+    Normal = 0b0000,
+    /// The RAM is ignored and read as 0
+    Bypass = 0b0100,
+    /// The inverted content
+    Inverse = 0b1000,
+}
+
+/// Data of DisplayUpdateControl1 (0x21)
+///
+/// A7:4 option of the red RAM, A3:0 option of the black/white RAM,
+/// B7 source outputs S8 to S167 instead of S0 to S175.
+///
+/// `Default` is the setting of the driver: the red RAM inverted, as the chromatic plane is
+/// 0 for red pixels, and S8 to S167 for the 122 source lines of the panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayUpdateControl1([u8; 2]);
+
+impl DisplayUpdateControl1 {
+    /// Both RAMs as they are and all source outputs
+    pub fn new() -> DisplayUpdateControl1 {
+        DisplayUpdateControl1([0x00, 0x00])
+    }
+
+    /// How the red RAM is used
+    pub fn red_ram(mut self, option: RamOption) -> Self {
+        self.0[0].set_bits(4..8, option as u8);
+        self
+    }
+
+    /// How the black/white RAM is used
+    pub fn bw_ram(mut self, option: RamOption) -> Self {
+        self.0[0].set_bits(0..4, option as u8);
+        self
+    }
+
+    /// Drive the panel with the source outputs S8 to S167 only
+    pub fn narrow_source_output(mut self, narrow: bool) -> Self {
+        self.0[1].set_bit(7, narrow);
+        self
+    }
+
+    /// The data bytes of the command
+    pub fn to_bytes(self) -> [u8; 2] {
+        self.0
+    }
+}
+
+impl Default for DisplayUpdateControl1 {
+    fn default() -> Self {
+        DisplayUpdateControl1::new()
+            .red_ram(RamOption::Inverse)
+            .narrow_source_output(true)
+    }
+}
+
 #[allow(dead_code, clippy::enum_variant_names)]
 pub(crate) enum DataEntryModeIncr {
     XDecrYDecr = 0x0,
@@ -179,22 +241,27 @@ pub enum DeepSleepMode {
     Mode2 = 0x11,
 }
 
+#[allow(dead_code)]
 pub(crate) struct GateDrivingVoltage(pub u8);
+#[allow(dead_code)]
 pub(crate) struct SourceDrivingVoltage(pub u8);
+#[allow(dead_code)]
 pub(crate) struct Vcom(pub u8);
 
+#[allow(dead_code)]
 pub(crate) trait I32Ext {
-    fn vcom(self) -> Vcom;
-    fn gate_driving_decivolt(self) -> GateDrivingVoltage;
-    fn source_driving_decivolt(self) -> SourceDrivingVoltage;
+    fn vcom<E>(self) -> Result<Vcom, Error<E>>;
+    fn gate_driving_decivolt<E>(self) -> Result<GateDrivingVoltage, Error<E>>;
+    fn source_driving_decivolt<E>(self) -> Result<SourceDrivingVoltage, Error<E>>;
 }
 
-
 impl I32Ext for i32 {
     // This is really not very nice. Until I find something better, this will be
     // a placeholder.
-    fn vcom(self) -> Vcom {
-        assert!((-30..=-2).contains(&self));
+    fn vcom<E>(self) -> Result<Vcom, Error<E>> {
+        if !(-30..=-2).contains(&self) {
+            return Err(Error::OutOfRange);
+        }
         let u = match -self {
             2 => 0x08,
             3 => 0x0B,
@@ -227,24 +294,28 @@ impl I32Ext for i32 {
             30 => 0x78,
             _ => 0,
         };
-        Vcom(u)
+        Ok(Vcom(u))
     }
 
-    fn gate_driving_decivolt(self) -> GateDrivingVoltage {
-        assert!((100..=210).contains(&self) && self % 5 == 0);
-        GateDrivingVoltage(((self - 100) / 5 + 0x03) as u8)
+    fn gate_driving_decivolt<E>(self) -> Result<GateDrivingVoltage, Error<E>> {
+        if !((100..=210).contains(&self) && self % 5 == 0) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(GateDrivingVoltage(((self - 100) / 5 + 0x03) as u8))
     }
 
-    fn source_driving_decivolt(self) -> SourceDrivingVoltage {
-        assert!((24..=88).contains(&self) || (self % 5 == 0 && (90..=180).contains(&self.abs())));
+    fn source_driving_decivolt<E>(self) -> Result<SourceDrivingVoltage, Error<E>> {
+        if !((24..=88).contains(&self) || (self % 5 == 0 && (90..=180).contains(&self.abs()))) {
+            return Err(Error::OutOfRange);
+        }
 
-        if (24..=88).contains(&self) {
+        Ok(if (24..=88).contains(&self) {
             SourceDrivingVoltage(((self - 24) + 0x8E) as u8)
         } else if (90..=180).contains(&self) {
             SourceDrivingVoltage(((self - 90) / 2 + 0x23) as u8)
         } else {
             SourceDrivingVoltage((((-self - 90) / 5) * 2 + 0x1A) as u8)
-        }
+        })
     }
 }
 
@@ -254,4 +325,3 @@ impl traits::Command for Command {
         self as u8
     }
 }
-
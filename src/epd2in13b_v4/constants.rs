@@ -14,6 +14,7 @@ pub(crate) const LUT_FULL_UPDATE: [u8; 70] =[
     0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
     0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
 ];
+#[rustfmt::skip]
 pub(crate) const LUT_PARTIAL_UPDATE: [u8; 70] =[
     0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
     0x80,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
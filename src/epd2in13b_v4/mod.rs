@@ -6,7 +6,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{prelude::*, primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder}};
 //!use epd_waveshare::{epd2in13b_v4::*, prelude::*};
 //!#
@@ -40,8 +40,8 @@
 //!epd.update_color_frame(
 //!    &mut spi,
 //!    &mut delay,
-//!    &tricolor_display.bw_buffer(),
-//!    &tricolor_display.chromatic_buffer()
+//!    tricolor_display.bw_buffer(),
+//!    tricolor_display.chromatic_buffer()
 //!)?;
 //!epd.display_frame(&mut spi, &mut delay)?;
 //!
@@ -52,21 +52,29 @@
 //!```
 // Original Waveforms from Waveshare
 use embedded_hal::{
-    blocking::{delay::*, spi::Write},
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
     digital::v2::{InputPin, OutputPin},
 };
 
 use crate::buffer_len;
-use crate::color::TriColor;
-use crate::interface::DisplayInterface;
-use crate::traits::{ InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay, };
+use crate::color::{BwPlane, ChromaticPlane, PlaneBackgrounds, TriColor};
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut, WaveshareThreeColorDisplay,
+};
 
 pub(crate) mod command;
 use self::command::{
     BorderWaveForm, BorderWaveFormFixLevel, BorderWaveFormGs, BorderWaveFormVbd, Command,
     DataEntryModeDir, DataEntryModeIncr, DeepSleepMode, DisplayUpdateControl2, DriverOutput,
-    GateDrivingVoltage, I32Ext, SourceDrivingVoltage, Vcom,
+    GateDrivingVoltage, SourceDrivingVoltage, Vcom,
 };
+pub use self::command::{DisplayUpdateControl1, RamOption};
 
 pub(crate) mod constants;
 use self::constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE};
@@ -89,6 +97,16 @@ pub const HEIGHT: u32 = 250;
 
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: TriColor = TriColor::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 15_000,
+    min_refresh_interval_ms: 180_000,
+};
 const IS_BUSY_LOW: bool = false;
 
 /// Epd2in13b (V4) driver
@@ -99,9 +117,82 @@ pub struct Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY> {
 
     /// Background Color
     background_color: TriColor,
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
+
+    /// Offset in °C added to the measured temperature
+    temperature_offset: i8,
+    /// The temperature register was loaded by `load_temperature`
+    temperature_loaded: bool,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Sent by `init`
+    update_control1: DisplayUpdateControl1,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn update_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_achromatic_frame(spi, delay, black)?;
+        self.update_chromatic_frame(spi, delay, chromatic)
+    }
+
+    /// Update only the black/white data of the display.
+    ///
+    /// Finish by calling `update_chromatic_frame`.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        BwPlane(black): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.data(spi, black)?;
+        Ok(())
+    }
+
+    /// Update only chromatic data of the display.
+    ///
+    /// This data takes precedence over the black/white data. The black/white RAM is left
+    /// untouched, so `display_frame` can follow directly when only the chromatic pixels
+    /// changed.
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRamRed)?;
+        self.interface.data(spi, chromatic)?;
+
+        self.wait_until_idle(spi, delay)?;
+        Ok(())
+    }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -111,9 +202,33 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        Epd2in13b {
+            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            planes: DEFAULT_BACKGROUND_COLOR.into(),
+            temperature_offset: 0,
+            temperature_loaded: false,
+            refresh: RefreshLut::Full,
+            update_control1: DisplayUpdateControl1::default(),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         // HW reset
         self.interface.reset(delay, 10_000, 10_000);
+        self.temperature_loaded = false;
 
         self.wait_until_idle(spi, delay)?;
         self.interface.cmd(spi, Command::SwReset)?;
@@ -151,88 +266,50 @@ where
                 gs_trans: BorderWaveFormGs::Lut3,
             },
         )?;
-        // self.set_vcom_register(spi, (-21).vcom())?;
+        // self.set_vcom_register(spi, (-21).vcom()?)?;
 
-        // self.set_gate_driving_voltage(spi, 190.gate_driving_decivolt())?;
+        // self.set_gate_driving_voltage(spi, 190.gate_driving_decivolt()?)?;
         // self.set_source_driving_voltage(
         //     spi,
-        //     150.source_driving_decivolt(),
-        //     50.source_driving_decivolt(),
-        //     (-150).source_driving_decivolt(),
+        //     150.source_driving_decivolt()?,
+        //     50.source_driving_decivolt()?,
+        //     (-150).source_driving_decivolt()?,
         // )?;
 
         // self.set_gate_line_width(spi, 10)?;
 
-        // self.set_lut(spi, delay, Some(self.refresh))?;
+        // The full refresh loads the LUT of the OTP
+        if self.refresh == RefreshLut::Quick {
+            self.set_lut(spi, delay, None)?;
+        }
 
         self.interface
             .cmd_with_data(spi, Command::TemperatureSensorRead, &[0x80])?;
 
-        self.interface
-            .cmd_with_data(spi, Command::DisplayUpdateControl1, &[0x80, 0x80])?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::DisplayUpdateControl1,
+            &self.update_control1.to_bytes(),
+        )?;
 
         self.wait_until_idle(spi, delay)?;
 
         Ok(())
     }
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-
-    fn update_color_frame(
-        &mut self,
-        spi: &mut SPI,
-        delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.update_achromatic_frame(spi, delay, black)?;
-        self.update_chromatic_frame(spi, delay, chromatic)
-    }
 
-    /// Update only the black/white data of the display.
-    ///
-    /// Finish by calling `update_chromatic_frame`.
-    fn update_achromatic_frame(
-        &mut self,
-        spi: &mut SPI,
-        delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface.data(spi, black)?;
-        Ok(())
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
     }
 
-    /// Update only chromatic data of the display.
-    ///
-    /// This data takes precedence over the black/white data.
-    fn update_chromatic_frame(
-        &mut self,
-        spi: &mut SPI,
-        delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface.cmd(spi, Command::WriteRamRed)?;
-        self.interface.data(spi, chromatic)?;
-
-        self.wait_until_idle(spi, delay)?;
-        Ok(())
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
     }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -243,62 +320,21 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = TriColor;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let mut epd = Epd2in13b {
-            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
-            background_color: DEFAULT_BACKGROUND_COLOR,
-        };
-
-        epd.init(spi, delay)?;
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-
-        // All sample code enables and disables analog/clocks...
-        self.set_display_update_control_2(
-            spi,
-            DisplayUpdateControl2::new()
-                .enable_analog()
-                .enable_clock()
-                .disable_analog()
-                .disable_clock(),
-        )?;
-        self.command(spi, Command::MasterActivation)?;
-
-        self.set_sleep_mode(spi, DeepSleepMode::Normal)?;
-        Ok(())
-    }
 
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        assert!(buffer.len() == buffer_len(WIDTH as usize, HEIGHT as usize));
-        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
-        self.set_ram_address_counters(spi, delay, 0, 0)?;
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        self.use_full_frame(spi, delay)?;
 
         self.cmd_with_data(spi, Command::WriteRam, buffer)?;
 
         if true {
             // Always keep the base buffer equal to current if not doing partial refresh.
-            self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
-            self.set_ram_address_counters(spi, delay, 0, 0)?;
+            self.use_full_frame(spi, delay)?;
 
             self.command(spi, Command::WriteRamRed)?;
             self.interface.data_x_times(
@@ -322,26 +358,22 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        assert!((width * height / 8) as usize == buffer.len());
-
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         // This should not be used when doing partial refresh. The RAM_RED must
         // be updated with the last buffer having been displayed. Doing partial
         // update directly in RAM makes this update impossible (we can't read
         // RAM content). Using this function will most probably make the actual
         // display incorrect as the controler will compare with something
         // incorrect.
-        assert!(true);
 
-        self.set_ram_area(spi, x, y, x + width, y + height)?;
-        self.set_ram_address_counters(spi, delay, x, y)?;
+        self.use_partial_frame(spi, delay, x, y, width, height)?;
 
         self.cmd_with_data(spi, Command::WriteRam, buffer)?;
 
         if true {
             // Always keep the base buffer equals to current if not doing partial refresh.
-            self.set_ram_area(spi, x, y, x + width, y + height)?;
-            self.set_ram_address_counters(spi, delay, x, y)?;
+            self.use_partial_frame(spi, delay, x, y, width, height)?;
 
             self.cmd_with_data(spi, Command::WriteRamRed, buffer)?;
         }
@@ -349,42 +381,11 @@ where
         Ok(())
     }
 
-    /// Never use directly this function when using partial refresh, or also
-    /// keep the base buffer in syncd using `set_partial_base_buffer` function.
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-            self.set_display_update_control_2(
-                spi,
-                DisplayUpdateControl2::new()
-                    .enable_clock()
-                    .enable_analog()
-                    .load_lut()
-                    .load_temp()
-                    .display()
-                    .disable_analog()
-                    .disable_clock(),
-            )?;
-        self.command(spi, Command::MasterActivation)?;
-        self.wait_until_idle(spi, delay)?;
-
-        Ok(())
-    }
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        let color = self.planes.achromatic.get_byte_value();
+        let chromatic = self.planes.chromatic_byte(0xFF);
 
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.display_frame(spi, delay)?;
-        Ok(())
-    }
-
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        let color = self.background_color.get_byte_value();
-
-        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
-        self.set_ram_address_counters(spi, delay, 0, 0)?;
+        self.use_full_frame(spi, delay)?;
 
         self.command(spi, Command::WriteRam)?;
         self.interface.data_x_times(
@@ -395,13 +396,12 @@ where
 
         // Always keep the base buffer equals to current if not doing partial refresh.
         if true {
-            self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
-            self.set_ram_address_counters(spi, delay, 0, 0)?;
+            self.use_full_frame(spi, delay)?;
 
             self.command(spi, Command::WriteRamRed)?;
             self.interface.data_x_times(
                 spi,
-                color,
+                chromatic,
                 buffer_len(WIDTH as usize, HEIGHT as usize) as u32,
             )?;
         }
@@ -410,6 +410,7 @@ where
 
     fn set_background_color(&mut self, background_color: TriColor) {
         self.background_color = background_color;
+        self.planes = background_color.into();
     }
 
     fn background_color(&self) -> &TriColor {
@@ -424,22 +425,149 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Never use directly this function when using partial refresh, or also
+    /// keep the base buffer in syncd using `set_partial_base_buffer` function.
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        let mut control = DisplayUpdateControl2::new().enable_clock().enable_analog();
+        // The quick refresh uses the LUT written by `set_lut`
+        if self.refresh == RefreshLut::Full {
+            control = control.load_lut();
+        }
+        // Keep the compensated temperature if it was loaded before
+        if !self.temperature_loaded {
+            control = control.load_temp();
+        }
+        self.set_display_update_control_2(spi, control.display().disable_analog().disable_clock())?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+        self.temperature_loaded = false;
+
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         _delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        let buffer = match refresh_rate {
-            Some(RefreshLut::Full) | None => &LUT_FULL_UPDATE,
-            Some(RefreshLut::Quick) => &LUT_PARTIAL_UPDATE,
+    ) -> Result<(), Error<SPI::Error>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        let buffer = match self.refresh {
+            RefreshLut::Full => &LUT_FULL_UPDATE,
+            RefreshLut::Quick => &LUT_PARTIAL_UPDATE,
         };
 
-        self.cmd_with_data(spi, Command::WriteLutRegister, buffer)
+        self.cmd_with_data(spi, Command::WriteLutRegister, buffer)?;
+        Ok(())
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        match self.refresh {
+            RefreshLut::Full => LutSource::Otp,
+            RefreshLut::Quick => LutSource::Register,
+        }
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+
+        // All sample code enables and disables analog/clocks...
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .enable_analog()
+                .enable_clock()
+                .disable_analog()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+
+        self.set_sleep_mode(spi, DeepSleepMode::Normal)?;
         Ok(())
     }
 }
@@ -453,17 +581,91 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
+    /// Sets how the RAMs are used by the following refreshes and which source outputs drive the
+    /// panel, e.g. to leave out the red RAM for fast black/white updates
+    ///
+    /// The setting is kept over `init` and `wake_up`.
+    pub fn set_display_update_control1(
+        &mut self,
+        spi: &mut SPI,
+        control: DisplayUpdateControl1,
+    ) -> Result<(), SPI::Error> {
+        self.update_control1 = control;
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl1, &control.to_bytes())
+    }
+
+    /// Inverts the black/white and the chromatic plane in the following refreshes, e.g. for a
+    /// night mode
+    ///
+    /// The controller inverts the content of the RAMs, so the frame doesn't need to be drawn
+    /// or transmitted again, `display_frame` is enough. Uses the RAM options of
+    /// [`set_display_update_control1`](Epd2in13b::set_display_update_control1).
+    pub fn set_inverted(
+        &mut self,
+        spi: &mut SPI,
+        bw: bool,
+        chromatic: bool,
+    ) -> Result<(), SPI::Error> {
+        self.set_display_update_control1(spi, inverted(self.update_control1, bw, chromatic))
+    }
+
+    /// The setting sent with [`set_display_update_control1`](Epd2in13b::set_display_update_control1)
+    pub fn display_update_control1(&self) -> DisplayUpdateControl1 {
+        self.update_control1
+    }
+
+    /// Sets the offset in °C which is added to the temperature measured by the internal
+    /// sensor before it is used to select the waveform.
+    ///
+    /// The offset is applied by [`load_temperature`](Epd2in13b::load_temperature) and
+    /// [`display_frame_compensated`](Epd2in13b::display_frame_compensated), the plain
+    /// [`display_frame`](EpdRefresh::display_frame) uses the measured temperature. The
+    /// compensated temperature saturates, so every offset is accepted.
+    pub fn set_temperature_offset(&mut self, offset: i8) {
+        self.temperature_offset = offset;
+    }
+
+    /// Returns the configured temperature offset in °C
+    pub fn temperature_offset(&self) -> i8 {
+        self.temperature_offset
+    }
+
+    /// Disables the clock and analog blocks of the controller without entering deep sleep.
+    ///
+    /// RAM and registers are kept and no reset is needed afterwards, the next refresh
+    /// enables the blocks again as needed.
+    pub fn low_power_idle(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .disable_analog()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    #[allow(dead_code)]
     fn set_gate_scan_start_position(
         &mut self,
         spi: &mut SPI,
         start: u16,
-    ) -> Result<(), SPI::Error> {
-        assert!(start <= 295);
+    ) -> Result<(), Error<SPI::Error>> {
+        if start > 295 {
+            return Err(Error::OutOfRange);
+        }
         self.cmd_with_data(
             spi,
             Command::GateScanStartPosition,
             &[(start & 0xFF) as u8, ((start >> 8) & 0x1) as u8],
-        )
+        )?;
+        Ok(())
     }
 
     fn set_border_waveform(
@@ -478,10 +680,12 @@ where
         )
     }
 
+    #[allow(dead_code)]
     fn set_vcom_register(&mut self, spi: &mut SPI, vcom: Vcom) -> Result<(), SPI::Error> {
         self.cmd_with_data(spi, Command::WriteVcomRegister, &[vcom.0])
     }
 
+    #[allow(dead_code)]
     fn set_gate_driving_voltage(
         &mut self,
         spi: &mut SPI,
@@ -490,20 +694,26 @@ where
         self.cmd_with_data(spi, Command::GateDrivingVoltageCtrl, &[voltage.0])
     }
 
+    #[allow(dead_code)]
     fn set_dummy_line_period(
         &mut self,
         spi: &mut SPI,
         number_of_lines: u8,
-    ) -> Result<(), SPI::Error> {
-        assert!(number_of_lines <= 127);
-        self.cmd_with_data(spi, Command::SetDummyLinePeriod, &[number_of_lines])
+    ) -> Result<(), Error<SPI::Error>> {
+        if number_of_lines > 127 {
+            return Err(Error::OutOfRange);
+        }
+        self.cmd_with_data(spi, Command::SetDummyLinePeriod, &[number_of_lines])?;
+        Ok(())
     }
 
+    #[allow(dead_code)]
     fn set_gate_line_width(&mut self, spi: &mut SPI, width: u8) -> Result<(), SPI::Error> {
         self.cmd_with_data(spi, Command::SetGateLineWidth, &[width & 0x0F])
     }
 
     /// Sets the source driving voltage value
+    #[allow(dead_code)]
     fn set_source_driving_voltage(
         &mut self,
         spi: &mut SPI,
@@ -549,6 +759,64 @@ where
         self.cmd_with_data(spi, Command::DataEntryModeSetting, &[mode])
     }
 
+    /// Transmits only the chromatic data and refreshes the display
+    ///
+    /// The black/white RAM isn't written, it keeps the data of the last update.
+    pub fn update_and_display_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_chromatic_frame(spi, delay, chromatic)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Transmits the chromatic data of a window, the rest of both RAMs is left untouched
+    ///
+    /// `x` and `width` must be multiples of 8.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_partial_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        chromatic: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(chromatic, x, y, width, height, WIDTH, HEIGHT)?;
+        self.wait_until_idle(spi, delay)?;
+        self.use_partial_frame(spi, delay, x, y, width, height)?;
+        self.cmd_with_data(spi, Command::WriteRamRed, chromatic)?;
+        Ok(())
+    }
+
+    /// Selects the whole RAM and moves the counters to its start
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        self.set_ram_address_counters(spi, delay, 0, 0)
+    }
+
+    /// Selects the RAM window of a partial update and moves the counters to its start
+    fn use_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_ram_area(spi, x, y, x + width - 1, y + height - 1)?;
+        self.set_ram_address_counters(spi, delay, x, y)
+    }
+
     /// Sets both X and Y pixels ranges
     fn set_ram_area(
         &mut self,
@@ -583,7 +851,7 @@ where
         delay: &mut DELAY,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::SetRamXAddressCounter, &[(x >> 3) as u8])?;
 
@@ -609,6 +877,88 @@ where
     }
 }
 
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in13b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8> + Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Refreshes the display like [`display_frame`](EpdRefresh::display_frame), with the
+    /// temperature offset applied by [`load_temperature`](Epd2in13b::load_temperature)
+    /// first if it isn't zero.
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the temperature register.
+    pub fn display_frame_compensated(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<<SPI as Write<u8>>::Error>> {
+        if self.temperature_offset != 0 {
+            self.load_temperature(spi, delay)?;
+        }
+        self.display_frame(spi, delay)
+    }
+
+    /// Measures the temperature with the internal sensor, adds the temperature offset
+    /// and loads the result into the temperature register used for the waveform selection.
+    ///
+    /// The next [`display_frame`](EpdRefresh::display_frame) uses this temperature
+    /// instead of measuring it again. Returns the compensated temperature in °C.
+    ///
+    /// The DIN line of the display must be connected to the MISO line of the spi bus
+    /// to be able to read the temperature register.
+    pub fn load_temperature(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<i8, Error<<SPI as Write<u8>>::Error>> {
+        let mut temperature = [0u8; 2];
+
+        self.wait_until_idle(spi, delay)?;
+        self.set_display_update_control_2(
+            spi,
+            DisplayUpdateControl2::new()
+                .enable_clock()
+                .load_temp()
+                .disable_clock(),
+        )?;
+        self.command(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface
+            .cmd_read(spi, Command::TemperatureSensorControlRead, &mut temperature)?;
+
+        // The first byte holds the integer part of the temperature
+        let compensated = (temperature[0] as i8).saturating_add(self.temperature_offset);
+        self.cmd_with_data(
+            spi,
+            Command::TemperatureSensorControlWrite,
+            &[compensated as u8, temperature[1]],
+        )?;
+        self.temperature_loaded = true;
+
+        Ok(compensated)
+    }
+}
+
+/// `control` with the RAM options inverting the planes
+///
+/// The red RAM is inverted when not inverting, as the chromatic plane is 0 for red pixels.
+fn inverted(control: DisplayUpdateControl1, bw: bool, chromatic: bool) -> DisplayUpdateControl1 {
+    let option = |inverse| {
+        if inverse {
+            RamOption::Inverse
+        } else {
+            RamOption::Normal
+        }
+    };
+    control.bw_ram(option(bw)).red_ram(option(!chromatic))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -619,4 +969,75 @@ mod tests {
         assert_eq!(HEIGHT, 250);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, TriColor::White);
     }
+
+    #[test]
+    fn display_update_control1() {
+        assert_eq!(DisplayUpdateControl1::default().to_bytes(), [0x80, 0x80]);
+        let control = DisplayUpdateControl1::new()
+            .red_ram(RamOption::Bypass)
+            .bw_ram(RamOption::Inverse);
+        assert_eq!(control.to_bytes(), [0x48, 0x00]);
+        assert_eq!(control.red_ram(RamOption::Normal).to_bytes(), [0x08, 0x00]);
+    }
+
+    #[test]
+    fn display_frame_compensated_applies_the_offset() {
+        extern crate std;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::{vec, vec::Vec};
+
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![0x22]),
+            spi::Transaction::write(vec![0x61]),
+            spi::Transaction::write(vec![0x20]),
+            spi::Transaction::write(vec![0x1B]),
+            spi::Transaction::transfer(vec![0x00, 0x00], vec![0x19, 0x80]),
+            spi::Transaction::write(vec![0x1A]),
+            spi::Transaction::write(vec![0x17, 0x80]),
+            spi::Transaction::write(vec![0x22]),
+            spi::Transaction::write(vec![0xD7]),
+            spi::Transaction::write(vec![0x20]),
+        ]);
+        let toggles = |n| {
+            (0..n)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<Vec<_>>()
+        };
+        let mut cs = Mock::new(&toggles(10));
+        let dc_states = [0, 1, 0, 0, 1, 0, 1, 0, 1, 0]
+            .map(|high| Transaction::set(if high == 1 { State::High } else { State::Low }));
+        let mut dc = Mock::new(&dc_states);
+        let mut busy = Mock::new(&vec![Transaction::get(State::Low); 3]);
+        let mut epd: Epd2in13b<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in13b::new_uninitialized(
+                cs.clone(),
+                busy.clone(),
+                dc.clone(),
+                Mock::new(&[]),
+                None,
+            );
+
+        epd.set_temperature_offset(-2);
+        assert_eq!(epd.temperature_offset(), -2);
+        epd.display_frame_compensated(&mut spi, &mut MockNoop::new())
+            .unwrap();
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+    }
+
+    #[test]
+    fn inversion() {
+        let control = DisplayUpdateControl1::default();
+        assert_eq!(inverted(control, false, false), control);
+        assert_eq!(inverted(control, true, false).to_bytes(), [0x88, 0x80]);
+        assert_eq!(inverted(control, false, true).to_bytes(), [0x00, 0x80]);
+        assert_eq!(inverted(control, true, true).to_bytes(), [0x08, 0x80]);
+    }
 }
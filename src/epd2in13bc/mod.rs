@@ -6,7 +6,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{prelude::*, primitives::{Line, PrimitiveStyle, PrimitiveStyleBuilder}};
 //!use epd_waveshare::{epd2in13bc::*, prelude::*};
 //!#
@@ -40,8 +40,8 @@
 //!epd.update_color_frame(
 //!    &mut spi,
 //!    &mut delay,
-//!    &tricolor_display.bw_buffer(),
-//!    &tricolor_display.chromatic_buffer()
+//!    tricolor_display.bw_buffer(),
+//!    tricolor_display.chromatic_buffer()
 //!)?;
 //!epd.display_frame(&mut spi, &mut delay)?;
 //!
@@ -55,9 +55,12 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::lut_table::Runs;
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut, WaveshareThreeColorDisplay,
 };
 
 /// Width of epd2in13bc in pixels
@@ -67,6 +70,16 @@ pub const HEIGHT: u32 = 212;
 /// Default background color (white) of epd2in13bc display
 pub const DEFAULT_BACKGROUND_COLOR: TriColor = TriColor::White;
 
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: false,
+    quick_refresh: false,
+    full_refresh_ms: 15_000,
+    min_refresh_interval_ms: 180_000,
+};
+
 /// Number of bits for b/w buffer and same for chromatic buffer
 const NUM_DISPLAY_BITS: u32 = WIDTH * HEIGHT / 8;
 
@@ -77,7 +90,7 @@ const BLACK_BORDER: u8 = 0x30;
 const CHROMATIC_BORDER: u8 = 0xb0;
 const FLOATING_BORDER: u8 = 0xF0;
 
-use crate::color::TriColor;
+use crate::color::{BwPlane, ChromaticPlane, PlaneBackgrounds, TriColor, TriColorKind};
 
 pub(crate) mod command;
 use self::command::Command;
@@ -97,50 +110,10 @@ pub type Display2in13bc = crate::graphics::Display<
 pub struct Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     color: TriColor,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // Values taken from datasheet and sample code
-
-        self.interface.reset(delay, 10_000, 10_000);
-
-        // start the booster
-        self.interface
-            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x17])?;
-
-        // power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_us(5000);
-        self.wait_until_idle(spi, delay)?;
-
-        // set the panel settings
-        self.cmd_with_data(spi, Command::PanelSetting, &[0x8F])?;
-
-        self.cmd_with_data(
-            spi,
-            Command::VcomAndDataIntervalSetting,
-            &[WHITE_BORDER | VCOM_DATA_INTERVAL],
-        )?;
-
-        // set resolution
-        self.send_resolution(spi)?;
-
-        self.cmd_with_data(spi, Command::VcmDcSetting, &[0x0A])?;
-
-        self.wait_until_idle(spi, delay)?;
-
-        Ok(())
-    }
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
+    /// Red for the (B), yellow for the (C) panel
+    chromatic_kind: TriColorKind,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
@@ -157,9 +130,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_achromatic_frame(spi, delay, black)?;
         self.update_chromatic_frame(spi, delay, chromatic)
     }
@@ -171,8 +144,8 @@ where
         &mut self,
         spi: &mut SPI,
         _delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error> {
+        BwPlane(black): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
         self.interface.data(spi, black)?;
         Ok(())
@@ -185,17 +158,25 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface.data(spi, chromatic)?;
 
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
+
+    fn chromatic_kind(&self) -> TriColorKind {
+        self.chromatic_kind
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -205,49 +186,89 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    type DisplayColor = TriColor;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
         let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
         let color = DEFAULT_BACKGROUND_COLOR;
 
-        let mut epd = Epd2in13bc { interface, color };
+        Epd2in13bc {
+            interface,
+            color,
+            planes: color.into(),
+            chromatic_kind: TriColorKind::default(),
+        }
+    }
+}
 
-        epd.init(spi, delay)?;
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        // Values taken from datasheet and sample code
 
-        Ok(epd)
-    }
+        self.interface.reset(delay, 10_000, 10_000);
 
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // Section 8.2 from datasheet
-        self.interface.cmd_with_data(
+        // start the booster
+        self.interface
+            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x17])?;
+
+        // power on
+        self.command(spi, Command::PowerOn)?;
+        delay.delay_us(5000);
+        self.wait_until_idle(spi, delay)?;
+
+        // set the panel settings
+        self.cmd_with_data(spi, Command::PanelSetting, &[0x8F])?;
+
+        self.cmd_with_data(
             spi,
             Command::VcomAndDataIntervalSetting,
-            &[FLOATING_BORDER | VCOM_DATA_INTERVAL],
+            &[WHITE_BORDER | VCOM_DATA_INTERVAL],
         )?;
 
-        self.command(spi, Command::PowerOff)?;
-        // The example STM code from Github has a wait after PowerOff
-        self.wait_until_idle(spi, delay)?;
+        // set resolution
+        self.send_resolution(spi)?;
 
-        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
+        self.cmd_with_data(spi, Command::VcmDcSetting, &[0x0A])?;
+
+        self.wait_until_idle(spi, delay)?;
 
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
         self.init(spi, delay)
     }
 
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = TriColor;
+
     fn set_background_color(&mut self, color: TriColor) {
         self.color = color;
+        self.planes = color.into();
     }
 
     fn background_color(&self) -> &TriColor {
@@ -262,12 +283,16 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
         self.interface.data(spi, buffer)?;
@@ -292,11 +317,56 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.send_resolution(spi)?;
+
+        let color = self.planes.achromatic.get_byte_value();
+        let chromatic = self.planes.chromatic_byte(0xFF);
+
+        // Clear the black
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+
+        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+
+        // Clear the chromatic
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface
+            .data_x_times(spi, chromatic, NUM_DISPLAY_BITS)?;
+
+        self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+}
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.command(spi, Command::DisplayRefresh)?;
 
         self.wait_until_idle(spi, delay)?;
@@ -308,43 +378,84 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.send_resolution(spi)?;
-
-        let color = DEFAULT_BACKGROUND_COLOR.get_byte_value();
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        Ok(())
+    }
 
-        // Clear the black
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+    fn refresh_mode(&self) -> RefreshLut {
+        RefreshLut::Full
+    }
 
-        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+    fn lut_source(&self) -> LutSource {
+        LutSource::Otp
+    }
 
-        // Clear the chromatic
-        self.interface.cmd(spi, Command::DataStartTransmission2)?;
-        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
 
-        self.wait_until_idle(spi, delay)?;
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
         Ok(())
     }
 
-    fn set_lut(
+    fn wait_until_idle_timeout(
         &mut self,
         _spi: &mut SPI,
-        _delay: &mut DELAY,
-        _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        Ok(())
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
     }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        // Section 8.2 from datasheet
+        self.interface.cmd_with_data(
+            spi,
+            Command::VcomAndDataIntervalSetting,
+            &[FLOATING_BORDER | VCOM_DATA_INTERVAL],
+        )?;
+
+        self.command(spi, Command::PowerOff)?;
+        // The example STM code from Github has a wait after PowerOff
+        self.wait_until_idle(spi, delay)?;
+
+        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
         Ok(())
     }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in13bc<SPI, CS, BUSY, DC, RST, DELAY>
@@ -356,6 +467,31 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
+    /// Same as [`update_color_frame`](WaveshareThreeColorDisplay::update_color_frame) with
+    /// a run length encoded buffer holding both planes, see `graphics::encode_rle`
+    ///
+    /// The planes are expanded while they are sent, so they are never kept in memory as a
+    /// whole.
+    pub fn update_color_frame_rle(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        encoded: &[u8],
+    ) -> Result<(), Error<SPI::Error>> {
+        let len = NUM_DISPLAY_BITS as usize;
+        crate::check_rle_len(encoded, 2 * len)?;
+        let mut runs = Runs::new(encoded);
+
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        self.interface.data_rle(spi, &mut runs, len)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface.data_rle(spi, &mut runs, len)?;
+
+        self.wait_until_idle(spi, delay)?;
+        Ok(())
+    }
+
     fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
         self.interface.cmd(spi, command)
     }
@@ -384,6 +520,12 @@ where
         self.send_data(spi, &[h as u8])
     }
 
+    /// Sets the chromatic color of the panel, the driver is the same for the red (B) and the
+    /// yellow (C) panel
+    pub fn set_chromatic_kind(&mut self, kind: TriColorKind) {
+        self.chromatic_kind = kind;
+    }
+
     /// Set the outer border of the display to the chosen color.
     pub fn set_border_color(&mut self, spi: &mut SPI, color: TriColor) -> Result<(), SPI::Error> {
         let border = match color {
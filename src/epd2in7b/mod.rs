@@ -7,9 +7,11 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut, WaveshareThreeColorDisplay,
 };
 
 // The Lookup Tables for the Display
@@ -22,9 +24,19 @@ pub const WIDTH: u32 = 176;
 pub const HEIGHT: u32 = 264;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: false,
+    full_refresh_ms: 15_000,
+    min_refresh_interval_ms: 180_000,
+};
 const IS_BUSY_LOW: bool = true;
 
-use crate::color::Color;
+use crate::color::{BwPlane, ChromaticPlane, Color, PlaneBackgrounds};
 
 pub(crate) mod command;
 use self::command::Command;
@@ -47,9 +59,11 @@ pub struct Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     /// Background Color
     color: Color,
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -59,7 +73,29 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        Epd2in7b {
+            interface,
+            color,
+            planes: color.into(),
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         // reset the device
         self.interface.reset(delay, 10_000, 2_000);
 
@@ -113,9 +149,20 @@ where
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 2_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -126,47 +173,13 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-        let color = DEFAULT_BACKGROUND_COLOR;
-
-        let mut epd = Epd2in7b { interface, color };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xf7])?;
-
-        self.command(spi, Command::PowerOff)?;
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
-        Ok(())
-    }
 
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
         self.send_buffer_helper(spi, buffer)?;
 
@@ -188,7 +201,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.interface
             .cmd(spi, Command::PartialDataStartTransmission1)?;
 
@@ -204,30 +218,15 @@ where
 
         self.send_buffer_helper(spi, buffer)?;
 
-        self.interface.cmd(spi, Command::DataStop)
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.command(spi, Command::DisplayRefresh)?;
-        self.wait_until_idle(spi, delay)?;
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
+        self.interface.cmd(spi, Command::DataStop)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
 
-        let color_value = self.color.get_byte_value();
+        let color_value = self.planes.achromatic.get_byte_value();
+        let chromatic_value = self.planes.chromatic_byte(0xFF);
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
         self.interface
             .data_x_times(spi, color_value, WIDTH * HEIGHT / 8)?;
@@ -236,13 +235,14 @@ where
 
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface
-            .data_x_times(spi, color_value, WIDTH * HEIGHT / 8)?;
+            .data_x_times(spi, chromatic_value, WIDTH * HEIGHT / 8)?;
         self.interface.cmd(spi, Command::DataStop)?;
         Ok(())
     }
 
     fn set_background_color(&mut self, color: Color) {
         self.color = color;
+        self.planes = color.into();
     }
 
     fn background_color(&self) -> &Color {
@@ -257,12 +257,58 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.command(spi, Command::DisplayRefresh)?;
+        self.wait_until_idle(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.command(spi, Command::DisplayRefresh)?;
+        Ok(())
+    }
+
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.cmd_with_data(spi, Command::LutForVcom, &LUT_VCOM_DC)?;
         self.cmd_with_data(spi, Command::LutWhiteToWhite, &LUT_WW)?;
@@ -272,8 +318,62 @@ where
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn refresh_mode(&self) -> RefreshLut {
+        RefreshLut::Full
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0xf7])?;
+
+        self.command(spi, Command::PowerOff)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
         Ok(())
     }
 }
@@ -292,9 +392,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_achromatic_frame(spi, delay, black)?;
         self.update_chromatic_frame(spi, delay, chromatic)
     }
@@ -306,13 +406,14 @@ where
         &mut self,
         spi: &mut SPI,
         _delay: &mut DELAY,
-        achromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        BwPlane(achromatic): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
 
         self.send_buffer_helper(spi, achromatic)?;
 
-        self.interface.cmd(spi, Command::DataStop)
+        self.interface.cmd(spi, Command::DataStop)?;
+        Ok(())
     }
 
     /// Update only chromatic data of the display.
@@ -322,8 +423,8 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
 
         self.send_buffer_helper(spi, chromatic)?;
@@ -333,6 +434,10 @@ where
 
         Ok(())
     }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in7b<SPI, CS, BUSY, DC, RST, DELAY>
@@ -355,10 +460,12 @@ where
     fn send_buffer_helper(&mut self, spi: &mut SPI, buffer: &[u8]) -> Result<(), SPI::Error> {
         // Based on the waveshare implementation, all data for color values is flipped. This helper
         // method makes that transmission easier
-        for b in buffer.iter() {
-            self.send_data(spi, &[!b])?;
-        }
-        Ok(())
+        self.interface.begin_data();
+        let result = buffer
+            .iter()
+            .try_for_each(|b| self.interface.write(spi, &[!b]));
+        self.interface.end();
+        result
     }
 
     fn cmd_with_data(
@@ -379,7 +486,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.command(spi, Command::PartialDisplayRefresh)?;
         self.send_data(spi, &[(x >> 8) as u8])?;
         self.send_data(spi, &[(x & 0xf8) as u8])?;
@@ -404,7 +511,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface
             .cmd(spi, Command::PartialDataStartTransmission1)?;
         self.send_data(spi, &[(x >> 8) as u8])?;
@@ -417,11 +524,8 @@ where
         self.send_data(spi, &[(height & 0xff) as u8])?;
         self.wait_until_idle(spi, delay)?;
 
-        for b in achromatic.iter() {
-            // Flipping based on waveshare implementation
-            self.send_data(spi, &[!b])?;
-        }
-
+        // Flipping based on waveshare implementation
+        self.send_buffer_helper(spi, achromatic)?;
         Ok(())
     }
 
@@ -436,7 +540,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface
             .cmd(spi, Command::PartialDataStartTransmission2)?;
         self.send_data(spi, &[(x >> 8) as u8])?;
@@ -449,11 +553,8 @@ where
         self.send_data(spi, &[(height & 0xff) as u8])?;
         self.wait_until_idle(spi, delay)?;
 
-        for b in chromatic.iter() {
-            // Flipping based on waveshare implementation
-            self.send_data(spi, &[!b])?;
-        }
-
+        // Flipping based on waveshare implementation
+        self.send_buffer_helper(spi, chromatic)?;
         Ok(())
     }
 }
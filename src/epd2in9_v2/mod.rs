@@ -6,7 +6,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
@@ -60,34 +60,50 @@ pub const WIDTH: u32 = 128;
 pub const HEIGHT: u32 = 296;
 /// Default Background Color (white)
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: true,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 3000,
+    min_refresh_interval_ms: 0,
+};
 const IS_BUSY_LOW: bool = false;
 
-const LUT_PARTIAL_2IN9: [u8; 159] = [
-    0x0, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0x80, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0A, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-    0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x22, 0x22, 0x22, 0x22, 0x22,
-    0x22, 0x0, 0x0, 0x0, 0x22, 0x17, 0x41, 0xB0, 0x32, 0x36,
-];
+lut_table! {
+    LUT_PARTIAL_2IN9: [u8; 159] = [
+        0x0, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x80, 0x80, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x40, 0x40, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x80, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0A, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x22, 0x22, 0x22, 0x22, 0x22,
+        0x22, 0x0, 0x0, 0x0, 0x22, 0x17, 0x41, 0xB0, 0x32, 0x36,
+    ];
+}
 
 use embedded_hal::{
     blocking::{delay::*, spi::Write},
     digital::v2::*,
 };
 
-use crate::type_a::command::Command;
+use crate::type_a::{command::Command, constants::LUT_GRAY_UPDATE};
 
 use crate::color::Color;
+#[cfg(feature = "graphics")]
+use crate::color::Gray2;
 
 use crate::traits::*;
 
 use crate::buffer_len;
-use crate::interface::DisplayInterface;
-use crate::traits::QuickRefresh;
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::lut_table::lut_table;
+use crate::traits::{sealed::HasHooks, QuickRefresh};
 
 /// Display with Fullsize buffer for use with the 2in9 EPD V2
 #[cfg(feature = "graphics")]
@@ -99,6 +115,13 @@ pub type Display2in9 = crate::graphics::Display<
     Color,
 >;
 
+/// Display with Fullsize buffer and 4 gray levels for use with the 2in9 EPD V2
+///
+/// To be transmitted with [`Epd2in9::update_frame_gray2`].
+#[cfg(feature = "graphics")]
+pub type Display2in9Gray2 =
+    crate::graphics::Display<WIDTH, HEIGHT, false, { (WIDTH * HEIGHT / 4) as usize }, Gray2>;
+
 /// Epd2in9 driver
 ///
 pub struct Epd2in9<SPI, CS, BUSY, DC, RST, DELAY> {
@@ -108,9 +131,12 @@ pub struct Epd2in9<SPI, CS, BUSY, DC, RST, DELAY> {
     background_color: Color,
     /// Refresh LUT
     refresh: RefreshLut,
+    /// Full refreshes use the fast waveform of the OTP
+    fast_refresh: bool,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
     CS: OutputPin,
@@ -119,7 +145,29 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
+
+        Epd2in9 {
+            interface,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            fast_refresh: false,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.interface.reset(delay, 10_000, 2_000);
 
         self.wait_until_idle(spi, delay)?;
@@ -147,11 +195,27 @@ where
         self.set_ram_counter(spi, delay, 0, 0)?;
 
         self.wait_until_idle(spi, delay)?;
+
+        // The reset dropped the LUT of the quick refresh
+        if self.refresh == RefreshLut::Quick {
+            self.load_partial_lut(spi, delay)?;
+        }
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 2_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -162,6 +226,7 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
+
     fn width(&self) -> u32 {
         WIDTH
     }
@@ -170,39 +235,8 @@ where
         HEIGHT
     }
 
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-
-        let mut epd = Epd2in9 {
-            interface,
-            background_color: DEFAULT_BACKGROUND_COLOR,
-            refresh: RefreshLut::Full,
-        };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
-        Ok(())
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)?;
-        Ok(())
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
     }
 
     fn update_frame(
@@ -210,9 +244,11 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        self.interface.cmd_with_data(spi, Command::WriteRam, buffer)
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+        Ok(())
     }
 
     fn update_partial_frame(
@@ -224,7 +260,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         //TODO This is copied from epd2in9 but it seems not working. Partial refresh supported by version 2?
         self.wait_until_idle(spi, delay)?;
         self.set_ram_area(spi, x, y, x + width, y + height)?;
@@ -235,12 +272,74 @@ where
         Ok(())
     }
 
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+
+        // clear the ram with the background color
+        let color = self.background_color.get_byte_value();
+
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
     /// actually is the "Turn on Display" sequence
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    ///
+    /// With `RefreshLut::Quick` the partial waveform loaded by `set_lut` is used instead of
+    /// the one of the OTP.
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+        if self.refresh == RefreshLut::Full && self.fast_refresh {
+            self.load_fast_lut(spi, delay)?;
+        }
+        let control = match self.refresh {
+            // Enable clock signal, Enable Analog, DISPLAY with DISPLAY Mode 1 and the LUT
+            // loaded by `load_fast_lut`, Disable Analog, Disable OSC
+            RefreshLut::Full if self.fast_refresh => 0xC7,
+            // Enable clock signal, Enable Analog, Load temperature value, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+            RefreshLut::Full => 0xF7,
+            // DISPLAY with DISPLAY Mode 2 and the LUT in the register
+            RefreshLut::Quick => 0x0F,
+        };
         self.interface
-            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xF7])?;
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[control])?;
         self.interface.cmd(spi, Command::MasterActivation)?;
         self.wait_until_idle(spi, delay)?;
         Ok(())
@@ -251,47 +350,84 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
+    /// The full refresh uses the LUT of the OTP, which `display_frame` loads itself
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        match self.refresh {
+            RefreshLut::Full => Ok(()),
+            RefreshLut::Quick => self.load_partial_lut(spi, delay),
+        }
+    }
 
-        // clear the ram with the background color
-        let color = self.background_color.get_byte_value();
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
 
-        self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface
-            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
-        self.interface.cmd(spi, Command::WriteRam2)?;
-        self.interface.data_x_times(spi, color, WIDTH / 8 * HEIGHT)
+    fn lut_source(&self) -> LutSource {
+        match self.refresh {
+            RefreshLut::Full => LutSource::Otp,
+            RefreshLut::Quick => LutSource::Register,
+        }
     }
 
-    fn set_background_color(&mut self, background_color: Color) {
-        self.background_color = background_color;
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
     }
 
-    fn background_color(&self) -> &Color {
-        &self.background_color
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
     }
 
-    fn set_lut(
+    fn wait_until_idle_timeout(
         &mut self,
         _spi: &mut SPI,
-        _delay: &mut DELAY,
-        refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        if let Some(refresh_lut) = refresh_rate {
-            self.refresh = refresh_lut;
-        }
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleepMode, &[0x01])?;
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)?;
         Ok(())
     }
 }
@@ -305,7 +441,11 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn use_full_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
         // choose full frame/ram
         self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
 
@@ -321,8 +461,8 @@ where
         end_x: u32,
         end_y: u32,
     ) -> Result<(), SPI::Error> {
-        assert!(start_x < end_x);
-        assert!(start_y < end_y);
+        debug_assert!(start_x < end_x);
+        debug_assert!(start_y < end_y);
 
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -351,7 +491,7 @@ where
         delay: &mut DELAY,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -367,13 +507,119 @@ where
         Ok(())
     }
 
+    /// Selects the fast waveform of the vendor for full refreshes
+    ///
+    /// The fast refresh flashes the panel once instead of several times and is faster,
+    /// at the cost of slightly more ghosting. It uses the waveform the OTP
+    /// holds for 100°C, like `EPD_2IN9_V2_Init_Fast` of the Waveshare examples, so the
+    /// compensation for the temperature of the panel is lost.
+    pub fn set_fast_refresh(&mut self, fast: bool) {
+        self.fast_refresh = fast;
+    }
+
+    /// Returns whether full refreshes use the fast waveform
+    pub fn fast_refresh(&self) -> bool {
+        self.fast_refresh
+    }
+
+    /// Loads the waveform of the fast refresh from the OTP by forcing the temperature
+    fn load_fast_lut(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        // Internal temperature sensor
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureSensorSelection, &[0x80])?;
+        // 100°C
+        self.interface
+            .cmd_with_data(spi, Command::TemperatureSensorControl, &[0x64, 0x00])?;
+        // Enable clock signal, Load LUT, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0x91])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    /// Transmits a frame with 4 gray levels
+    ///
+    /// The buffer holds 2 bits per pixel, 4 pixels per byte with the first pixel in the
+    /// upper bits: `0b00` is black, `0b01` dark gray, `0b10` light gray and `0b11` white.
+    /// It needs `WIDTH * HEIGHT / 4` bytes, e.g. the buffer of a `Display2in9Gray2`.
+    ///
+    /// The lower bits are written to the black/white RAM, the upper bits to the second
+    /// RAM, which is why it must be shown with [`display_frame_gray2`](Epd2in9::display_frame_gray2).
+    pub fn update_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, (WIDTH * HEIGHT / 4) as usize)?;
+        self.wait_until_idle(spi, delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface.data_gray2_plane(spi, buffer, 0)?;
+
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam2)?;
+        self.interface.data_gray2_plane(spi, buffer, 1)?;
+        Ok(())
+    }
+
+    /// Displays the frame transmitted with [`update_frame_gray2`](Epd2in9::update_frame_gray2)
+    /// using the 4 gray levels LUT of the vendor.
+    ///
+    /// The LUT of the selected refresh mode is loaded again afterwards.
+    pub fn display_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        let lut = LUT_GRAY_UPDATE.get();
+        self.set_lut_helper(spi, delay, &lut[..153])?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegisterEnd, &[lut[153]])?;
+        self.interface
+            .cmd_with_data(spi, Command::GateDrivingVoltage, &[lut[154]])?;
+        self.interface
+            .cmd_with_data(spi, Command::SourceDrivingVoltage, &lut[155..158])?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteVcomRegister, &[lut[158]])?;
+
+        // Enable clock signal, Enable Analog, DISPLAY with DISPLAY Mode 1, Disable Analog, Disable OSC
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC7])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+        self.wait_until_idle(spi, delay)?;
+        self.set_lut(spi, delay, None)
+    }
+
+    /// Loads the partial waveform and enables the clock and analog blocks for the quick refresh
+    fn load_partial_lut(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_lut_helper(spi, delay, &LUT_PARTIAL_2IN9.get())?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::WriteOtpSelection,
+            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00],
+        )?;
+        self.interface
+            .cmd_with_data(spi, Command::BorderWaveformControl, &[0x80])?;
+        self.interface
+            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC0])?;
+        self.interface.cmd(spi, Command::MasterActivation)?;
+
+        self.wait_until_idle(spi, delay)
+    }
+
     /// Set your own LUT, this function is also used internally for set_lut
     fn set_lut_helper(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         buffer: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.interface
             .cmd_with_data(spi, Command::WriteLutRegister, buffer)?;
@@ -398,10 +644,11 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.interface
-            .cmd_with_data(spi, Command::WriteRam2, buffer)
+            .cmd_with_data(spi, Command::WriteRam2, buffer)?;
+        Ok(())
     }
 
     /// To be used immediately after `update_old_frame`.
@@ -410,23 +657,11 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.interface.reset(delay, 10_000, 2_000);
 
-        self.set_lut_helper(spi, delay, &LUT_PARTIAL_2IN9)?;
-        self.interface.cmd_with_data(
-            spi,
-            Command::WriteOtpSelection,
-            &[0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00],
-        )?;
-        self.interface
-            .cmd_with_data(spi, Command::BorderWaveformControl, &[0x80])?;
-        self.interface
-            .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0xC0])?;
-        self.interface.cmd(spi, Command::MasterActivation)?;
-
-        self.wait_until_idle(spi, delay)?;
+        self.load_partial_lut(spi, delay)?;
 
         self.use_full_frame(spi, delay)?;
 
@@ -436,7 +671,11 @@ where
     }
 
     /// For a quick refresh of the new updated frame. To be used immediately after `update_new_frame`
-    fn display_new_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.interface
             .cmd_with_data(spi, Command::DisplayUpdateControl2, &[0x0F])?;
@@ -451,7 +690,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_new_frame(spi, buffer, delay)?;
         self.display_new_frame(spi, delay)?;
         Ok(())
@@ -468,9 +707,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        //TODO supported by display?
-        unimplemented!()
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
     }
 
     /// Partial quick refresh not supported yet
@@ -484,9 +722,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        //TODO supported by display?
-        unimplemented!()
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
     }
 
     /// Partial quick refresh not supported yet
@@ -499,9 +736,8 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        //TODO supported by display?
-        unimplemented!()
+    ) -> Result<(), Error<SPI::Error>> {
+        Err(Error::Unsupported)
     }
 }
 
@@ -515,4 +751,33 @@ mod tests {
         assert_eq!(HEIGHT, 296);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[test]
+    fn lut_source_follows_refresh_mode() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut epd: Epd2in9<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in9::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(epd.refresh_mode(), RefreshLut::Full);
+        assert_eq!(epd.lut_source(), LutSource::Otp);
+
+        epd.refresh = RefreshLut::Quick;
+        assert_eq!(epd.refresh_mode(), RefreshLut::Quick);
+        assert_eq!(epd.lut_source(), LutSource::Register);
+    }
+
+    #[test]
+    fn fast_refresh() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut epd: Epd2in9<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd2in9::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert!(!epd.fast_refresh());
+        epd.set_fast_refresh(true);
+        assert!(epd.fast_refresh());
+        // the fast waveform still comes from the OTP
+        assert_eq!(epd.lut_source(), LutSource::Otp);
+    }
 }
@@ -12,24 +12,24 @@ use crate::traits;
 #[allow(dead_code)]
 #[derive(Copy, Clone)]
 pub(crate) enum Command {
-    ///
+    /// Sets the number of gates and the scanning order
     GateSetting = 0x01,
-    ///
+    /// Turns off the power of the panel
     PowerOff = 0x02,
-    ///
+    /// Alternative deep sleep command
     Sleep2 = 0x07,
-    ///
+    /// Sets the gate driving voltage
     GateVoltage = 0x03,
-    ///
+    /// Sets the source driving voltage
     GateVoltageSource = 0x04,
-    ///
+    /// Sets the booster soft start parameters
     BoosterSoftStartControl = 0x0C,
     /// After this command initiated, the chip will enter Deep Sleep Mode,
     /// BUSY pad will keep output high.
     ///
     /// Note: To exit Deep Sleep Mode, User required to send HWRESET to the driver.
     DeepSleep = 0x10,
-    ///
+    /// Defines the data entry mode (address counter increment and direction)
     DataEntrySequence = 0x11,
     /// This command resets commands and parameters to their S/W Reset default values,
     /// except Deep Sleep Mode.
@@ -53,14 +53,17 @@ pub(crate) enum Command {
     DisplayUpdateSequenceSetting = 0x22,
     /// This command will transfer its data to B/W RAM, until another command is written
     WriteRam = 0x24,
+    /// This command will transfer its data to RED RAM, which holds the old frame
+    /// the quick refresh compares against
+    WriteRam2 = 0x26,
     /// This command writes VCOM register from MCU interface
     WriteVcomRegister = 0x2C,
     /// This command writes LUT register from MCU interface (105 bytes),
     /// which contains the content of VS [nx-LUT], TP #[nX], RP #[n]
     WriteLutRegister = 0x32,
-    ///
+    /// Sets the display options (ping-pong mode, waveform selection)
     DisplayOption = 0x37,
-    ///
+    /// Selects the border waveform
     BorderWaveformControl = 0x3C,
     /// This command specifies the start/end positions of the window address in the X direction,
     /// by an address unit of RAM.
@@ -68,15 +71,15 @@ pub(crate) enum Command {
     /// This command specifies the start/end positions of the window address in the Y direction,
     /// by an address unit of RAM.
     SetRamYAddressStartEndPosition = 0x45,
-    ///
+    /// Fills the red RAM with a regular pattern
     AutoWriteRedRamRegularPattern = 0x46,
-    ///
+    /// Fills the B/W RAM with a regular pattern
     AutoWriteBwRamRegularPattern = 0x47,
     /// This command makes the initial settings for the RAM X address in the address counter (AC)
     SetRamXAddressCounter = 0x4E,
     /// This command makes the initial settings for the RAM Y address in the address counter (AC)
     SetRamYAddressCounter = 0x4F,
-    ///
+    /// Sleep command (undocumented, used by the Waveshare reference code)
     Sleep = 0x50,
 }
 
@@ -15,8 +15,12 @@ use self::constants::*;
 
 use crate::buffer_len;
 use crate::color::Color;
-use crate::interface::DisplayInterface;
-use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, QuickRefresh, RefreshLut,
+};
 
 /// Width of the display.
 pub const WIDTH: u32 = 280;
@@ -27,6 +31,16 @@ pub const HEIGHT: u32 = 480;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
 
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 3000,
+    min_refresh_interval_ms: 0,
+};
+
 const IS_BUSY_LOW: bool = false;
 
 /// Display with Fullsize buffer for use with the 3in7 EPD
@@ -45,9 +59,36 @@ pub struct EPD3in7<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     /// Background Color
     background_color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Animation frames between two cleanup refreshes, 0 if the animation mode is off
+    animation_cleanup: u16,
+    /// Animation frames shown since the last cleanup refresh
+    animation_frames: u16,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        EPD3in7 {
+            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh: RefreshLut::Full,
+            animation_cleanup: 0,
+            animation_frames: 0,
+        }
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
     for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -57,9 +98,9 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         // reset the device
-        self.interface.reset(delay, 30, 10);
+        self.interface.reset(delay, 30_000, 10_000);
 
         self.interface.cmd(spi, Command::SwReset)?;
         delay.delay_us(300000u32);
@@ -102,26 +143,28 @@ where
             &[0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0x4F, 0xFF, 0xFF, 0xFF, 0xFF],
         )?;
 
-        self.interface.cmd_with_data(
-            spi,
-            Command::SetRamXAddressStartEndPosition,
-            &[0x00, 0x00, 0x17, 0x01],
-        )?;
-        self.interface.cmd_with_data(
-            spi,
-            Command::SetRamYAddressStartEndPosition,
-            &[0x00, 0x00, 0xDF, 0x01],
-        )?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
 
         self.interface
             .cmd_with_data(spi, Command::DisplayUpdateSequenceSetting, &[0xCF])?;
 
-        self.set_lut(spi, delay, Some(RefreshLut::Full))?;
+        self.set_lut(spi, delay, None)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 30_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -133,36 +176,6 @@ where
 {
     type DisplayColor = Color;
 
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let mut epd = EPD3in7 {
-            interface: DisplayInterface::new(cs, busy, dc, rst, delay_us),
-            background_color: DEFAULT_BACKGROUND_COLOR,
-        };
-
-        epd.init(spi, delay)?;
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.cmd_with_data(spi, Command::Sleep, &[0xF7])?;
-        self.interface.cmd(spi, Command::PowerOff)?;
-        self.interface
-            .cmd_with_data(spi, Command::Sleep2, &[0xA5])?;
-        Ok(())
-    }
-
     fn set_background_color(&mut self, color: Self::DisplayColor) {
         self.background_color = color;
     }
@@ -179,39 +192,94 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        assert!(buffer.len() == buffer_len(WIDTH as usize, HEIGHT as usize));
-        self.interface
-            .cmd_with_data(spi, Command::SetRamXAddressCounter, &[0x00, 0x00])?;
-        self.interface
-            .cmd_with_data(spi, Command::SetRamYAddressCounter, &[0x00, 0x00])?;
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        self.set_ram_counter(spi, 0, 0)?;
 
         self.interface
             .cmd_with_data(spi, Command::WriteRam, buffer)?;
 
+        if self.refresh == RefreshLut::Full {
+            // Always keep the old frame equal to the current one if not doing quick refreshes
+            self.set_ram_counter(spi, 0, 0)?;
+            self.interface
+                .cmd_with_data(spi, Command::WriteRam2, buffer)?;
+        }
+
         Ok(())
     }
 
-    #[allow(unused)]
+    /// Transmits the new data of a window, `x` and `width` must be multiples of 8
+    ///
+    /// The quick refresh compares it with the old frame, see [`QuickRefresh`]
     fn update_partial_frame(
         &mut self,
         spi: &mut SPI,
-        delay: &mut DELAY,
+        _delay: &mut DELAY,
         buffer: &[u8],
         x: u32,
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
-        todo!()
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
+        self.write_window(spi, Command::WriteRam, buffer, x, y, width, height)?;
+        Ok(())
+    }
+
+    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.set_ram_counter(spi, 0, 0)?;
+
+        let color = self.background_color.get_byte_value();
+        self.interface.cmd(spi, Command::WriteRam)?;
+        self.interface
+            .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+
+        if self.refresh == RefreshLut::Full {
+            self.set_ram_counter(spi, 0, 0)?;
+            self.interface.cmd(spi, Command::WriteRam2)?;
+            self.interface
+                .data_x_times(spi, color, WIDTH / 8 * HEIGHT)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
     }
+}
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         //self.interface
         //    .cmd_with_data(spi, Command::WRITE_LUT_REGISTER, &LUT_1GRAY_GC)?;
         self.interface.cmd(spi, Command::DisplayUpdateSequence)?;
@@ -224,43 +292,373 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+        refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        let buffer = match self.refresh {
+            RefreshLut::Full => LUT_1GRAY_GC.get(),
+            RefreshLut::Quick => LUT_1GRAY_DU.get(),
+        };
+
         self.interface
-            .cmd_with_data(spi, Command::SetRamXAddressCounter, &[0x00, 0x00])?;
+            .cmd_with_data(spi, Command::WriteLutRegister, &buffer)?;
+        Ok(())
+    }
+
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
         self.interface
-            .cmd_with_data(spi, Command::SetRamYAddressCounter, &[0x00, 0x00])?;
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
 
-        let color = self.background_color.get_byte_value();
-        self.interface.cmd(spi, Command::WriteRam)?;
-        self.interface.data_x_times(spi, color, WIDTH * HEIGHT)?;
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 
+    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.cmd_with_data(spi, Command::Sleep, &[0xF7])?;
+        self.interface.cmd(spi, Command::PowerOff)?;
+        self.interface
+            .cmd_with_data(spi, Command::Sleep2, &[0xA5])?;
         Ok(())
     }
+}
 
-    fn set_lut(
+impl<SPI, CS, BUSY, DC, RST, DELAY> EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Enables the animation mode, see
+    /// [`update_and_display_animation_frame`](EPD3in7::update_and_display_animation_frame)
+    ///
+    /// Every `cleanup_interval` frames, at least 1, a full refresh removes the ghosting
+    /// the animation frames leave behind.
+    pub fn enable_animation(&mut self, cleanup_interval: u16) {
+        self.animation_cleanup = cleanup_interval.max(1);
+        self.animation_frames = 0;
+    }
+
+    /// Disables the animation mode, cleaning up after the frames shown since the last
+    /// cleanup refresh
+    pub fn disable_animation(
         &mut self,
         spi: &mut SPI,
-        _delay: &mut DELAY,
-        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        if self.animation_frames > 0 {
+            self.cleanup_refresh(spi, delay)?;
+        }
+        self.animation_cleanup = 0;
+        Ok(())
+    }
+
+    /// Shows a frame of an animation with the A2 waveform, which drives only the changed
+    /// pixels and takes well below 500ms
+    ///
+    /// The A2 waveform leaves more ghosting than the quick refresh, which adds up over the
+    /// frames, so the animation mode has to be enabled with
+    /// [`enable_animation`](EPD3in7::enable_animation) first. It returns
+    /// [`Error::Unsupported`] otherwise.
+    pub fn update_and_display_animation_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        if self.animation_cleanup == 0 {
+            return Err(Error::Unsupported);
+        }
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+
+        self.set_ram_counter(spi, 0, 0)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegister, &LUT_1GRAY_A2.get())?;
+        self.interface.cmd(spi, Command::DisplayUpdateSequence)?;
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+
+        // The next frame is compared with this one
+        self.set_ram_counter(spi, 0, 0)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam2, buffer)?;
+
+        self.animation_frames += 1;
+        if self.animation_frames >= self.animation_cleanup {
+            self.cleanup_refresh(spi, delay)?;
+        } else {
+            self.set_lut(spi, delay, None)?;
+        }
+        Ok(())
+    }
+
+    /// Redraws the frame shown with a full refresh, removing the ghosting of the
+    /// animation frames
+    pub fn cleanup_refresh(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegister, &LUT_1GRAY_GC.get())?;
+        self.interface.cmd(spi, Command::DisplayUpdateSequence)?;
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        self.animation_frames = 0;
+        self.set_lut(spi, delay, None)
+    }
+
+    /// The X addresses of this controller are in pixels
+    fn set_ram_area(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
     ) -> Result<(), SPI::Error> {
-        let buffer = match refresh_rate {
-            Some(RefreshLut::Full) | None => &LUT_1GRAY_GC,
-            Some(RefreshLut::Quick) => &LUT_1GRAY_DU,
-        };
+        let x_end = x + width - 1;
+        let y_end = y + height - 1;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressStartEndPosition,
+            &[x as u8, (x >> 8) as u8, x_end as u8, (x_end >> 8) as u8],
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressStartEndPosition,
+            &[y as u8, (y >> 8) as u8, y_end as u8, (y_end >> 8) as u8],
+        )
+    }
+
+    fn set_ram_counter(&mut self, spi: &mut SPI, x: u32, y: u32) -> Result<(), SPI::Error> {
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamXAddressCounter,
+            &[x as u8, (x >> 8) as u8],
+        )?;
+        self.interface.cmd_with_data(
+            spi,
+            Command::SetRamYAddressCounter,
+            &[y as u8, (y >> 8) as u8],
+        )
+    }
 
+    /// Writes `buffer` to a window of the RAM selected by `command`, then selects the
+    /// full frame again
+    #[allow(clippy::too_many_arguments)]
+    fn write_window(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(width as usize, height as usize))?;
+        self.set_ram_area(spi, x, y, width, height)?;
+        self.set_ram_counter(spi, x, y)?;
+        self.interface.cmd_with_data(spi, command, buffer)?;
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for EPD3in7<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Transmits the frame currently shown, which the quick refresh compares against.
+    ///
+    /// With `RefreshLut::Full` this is done by `update_frame` already.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        _delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        self.set_ram_counter(spi, 0, 0)?;
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam2, buffer)?;
+        Ok(())
+    }
+
+    /// To be used after `update_old_frame`.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        _delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, buffer_len(WIDTH as usize, HEIGHT as usize))?;
+        self.set_ram_counter(spi, 0, 0)?;
         self.interface
-            .cmd_with_data(spi, Command::WriteLutRegister, buffer)?;
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
         Ok(())
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    /// Quick refresh of the changed pixels with the DU waveform.
+    ///
+    /// Afterwards the new frame must be sent as old frame before the next quick refresh.
+    /// The LUT of the selected refresh mode is loaded again.
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .cmd_with_data(spi, Command::WriteLutRegister, &LUT_1GRAY_DU.get())?;
+        self.interface.cmd(spi, Command::DisplayUpdateSequence)?;
         self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+        self.set_lut(spi, delay, None)
+    }
+
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_new_frame(spi, delay)?;
+        Ok(())
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
+        self.write_window(spi, Command::WriteRam2, buffer, x, y, width, height)?;
+        Ok(())
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
+        self.write_window(spi, Command::WriteRam, buffer, x, y, width, height)?;
         Ok(())
     }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(width as usize, height as usize) as u32;
+
+        for command in [Command::WriteRam, Command::WriteRam2] {
+            self.set_ram_area(spi, x, y, width, height)?;
+            self.set_ram_counter(spi, x, y)?;
+            self.interface.cmd(spi, command)?;
+            self.interface.data_x_times(spi, color, len)?;
+        }
+        self.set_ram_area(spi, 0, 0, WIDTH, HEIGHT)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animation_is_opt_in() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut spi = spi::Mock::new(&[]);
+        let mut epd: EPD3in7<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            EPD3in7::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        let buffer = [0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        assert_eq!(
+            epd.update_and_display_animation_frame(&mut spi, &buffer, &mut MockNoop::new()),
+            Err(Error::Unsupported)
+        );
+
+        epd.enable_animation(0);
+        assert_eq!(epd.animation_cleanup, 1);
+        spi.done();
+    }
 }
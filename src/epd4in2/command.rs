@@ -87,6 +87,9 @@ pub(crate) enum Command {
     ///
     /// from IL0373
     LutBlackToBlack = 0x24,
+    /// This command stores the Look-Up Table of the border (VCOM of the 4 gray levels mode)
+    /// with 7 groups of data.
+    LutBorder = 0x25,
     /// The command controls the PLL clock frequency.
     PllControl = 0x30,
     /// This command reads the temperature sensed by the temperature sensor.
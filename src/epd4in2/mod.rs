@@ -9,7 +9,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
@@ -54,8 +54,13 @@ use embedded_hal::{
     digital::v2::*,
 };
 
-use crate::interface::DisplayInterface;
-use crate::traits::{InternalWiAdditions, QuickRefresh, RefreshLut, WaveshareDisplay};
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::lut_table::Runs;
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, QuickRefresh, RefreshLut,
+};
 
 //The Lookup Tables for the Display
 mod constants;
@@ -67,9 +72,21 @@ pub const WIDTH: u32 = 400;
 pub const HEIGHT: u32 = 300;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: true,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 4000,
+    min_refresh_interval_ms: 0,
+};
 const IS_BUSY_LOW: bool = true;
 
 use crate::color::Color;
+#[cfg(feature = "graphics")]
+use crate::color::Gray2;
 
 pub(crate) mod command;
 use self::command::Command;
@@ -85,6 +102,13 @@ pub type Display4in2 = crate::graphics::Display<
     Color,
 >;
 
+/// Full size buffer with 4 gray levels for use with the 4in2 EPD
+///
+/// To be transmitted with [`Epd4in2::update_frame_gray2`].
+#[cfg(feature = "graphics")]
+pub type Display4in2Gray2 =
+    crate::graphics::Display<WIDTH, HEIGHT, false, { (WIDTH * HEIGHT / 4) as usize }, Gray2>;
+
 /// Epd4in2 driver
 ///
 pub struct Epd4in2<SPI, CS, BUSY, DC, RST, DELAY> {
@@ -96,7 +120,29 @@ pub struct Epd4in2<SPI, CS, BUSY, DC, RST, DELAY> {
     refresh: RefreshLut,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        Epd4in2 {
+            interface,
+            color,
+            refresh: RefreshLut::Full,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -106,7 +152,7 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         // reset the device
         self.interface.reset(delay, 10_000, 10_000);
 
@@ -149,9 +195,20 @@ where
         self.wait_until_idle(spi, delay)?;
         Ok(())
     }
+
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
+        self.init(spi, delay)
+    }
+
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -162,51 +219,6 @@ where
     DELAY: DelayUs<u32>,
 {
     type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-        let color = DEFAULT_BACKGROUND_COLOR;
-
-        let mut epd = Epd4in2 {
-            interface,
-            color,
-            refresh: RefreshLut::Full,
-        };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
-        self.command(spi, Command::VcmDcSetting)?; // VCOM to 0V
-        self.command(spi, Command::PanelSetting)?;
-
-        self.command(spi, Command::PowerSetting)?; //VG&VS to 0V fast
-        for _ in 0..4 {
-            self.send_data(spi, &[0x00])?;
-        }
-
-        self.command(spi, Command::PowerOff)?;
-        self.wait_until_idle(spi, delay)?;
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
-        Ok(())
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
 
     fn set_background_color(&mut self, color: Color) {
         self.color = color;
@@ -224,12 +236,16 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         let color_value = self.color.get_byte_value();
 
@@ -251,12 +267,9 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
 
         self.command(spi, Command::PartialIn)?;
         self.command(spi, Command::PartialWindow)?;
@@ -289,24 +302,7 @@ where
         Ok(())
     }
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.send_resolution(spi)?;
 
@@ -321,13 +317,55 @@ where
             .data_x_times(spi, color_value, WIDTH / 8 * HEIGHT)?;
         Ok(())
     }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.command(spi, Command::DisplayRefresh)?;
+        Ok(())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.command(spi, Command::DisplayRefresh)?;
+        Ok(())
+    }
 
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         if let Some(refresh_lut) = refresh_rate {
             self.refresh = refresh_lut;
         }
@@ -347,10 +385,71 @@ where
         }
     }
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+    fn refresh_mode(&self) -> RefreshLut {
+        self.refresh
+    }
+
+    fn lut_source(&self) -> LutSource {
+        LutSource::Register
+    }
+
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
+        Ok(())
+    }
+
+    fn wait_until_idle_timeout(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
+        self.command(spi, Command::VcmDcSetting)?; // VCOM to 0V
+        self.command(spi, Command::PanelSetting)?;
+
+        self.command(spi, Command::PowerSetting)?; //VG&VS to 0V fast
+        for _ in 0..4 {
+            self.send_data(spi, &[0x00])?;
+        }
+
+        self.command(spi, Command::PowerOff)?;
+        self.wait_until_idle(spi, delay)?;
+        self.interface
+            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
         Ok(())
     }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd4in2<SPI, CS, BUSY, DC, RST, DELAY>
@@ -362,6 +461,81 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
+    /// Same as [`update_frame`](EpdUpdate::update_frame) with a run length encoded frame,
+    /// see `graphics::encode_rle`
+    ///
+    /// The frame is expanded while it is sent, so it is never kept in memory as a whole.
+    pub fn update_frame_rle(
+        &mut self,
+        spi: &mut SPI,
+        encoded: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        let len = (WIDTH / 8 * HEIGHT) as usize;
+        crate::check_rle_len(encoded, len)?;
+        self.wait_until_idle(spi, delay)?;
+        let color_value = self.color.get_byte_value();
+
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        self.interface
+            .data_x_times(spi, color_value, WIDTH / 8 * HEIGHT)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface.data_rle(spi, &mut Runs::new(encoded), len)?;
+        Ok(())
+    }
+
+    /// Transmits a frame with 4 gray levels
+    ///
+    /// The buffer holds 2 bits per pixel, 4 pixels per byte with the first pixel in the
+    /// upper bits: `0b00` is black, `0b01` dark gray, `0b10` light gray and `0b11` white.
+    /// It needs `WIDTH * HEIGHT / 4` bytes, e.g. the buffer of a `Display4in2Gray2`.
+    ///
+    /// The upper bits are sent as old data, the lower bits as new data, which is why it must
+    /// be shown with [`display_frame_gray2`](Epd4in2::display_frame_gray2).
+    pub fn update_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_buffer_len(buffer, (WIDTH * HEIGHT / 4) as usize)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        self.interface.data_gray2_plane(spi, buffer, 1)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.interface.data_gray2_plane(spi, buffer, 0)?;
+        Ok(())
+    }
+
+    /// Displays the frame transmitted with [`update_frame_gray2`](Epd4in2::update_frame_gray2)
+    /// using the 4 gray levels LUTs of the vendor.
+    ///
+    /// The LUTs of the selected refresh mode are loaded again afterwards.
+    pub fn display_frame_gray2(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.set_lut_helper(
+            spi,
+            delay,
+            &LUT_VCOM0_GRAY,
+            &LUT_WW_GRAY,
+            &LUT_BW_GRAY,
+            &LUT_WB_GRAY,
+            &LUT_BB_GRAY,
+        )?;
+        self.cmd_with_data(spi, Command::LutBorder, &LUT_WW_GRAY)?;
+
+        self.command(spi, Command::DisplayRefresh)?;
+        self.wait_until_idle(spi, delay)?;
+
+        self.set_lut(spi, delay, None)
+    }
+
     fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
         self.interface.cmd(spi, command)
     }
@@ -400,7 +574,7 @@ where
         lut_bw: &[u8],
         lut_wb: &[u8],
         lut_bb: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // LUT VCOM
         self.cmd_with_data(spi, Command::LutForVcom, lut_vcom)?;
@@ -464,7 +638,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
@@ -480,7 +654,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         // self.send_resolution(spi)?;
 
@@ -493,7 +667,11 @@ where
 
     /// This is a wrapper around `display_frame` for using this device as a true
     /// `QuickRefresh` device.
-    fn display_new_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
         self.display_frame(spi, delay)
     }
 
@@ -506,9 +684,10 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_new_frame(spi, buffer, delay)?;
-        self.display_frame(spi, delay)
+        self.display_frame(spi, delay)?;
+        Ok(())
     }
 
     fn update_partial_old_frame(
@@ -520,14 +699,10 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
 
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
-
         self.interface.cmd(spi, Command::PartialIn)?;
         self.interface.cmd(spi, Command::PartialWindow)?;
 
@@ -551,12 +726,9 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
 
         self.shift_display(spi, x, y, width, height)?;
 
@@ -576,7 +748,7 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
         self.send_resolution(spi)?;
 
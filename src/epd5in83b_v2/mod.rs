@@ -11,10 +11,14 @@ use embedded_hal::{
     digital::v2::{InputPin, OutputPin},
 };
 
-use crate::color::Color;
-use crate::interface::DisplayInterface;
-use crate::prelude::{TriColor, WaveshareDisplay, WaveshareThreeColorDisplay};
-use crate::traits::{InternalWiAdditions, RefreshLut};
+use crate::color::{BwPlane, ChromaticPlane, Color, PlaneBackgrounds};
+use crate::error::Error;
+use crate::interface::{DisplayInterface, Hooks, PROBE_TIMEOUT_MS};
+use crate::prelude::{TriColor, WaveshareThreeColorDisplay};
+use crate::traits::{
+    sealed::HasHooks, ColorKind, DisplayCapabilities, EpdInit, EpdNew, EpdPower, EpdRefresh,
+    EpdUpdate, LutSource, RefreshLut,
+};
 
 pub(crate) mod command;
 use self::command::Command;
@@ -36,6 +40,16 @@ pub const WIDTH: u32 = 648;
 pub const HEIGHT: u32 = 480;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::TriColor,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: false,
+    full_refresh_ms: 16_000,
+    min_refresh_interval_ms: 180_000,
+};
 const IS_BUSY_LOW: bool = true;
 const NUM_DISPLAY_BITS: u32 = WIDTH * HEIGHT / 8;
 
@@ -46,51 +60,10 @@ pub struct Epd5in83<SPI, CS, BUSY, DC, RST, DELAY> {
     interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
     /// Background Color
     color: Color,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // Reset the device
-        self.interface.reset(delay, 10_000, 10_000);
-
-        // Start the booster
-        self.cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x1e, 0x17])?;
-
-        // Set the power settings: VGH=20V,VGL=-20V,VDH=15V,VDL=-15V
-        self.cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x07, 0x3F, 0x3F])?;
-
-        // Power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_us(5000);
-        self.wait_until_idle(spi, delay)?;
-
-        // Set the panel settings: BWROTP
-        self.cmd_with_data(spi, Command::PanelSetting, &[0x0F])?;
-
-        // Set the real resolution
-        self.send_resolution(spi)?;
-
-        // Disable dual SPI
-        self.cmd_with_data(spi, Command::DualSPI, &[0x00])?;
-
-        // Set Vcom and data interval
-        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x11, 0x07])?;
-
-        // Set S2G and G2S non-overlap periods to 12 (default)
-        self.cmd_with_data(spi, Command::TconSetting, &[0x22])?;
-
-        self.wait_until_idle(spi, delay)?;
-        Ok(())
-    }
+    /// Backgrounds of the planes used by `clear_frame`
+    planes: PlaneBackgrounds,
+    /// Whether a partial window is selected, it is left after the next refresh
+    partial: bool,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareThreeColorDisplay<SPI, CS, BUSY, DC, RST, DELAY>
@@ -107,9 +80,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_achromatic_frame(spi, delay, black)?;
         self.update_chromatic_frame(spi, delay, chromatic)?;
         Ok(())
@@ -119,9 +92,10 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error> {
+        BwPlane(black): BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
+        self.leave_partial_mode(spi)?;
         self.cmd_with_data(spi, Command::DataStartTransmission1, black)?;
         Ok(())
     }
@@ -130,15 +104,20 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        ChromaticPlane(chromatic): ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
+        self.leave_partial_mode(spi)?;
         self.cmd_with_data(spi, Command::DataStartTransmission2, chromatic)?;
         Ok(())
     }
+
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds) {
+        self.planes = backgrounds;
+    }
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
@@ -148,40 +127,91 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
         let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
         let color = DEFAULT_BACKGROUND_COLOR;
 
-        let mut epd = Epd5in83 { interface, color };
+        Epd5in83 {
+            interface,
+            color,
+            planes: color.into(),
+            partial: false,
+        }
+    }
+}
 
-        epd.init(spi, delay)?;
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        // Reset the device
+        self.interface.reset(delay, 10_000, 10_000);
+        self.partial = false;
 
-        Ok(epd)
-    }
+        // Start the booster
+        self.cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x1e, 0x17])?;
 
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+        // Set the power settings: VGH=20V,VGL=-20V,VDH=15V,VDL=-15V
+        self.cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x07, 0x3F, 0x3F])?;
+
+        // Power on
+        self.command(spi, Command::PowerOn)?;
+        delay.delay_us(5000);
         self.wait_until_idle(spi, delay)?;
-        self.command(spi, Command::PowerOff)?;
+
+        // Set the panel settings: BWROTP
+        self.cmd_with_data(spi, Command::PanelSetting, &[0x0F])?;
+
+        // Set the real resolution
+        self.send_resolution(spi)?;
+
+        // Disable dual SPI
+        self.cmd_with_data(spi, Command::DualSPI, &[0x00])?;
+
+        // Set Vcom and data interval
+        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x11, 0x07])?;
+
+        // Set S2G and G2S non-overlap periods to 12 (default)
+        self.cmd_with_data(spi, Command::TconSetting, &[0x22])?;
+
         self.wait_until_idle(spi, delay)?;
-        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.interface.reset(delay, 10_000, 10_000);
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, PROBE_TIMEOUT_MS)?;
         self.init(spi, delay)
     }
 
+    fn free(self) -> (CS, BUSY, DC, RST) {
+        self.interface.release()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    type DisplayColor = Color;
+
     fn set_background_color(&mut self, color: Color) {
         self.color = color;
+        self.planes = color.into();
     }
 
     fn background_color(&self) -> &Color {
@@ -196,20 +226,28 @@ where
         HEIGHT
     }
 
+    fn capabilities(&self) -> DisplayCapabilities {
+        CAPABILITIES
+    }
+
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
-        self.update_achromatic_frame(spi, delay, buffer)?;
+        self.update_achromatic_frame(spi, delay, BwPlane(buffer))?;
         let color = self.color.get_byte_value();
         self.command(spi, Command::DataStartTransmission2)?;
         self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
         Ok(())
     }
 
+    /// Transmits the black/white data of a window, without any chromatic pixels
+    ///
+    /// `x` and `width` must be multiples of 8. Only the window is refreshed by the
+    /// following `display_frame`.
     fn update_partial_frame(
         &mut self,
         spi: &mut SPI,
@@ -219,49 +257,65 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
+        crate::check_partial_window(buffer, x, y, width, height, self.width(), self.height())?;
         self.wait_until_idle(spi, delay)?;
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO panic or error
-        }
+        self.set_partial_window(spi, x, y, width, height)?;
 
-        let hrst_upper = (x / 8) as u8 >> 6;
-        let hrst_lower = ((x / 8) << 3) as u8;
-        let hred_upper = ((x + width) / 8) as u8 >> 6;
-        let hred_lower = (((x + width) / 8) << 3) as u8 & 0b111;
-        let vrst_upper = (y >> 8) as u8;
-        let vrst_lower = y as u8;
-        let vred_upper = ((y + height) >> 8) as u8;
-        let vred_lower = (y + height) as u8;
-        let pt_scan = 0x01; // Gates scan both inside and outside of the partial window. (default)
-
-        self.command(spi, Command::PartialIn)?;
-        self.command(spi, Command::PartialWindow)?;
-        self.send_data(
-            spi,
-            &[
-                hrst_upper, hrst_lower, hred_upper, hred_lower, vrst_upper, vrst_lower, vred_upper,
-                vred_lower, pt_scan,
-            ],
-        )?;
-        self.command(spi, Command::DataStartTransmission1)?;
-        self.send_data(spi, buffer)?;
+        self.cmd_with_data(spi, Command::DataStartTransmission1, buffer)?;
 
-        let color = TriColor::Black.get_byte_value(); //We need it black, so red channel will be rendered transparent
+        // Chromatic bits cleared, so the red channel will be rendered transparent
         self.command(spi, Command::DataStartTransmission2)?;
-        self.interface
-            .data_x_times(spi, color, width * height / 8)?;
+        self.interface.data_x_times(spi, 0x00, width / 8 * height)?;
+        Ok(())
+    }
 
-        self.command(spi, Command::DisplayRefresh)?;
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.wait_until_idle(spi, delay)?;
+        self.leave_partial_mode(spi)?;
+
+        // The Waveshare controllers all implement clear using 0x33
+        self.command(spi, Command::DataStartTransmission1)?;
+        let color = self.planes.achromatic.get_byte_value();
+        self.interface.data_x_times(spi, color, NUM_DISPLAY_BITS)?;
+
+        self.command(spi, Command::DataStartTransmission2)?;
+        let chromatic = self.planes.chromatic_byte(0x00);
+        self.interface
+            .data_x_times(spi, chromatic, NUM_DISPLAY_BITS)?;
 
-        self.command(spi, Command::PartialOut)?;
         Ok(())
     }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> HasHooks for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn hooks(&mut self) -> &mut Hooks {
+        self.interface.hooks()
+    }
+}
 
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
         self.command(spi, Command::DisplayRefresh)?;
         self.wait_until_idle(spi, delay)?;
+        self.leave_partial_mode(spi)?;
         Ok(())
     }
 
@@ -270,38 +324,76 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), Error<SPI::Error>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
+    fn set_lut(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+        _refresh_rate: Option<RefreshLut>,
+    ) -> Result<(), Error<SPI::Error>> {
+        // The LUT of the OTP is the only one supported
+        Ok(())
+    }
 
-        // The Waveshare controllers all implement clear using 0x33
-        self.command(spi, Command::DataStartTransmission1)?;
-        self.interface.data_x_times(spi, 0xFF, NUM_DISPLAY_BITS)?;
+    fn refresh_mode(&self) -> RefreshLut {
+        RefreshLut::Full
+    }
 
-        self.command(spi, Command::DataStartTransmission2)?;
-        self.interface.data_x_times(spi, 0x00, NUM_DISPLAY_BITS)?;
+    fn lut_source(&self) -> LutSource {
+        LutSource::Otp
+    }
 
+    fn is_busy(&self) -> bool {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn wait_until_idle_with(
+        &mut self,
+        _spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface.poll_until_idle(delay, IS_BUSY_LOW, on_poll);
         Ok(())
     }
 
-    fn set_lut(
+    fn wait_until_idle_timeout(
         &mut self,
         _spi: &mut SPI,
-        _delay: &mut DELAY,
-        _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!();
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.interface
+            .wait_until_idle_timeout(delay, IS_BUSY_LOW, timeout_ms)
     }
+}
 
-    fn wait_until_idle(&mut self, _spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.wait_until_idle(delay, IS_BUSY_LOW);
+impl<SPI, CS, BUSY, DC, RST, DELAY> EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.command(spi, Command::PowerOff)?;
+        self.wait_until_idle(spi, delay)?;
+        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
         Ok(())
     }
+
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)
+    }
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd5in83<SPI, CS, BUSY, DC, RST, DELAY>
@@ -313,6 +405,73 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
+    /// Transmits the black/white and the chromatic data of a window
+    ///
+    /// `x` and `width` must be multiples of 8. Only the window is refreshed by the
+    /// following `display_frame`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_partial_color_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        black: &[u8],
+        chromatic: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle(spi, delay)?;
+        self.set_partial_window(spi, x, y, width, height)?;
+
+        self.cmd_with_data(spi, Command::DataStartTransmission1, black)?;
+        self.cmd_with_data(spi, Command::DataStartTransmission2, chromatic)?;
+        Ok(())
+    }
+
+    fn set_partial_window(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), SPI::Error> {
+        // x should be the multiple of 8, the last 3 bit will always be ignored
+        let x_start = x & !0x07;
+        let x_end = (x_start + width - 1) | 0x07;
+        let y_end = y + height - 1;
+
+        if !self.partial {
+            self.command(spi, Command::PartialIn)?;
+            self.partial = true;
+        }
+        self.cmd_with_data(
+            spi,
+            Command::PartialWindow,
+            &[
+                (x_start >> 8) as u8,
+                x_start as u8,
+                (x_end >> 8) as u8,
+                x_end as u8,
+                (y >> 8) as u8,
+                y as u8,
+                (y_end >> 8) as u8,
+                y_end as u8,
+                // Gates scan both inside and outside of the partial window. (default)
+                0x01,
+            ],
+        )
+    }
+
+    fn leave_partial_mode(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+        if self.partial {
+            self.command(spi, Command::PartialOut)?;
+            self.partial = false;
+        }
+        Ok(())
+    }
+
     fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
         self.interface.cmd(spi, command)
     }
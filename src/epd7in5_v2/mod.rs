@@ -9,18 +9,16 @@
 //! Important note for V2:
 //! Revision V2 has been released on 2019.11, the resolution is upgraded to 800×480, from 640×384 of V1.
 //! The hardware and interface of V2 are compatible with V1, however, the related software should be updated.
-
-use embedded_hal::{
-    blocking::{delay::*, spi::Write},
-    digital::v2::{InputPin, OutputPin},
-};
+//!
+//! The driver is the generic driver of the [`Uc8179`] controller with the configuration
+//! [`Panel7in5V2`], see [`controllers::uc8179`](crate::controllers::uc8179).
 
 use crate::color::Color;
-use crate::interface::DisplayInterface;
-use crate::traits::{InternalWiAdditions, RefreshLut, WaveshareDisplay};
+use crate::controllers::Uc8179;
+use crate::generic::{Epd, Panel7in5V2};
+use crate::traits::{ColorKind, DisplayCapabilities};
+use crate::transport::SingleSpi;
 
-pub(crate) mod command;
-use self::command::Command;
 use crate::buffer_len;
 
 /// Full size buffer for use with the 7in5 v2 EPD
@@ -39,220 +37,28 @@ pub const WIDTH: u32 = 800;
 pub const HEIGHT: u32 = 480;
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
-const IS_BUSY_LOW: bool = true;
+
+/// What this driver supports
+pub const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+    color: ColorKind::BlackWhite,
+    grayscale: false,
+    partial_refresh: true,
+    quick_refresh: true,
+    full_refresh_ms: 5000,
+    min_refresh_interval_ms: 0,
+};
 
 /// Epd7in5 (V2) driver
 ///
-pub struct Epd7in5<SPI, CS, BUSY, DC, RST, DELAY> {
-    /// Connection Interface
-    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
-    /// Background Color
-    color: Color,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd7in5<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // Reset the device
-        self.interface.reset(delay, 10_000, 2_000);
-
-        // V2 procedure as described here:
-        // https://github.com/waveshare/e-Paper/blob/master/RaspberryPi%26JetsonNano/python/lib/waveshare_epd/epd7in5bc_V2.py
-        // and as per specs:
-        // https://www.waveshare.com/w/upload/6/60/7.5inch_e-Paper_V2_Specification.pdf
-
-        self.cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x27, 0x17])?;
-        self.cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x17, 0x3F, 0x3F])?;
-        self.command(spi, Command::PowerOn)?;
-        self.wait_until_idle(spi, delay)?;
-        self.cmd_with_data(spi, Command::PanelSetting, &[0x1F])?;
-        self.cmd_with_data(spi, Command::PllControl, &[0x06])?;
-        self.cmd_with_data(spi, Command::TconResolution, &[0x03, 0x20, 0x01, 0xE0])?;
-        self.cmd_with_data(spi, Command::DualSpi, &[0x00])?;
-        self.cmd_with_data(spi, Command::TconSetting, &[0x22])?;
-        self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x10, 0x07])?;
-        self.wait_until_idle(spi, delay)?;
-        Ok(())
-    }
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd7in5<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-        delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst, delay_us);
-        let color = DEFAULT_BACKGROUND_COLOR;
-
-        let mut epd = Epd7in5 { interface, color };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.command(spi, Command::PowerOff)?;
-        self.wait_until_idle(spi, delay)?;
-        self.cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
-        Ok(())
-    }
-
-    fn update_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.cmd_with_data(spi, Command::DataStartTransmission2, buffer)?;
-        Ok(())
-    }
-
-    fn update_partial_frame(
-        &mut self,
-        _spi: &mut SPI,
-        _delay: &mut DELAY,
-        _buffer: &[u8],
-        _x: u32,
-        _y: u32,
-        _width: u32,
-        _height: u32,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!();
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle(spi, delay)?;
-        self.send_resolution(spi)?;
-
-        self.command(spi, Command::DataStartTransmission1)?;
-        self.interface.data_x_times(spi, 0x00, WIDTH * HEIGHT / 8)?;
-
-        self.command(spi, Command::DataStartTransmission2)?;
-        self.interface.data_x_times(spi, 0x00, WIDTH * HEIGHT / 8)?;
-
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn set_background_color(&mut self, color: Color) {
-        self.color = color;
-    }
-
-    fn background_color(&self) -> &Color {
-        &self.color
-    }
-
-    fn width(&self) -> u32 {
-        WIDTH
-    }
-
-    fn height(&self) -> u32 {
-        HEIGHT
-    }
-
-    fn set_lut(
-        &mut self,
-        _spi: &mut SPI,
-        _delay: &mut DELAY,
-        _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!();
-    }
-
-    fn wait_until_idle(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface
-            .wait_until_idle_with_cmd(spi, delay, IS_BUSY_LOW, Command::GetStatus)
-    }
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> Epd7in5<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
-        self.interface.cmd(spi, command)
-    }
-
-    fn send_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
-        self.interface.data(spi, data)
-    }
-
-    fn cmd_with_data(
-        &mut self,
-        spi: &mut SPI,
-        command: Command,
-        data: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.interface.cmd_with_data(spi, command, data)
-    }
-
-    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
-        let w = self.width();
-        let h = self.height();
-
-        self.command(spi, Command::TconResolution)?;
-        self.send_data(spi, &[(w >> 8) as u8])?;
-        self.send_data(spi, &[w as u8])?;
-        self.send_data(spi, &[(h >> 8) as u8])?;
-        self.send_data(spi, &[h as u8])
-    }
-}
+/// `TRANSPORT` sends the frames, see [`with_transport`](Epd::with_transport).
+pub type Epd7in5<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT = SingleSpi> =
+    Epd<Uc8179, Panel7in5V2, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::Error;
+    use crate::traits::{EpdNew, EpdUpdate};
 
     #[test]
     fn epd_size() {
@@ -260,4 +66,133 @@ mod tests {
         assert_eq!(HEIGHT, 480);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[test]
+    fn active_window() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut epd: Epd7in5<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd7in5::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(epd.active_window(), None);
+
+        epd.set_active_window(16, 10, 64, 20).unwrap();
+        assert_eq!(epd.active_window(), Some((16, 10, 64, 20)));
+        assert!(matches!(
+            epd.set_active_window(12, 10, 64, 20),
+            Err(Error::InvalidRegion)
+        ));
+        assert!(matches!(
+            epd.set_active_window(0, 470, 64, 20),
+            Err(Error::InvalidRegion)
+        ));
+        assert_eq!(epd.active_window(), Some((16, 10, 64, 20)));
+
+        epd.reset_active_window();
+        assert_eq!(epd.active_window(), None);
+    }
+
+    #[test]
+    fn temperature_offset() {
+        use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+        let pin = || Mock::new(&[]);
+        let mut epd: Epd7in5<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd7in5::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        assert_eq!(epd.temperature_offset(), 0);
+
+        epd.set_temperature_offset(-4).unwrap();
+        epd.set_temperature_offset(3).unwrap();
+        assert!(matches!(
+            epd.set_temperature_offset(4),
+            Err(Error::OutOfRange)
+        ));
+        assert!(matches!(
+            epd.set_temperature_offset(-5),
+            Err(Error::OutOfRange)
+        ));
+        assert_eq!(epd.temperature_offset(), 3);
+    }
+
+    #[test]
+    fn frames_are_sent_with_the_transport() {
+        extern crate std;
+        use crate::interface::DisplayInterface;
+        use crate::transport::Transport;
+        use embedded_hal::blocking::spi::Write;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi, MockError,
+        };
+        use std::{vec, vec::Vec};
+
+        // Sends the frames inverted, to tell them apart from the rest of the traffic
+        struct Inverting(u8);
+
+        impl Transport<spi::Mock> for Inverting {
+            fn data_lines(&self) -> u8 {
+                self.0
+            }
+
+            fn write_data(&mut self, spi: &mut spi::Mock, data: &[u8]) -> Result<(), MockError> {
+                spi.write(&data.iter().map(|byte| !byte).collect::<Vec<_>>())
+            }
+        }
+
+        let pin = || Mock::new(&[]);
+        let mut delay = MockNoop::new();
+        let frame_len = (WIDTH / 8 * HEIGHT) as usize;
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![0x71]),
+            spi::Transaction::write(vec![0x13]),
+            spi::Transaction::write(vec![0x0F; frame_len]),
+        ]);
+        let toggles = |n| {
+            (0..n)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<Vec<_>>()
+        };
+        let mut cs = Mock::new(&toggles(3));
+        let mut dc = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]);
+        let mut busy = Mock::new(&[Transaction::get(State::High)]);
+        let interface = DisplayInterface::with_transport(
+            cs.clone(),
+            busy.clone(),
+            dc.clone(),
+            pin(),
+            None,
+            Inverting(2),
+        );
+        let mut epd = Epd7in5::from_interface(interface);
+        assert_eq!(epd.interface.frame_data_lines(), 2);
+
+        epd.update_frame(&mut spi, &vec![0xF0; frame_len], &mut delay)
+            .unwrap();
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+
+        let mut spi = spi::Mock::new(&[]);
+        assert!(matches!(
+            Epd7in5::with_transport(
+                &mut spi,
+                pin(),
+                pin(),
+                pin(),
+                pin(),
+                &mut delay,
+                None,
+                Inverting(4)
+            ),
+            Err(Error::Unsupported)
+        ));
+        spi.done();
+    }
 }
@@ -0,0 +1,397 @@
+//! Several panels sharing the data bus, each with its own CS and BUSY pin
+//!
+//! [`EpdArray`] transmits the frames of all panels one after the other, but triggers their
+//! refreshes back-to-back and only then waits for all BUSY pins. The panels refresh in
+//! parallel, so updating all of them takes about as long as updating a single one.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use epd_waveshare::{epd7in5_v2::*, epd_array::EpdArray, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let pin = || pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!// Both panels are on the same SPI bus, each driver gets its own CS and BUSY pin
+//!let left = Epd7in5::new(&mut spi, pin(), pin(), pin(), pin(), &mut delay, None)?;
+//!let right = Epd7in5::new(&mut spi, pin(), pin(), pin(), pin(), &mut delay, None)?;
+//!let mut panels = EpdArray::new([left, right]);
+//!
+//!let left_frame = Display7in5::default();
+//!let right_frame = Display7in5::default();
+//!panels.update_and_display_frames(
+//!    &mut spi,
+//!    [left_frame.buffer(), right_frame.buffer()],
+//!    &mut delay,
+//!)?;
+//!panels.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::error::Error;
+use crate::traits::{EpdPower, EpdRefresh, EpdUpdate};
+
+/// Drivers of `N` panels sharing the data bus
+pub struct EpdArray<EPD, const N: usize> {
+    epds: [EPD; N],
+}
+
+impl<EPD, const N: usize> EpdArray<EPD, N> {
+    /// Takes the already created drivers of the panels
+    pub fn new(epds: [EPD; N]) -> Self {
+        EpdArray { epds }
+    }
+
+    /// Returns the drivers, e.g. to update a single panel
+    pub fn panels(&mut self) -> &mut [EPD; N] {
+        &mut self.epds
+    }
+
+    /// Returns the drivers
+    pub fn release(self) -> [EPD; N] {
+        self.epds
+    }
+
+    /// Transmits one frame to each panel, in the order of the panels
+    pub fn update_frames<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        buffers: [&[u8]; N],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        for (epd, buffer) in self.epds.iter_mut().zip(buffers) {
+            epd.update_frame(spi, buffer, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Triggers the refresh of all panels back-to-back and waits until all of them are done
+    pub fn display_frames<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        for epd in self.epds.iter_mut() {
+            epd.display_frame(spi, delay)?;
+        }
+        self.wait_until_idle(spi, delay)
+    }
+
+    /// Transmits one frame to each panel, then refreshes them like
+    /// [`display_frames`](EpdArray::display_frames)
+    pub fn update_and_display_frames<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        buffers: [&[u8]; N],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        self.update_frames(spi, buffers, delay)?;
+        self.display_frames(spi, delay)?;
+        Ok(())
+    }
+
+    /// Checks if any of the panels is busy, without blocking
+    pub fn is_busy<SPI, CS, BUSY, DC, RST, DELAY>(&self) -> bool
+    where
+        EPD: EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        self.epds.iter().any(|epd| epd.is_busy())
+    }
+
+    /// Waits until all panels are idle
+    pub fn wait_until_idle<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        for epd in self.epds.iter_mut() {
+            epd.wait_until_idle(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Puts all panels to sleep
+    pub fn sleep<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdPower<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        for epd in self.epds.iter_mut() {
+            epd.sleep(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Wakes all panels up
+    pub fn wake_up<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdPower<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        for epd in self.epds.iter_mut() {
+            epd.wake_up(spi, delay)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::color::Color;
+    use crate::traits::{
+        DisplayCapabilities, EpdInit, EpdPower, EpdRefresh, EpdUpdate, LutSource, RefreshLut,
+    };
+    use core::cell::RefCell;
+    use embedded_hal_mock::{delay::MockNoop, pin::Mock as Pin, spi::Mock as Spi, MockError};
+    use std::vec::Vec;
+
+    #[derive(Debug, PartialEq)]
+    enum Call {
+        Update(u8),
+        Display(u8),
+        Wait(u8),
+    }
+
+    // Only records the calls of the array
+    struct Panel<'a> {
+        id: u8,
+        calls: &'a RefCell<Vec<Call>>,
+    }
+
+    impl EpdInit<Spi, Pin, Pin, Pin, Pin, MockNoop> for Panel<'_> {
+        fn init(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+
+        fn probe(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+
+        fn free(self) -> (Pin, Pin, Pin, Pin) {
+            unimplemented!()
+        }
+    }
+
+    impl EpdUpdate<Spi, Pin, Pin, Pin, Pin, MockNoop> for Panel<'_> {
+        type DisplayColor = Color;
+
+        fn set_background_color(&mut self, _: Color) {}
+
+        fn background_color(&self) -> &Color {
+            &Color::White
+        }
+
+        fn width(&self) -> u32 {
+            8
+        }
+
+        fn height(&self) -> u32 {
+            1
+        }
+
+        fn capabilities(&self) -> DisplayCapabilities {
+            crate::epd7in5_v2::CAPABILITIES
+        }
+
+        fn update_frame(
+            &mut self,
+            _: &mut Spi,
+            _: &[u8],
+            _: &mut MockNoop,
+        ) -> Result<(), Error<MockError>> {
+            self.calls.borrow_mut().push(Call::Update(self.id));
+            Ok(())
+        }
+
+        fn update_partial_frame(
+            &mut self,
+            _: &mut Spi,
+            _: &mut MockNoop,
+            _: &[u8],
+            _: u32,
+            _: u32,
+            _: u32,
+            _: u32,
+        ) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+
+        fn clear_frame(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+    }
+
+    impl EpdRefresh<Spi, Pin, Pin, Pin, Pin, MockNoop> for Panel<'_> {
+        fn display_frame(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            self.calls.borrow_mut().push(Call::Display(self.id));
+            Ok(())
+        }
+
+        fn update_and_display_frame(
+            &mut self,
+            _: &mut Spi,
+            _: &[u8],
+            _: &mut MockNoop,
+        ) -> Result<(), Error<MockError>> {
+            unimplemented!()
+        }
+
+        fn set_lut(
+            &mut self,
+            _: &mut Spi,
+            _: &mut MockNoop,
+            _: Option<RefreshLut>,
+        ) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+
+        fn refresh_mode(&self) -> RefreshLut {
+            RefreshLut::Full
+        }
+
+        fn lut_source(&self) -> LutSource {
+            LutSource::Otp
+        }
+
+        fn is_busy(&self) -> bool {
+            false
+        }
+
+        fn wait_until_idle_with(
+            &mut self,
+            _: &mut Spi,
+            _: &mut MockNoop,
+            _: &mut dyn FnMut(),
+        ) -> Result<(), Error<MockError>> {
+            self.calls.borrow_mut().push(Call::Wait(self.id));
+            Ok(())
+        }
+
+        fn wait_until_idle_timeout(
+            &mut self,
+            _: &mut Spi,
+            _: &mut MockNoop,
+            _: u32,
+        ) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+    }
+
+    impl EpdPower<Spi, Pin, Pin, Pin, Pin, MockNoop> for Panel<'_> {
+        fn sleep(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+
+        fn wake_up(&mut self, _: &mut Spi, _: &mut MockNoop) -> Result<(), Error<MockError>> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn refreshes_are_triggered_before_waiting() {
+        let calls = RefCell::new(Vec::new());
+        let mut panels = EpdArray::new([
+            Panel {
+                id: 0,
+                calls: &calls,
+            },
+            Panel {
+                id: 1,
+                calls: &calls,
+            },
+        ]);
+        let mut spi = Spi::new(&[]);
+        let mut delay = MockNoop::new();
+
+        panels
+            .update_and_display_frames(&mut spi, [&[0xFF], &[0x00]], &mut delay)
+            .unwrap();
+
+        assert_eq!(
+            *calls.borrow(),
+            [
+                Call::Update(0),
+                Call::Update(1),
+                Call::Display(0),
+                Call::Display(1),
+                Call::Wait(0),
+                Call::Wait(1),
+            ]
+        );
+        spi.done();
+    }
+}
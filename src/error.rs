@@ -0,0 +1,61 @@
+//! Error type of the functions going beyond plain spi communication
+
+/// Errors which can occur while driving the display
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<SpiError> {
+    /// Error of the underlying spi bus
+    Spi(SpiError),
+    /// The minimum interval between two refreshes has not elapsed yet
+    TooSoon {
+        /// Milliseconds until the next refresh is allowed
+        remaining_ms: u64,
+    },
+    /// The BUSY pin didn't release within the timeout, e.g. because of a broken or
+    /// miswired connection to the display
+    BusyStuck,
+    /// The window of a partial update isn't aligned to whole bytes, exceeds the display or
+    /// doesn't match the size of the buffer
+    InvalidRegion,
+    /// The buffer doesn't have the size the display expects
+    InvalidBufferSize {
+        /// Number of bytes the display expects
+        expected: usize,
+        /// Number of bytes of the given buffer
+        actual: usize,
+    },
+    /// The display doesn't support the operation, at least not in its current mode
+    Unsupported,
+    /// A setting is outside of the range the controller accepts
+    OutOfRange,
+    /// The data read back from the RAM of the controller differs from the data written
+    VerificationFailed,
+}
+
+impl<SpiError> From<SpiError> for Error<SpiError> {
+    fn from(error: SpiError) -> Self {
+        Error::Spi(error)
+    }
+}
+
+impl<SpiError: core::fmt::Debug> core::fmt::Display for Error<SpiError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Spi(e) => write!(f, "Spi error: {:?}", e),
+            Error::TooSoon { remaining_ms } => {
+                write!(f, "Refresh requested {}ms too soon", remaining_ms)
+            }
+            Error::BusyStuck => write!(f, "Busy pin didn't release"),
+            Error::InvalidRegion => write!(f, "Invalid region for a partial update"),
+            Error::InvalidBufferSize { expected, actual } => {
+                write!(f, "Buffer of {} bytes given, {} expected", actual, expected)
+            }
+            Error::Unsupported => write!(f, "Operation not supported by the display"),
+            Error::OutOfRange => write!(f, "Setting out of the range of the controller"),
+            Error::VerificationFailed => write!(f, "Data read back from the RAM differs"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<SpiError: core::fmt::Debug> std::error::Error for Error<SpiError> {}
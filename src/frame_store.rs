@@ -0,0 +1,255 @@
+//! Keeping the last frame in user provided storage, e.g. flash or EEPROM
+//!
+//! A quick refresh compares the new frame with the one shown, which the controller loses
+//! when the panel is powered off and the MCU loses with its RAM in deep sleep.
+//! [`PersistentFrame`] stores every frame it shows with a [`FrameStore`] and sends the
+//! stored frame back as old frame after waking up, so quick refreshes keep working across
+//! power cycles.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::frame_store::FrameStoreError<MockError, ()>> {
+//!use epd_waveshare::{epd4in2::*, frame_store::*, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# struct Eeprom;
+//!# impl FrameStore for Eeprom {
+//!#     type Error = ();
+//!#     fn load(&mut self, frame: &mut [u8]) -> Result<bool, ()> { Ok(false) }
+//!#     fn store(&mut self, frame: &[u8]) -> Result<(), ()> { Ok(()) }
+//!# }
+//!# let display = Display4in2::default();
+//!
+//!let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)
+//!    .map_err(FrameStoreError::Display)?;
+//!let mut frames = PersistentFrame::new(Eeprom);
+//!
+//!// After waking up, send the frame shown before the deep sleep as old frame
+//!let mut old = [0u8; buffer_len(WIDTH as usize, HEIGHT as usize)];
+//!if !frames.restore(&mut epd, &mut spi, &mut old, &mut delay)? {
+//!    // Nothing stored yet, start with a full refresh
+//!    frames.update_and_display_frame(&mut epd, &mut spi, display.buffer(), &mut delay)?;
+//!}
+//!
+//!frames.update_and_display_new_frame(&mut epd, &mut spi, display.buffer(), &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::error::Error;
+use crate::traits::{EpdRefresh, EpdUpdate, QuickRefresh};
+
+/// Storage for one frame, provided by the user
+pub trait FrameStore {
+    /// Error of the storage
+    type Error;
+
+    /// Reads the stored frame into `frame`, returns false if no frame is stored
+    fn load(&mut self, frame: &mut [u8]) -> Result<bool, Self::Error>;
+
+    /// Replaces the stored frame
+    fn store(&mut self, frame: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Errors of the display or of the storage
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FrameStoreError<SpiError, StoreError> {
+    /// Error while driving the display
+    Display(Error<SpiError>),
+    /// Error of the [`FrameStore`]
+    Store(StoreError),
+}
+
+impl<SpiError, StoreError> core::fmt::Display for FrameStoreError<SpiError, StoreError>
+where
+    SpiError: core::fmt::Debug,
+    StoreError: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameStoreError::Display(e) => write!(f, "Display error: {}", e),
+            FrameStoreError::Store(e) => write!(f, "Frame store error: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<SpiError, StoreError> std::error::Error for FrameStoreError<SpiError, StoreError>
+where
+    SpiError: core::fmt::Debug,
+    StoreError: core::fmt::Debug,
+{
+}
+
+impl<SpiError, StoreError> From<Error<SpiError>> for FrameStoreError<SpiError, StoreError> {
+    fn from(error: Error<SpiError>) -> Self {
+        FrameStoreError::Display(error)
+    }
+}
+
+/// Stores the frames shown and restores the last one, see the [module documentation](self)
+pub struct PersistentFrame<STORE> {
+    store: STORE,
+}
+
+impl<STORE: FrameStore> PersistentFrame<STORE> {
+    /// Creates a new wrapper around the storage
+    pub fn new(store: STORE) -> Self {
+        PersistentFrame { store }
+    }
+
+    /// Loads the stored frame into `buffer` and sends it to the display as old frame
+    ///
+    /// Returns false without sending anything if no frame is stored, the next frame should
+    /// be shown with a full refresh then. The buffer needs the size of a frame.
+    pub fn restore<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        buffer: &mut [u8],
+        delay: &mut DELAY,
+    ) -> Result<bool, FrameStoreError<SPI::Error, STORE::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+            + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+            + QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        crate::check_buffer_len(buffer, epd.plane_len())?;
+        if !self.store.load(buffer).map_err(FrameStoreError::Store)? {
+            return Ok(false);
+        }
+        epd.update_old_frame(spi, buffer, delay)?;
+        Ok(true)
+    }
+
+    /// Same as [`EpdRefresh::update_and_display_frame`],
+    /// storing the frame once it is shown
+    pub fn update_and_display_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), FrameStoreError<SPI::Error, STORE::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        epd.update_and_display_frame(spi, buffer, delay)?;
+        self.store.store(buffer).map_err(FrameStoreError::Store)
+    }
+
+    /// Same as [`QuickRefresh::update_and_display_new_frame`], storing the frame once it
+    /// is shown
+    pub fn update_and_display_new_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), FrameStoreError<SPI::Error, STORE::Error>>
+    where
+        EPD: QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        epd.update_and_display_new_frame(spi, buffer, delay)?;
+        self.store.store(buffer).map_err(FrameStoreError::Store)
+    }
+
+    /// Returns the storage
+    pub fn release(self) -> STORE {
+        self.store
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::epd4in2::{Epd4in2, HEIGHT, WIDTH};
+    use crate::traits::EpdNew;
+    use embedded_hal_mock::{delay::MockNoop, pin::Mock, spi};
+
+    /// Fails to load if `broken`, has nothing stored otherwise
+    struct Empty {
+        broken: bool,
+    }
+
+    impl FrameStore for Empty {
+        type Error = ();
+
+        fn load(&mut self, _frame: &mut [u8]) -> Result<bool, ()> {
+            if self.broken {
+                Err(())
+            } else {
+                Ok(false)
+            }
+        }
+
+        fn store(&mut self, _frame: &[u8]) -> Result<(), ()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn restore_without_frame() {
+        let pin = || Mock::new(&[]);
+        let mut spi = spi::Mock::new(&[]);
+        let mut epd: Epd4in2<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd4in2::new_uninitialized(pin(), pin(), pin(), pin(), None);
+        let mut delay = MockNoop::new();
+        let mut buffer = [0u8; crate::buffer_len(WIDTH as usize, HEIGHT as usize)];
+
+        let mut frames = PersistentFrame::new(Empty { broken: false });
+        assert_eq!(
+            frames.restore(&mut epd, &mut spi, &mut buffer, &mut delay),
+            Ok(false)
+        );
+
+        let mut frames = PersistentFrame::new(Empty { broken: true });
+        assert_eq!(
+            frames.restore(&mut epd, &mut spi, &mut buffer, &mut delay),
+            Err(FrameStoreError::Store(()))
+        );
+
+        let expected = buffer.len();
+        assert_eq!(
+            frames.restore(&mut epd, &mut spi, &mut buffer[1..], &mut delay),
+            Err(FrameStoreError::Display(Error::InvalidBufferSize {
+                expected,
+                actual: expected - 1,
+            }))
+        );
+        spi.done();
+    }
+}
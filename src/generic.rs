@@ -0,0 +1,208 @@
+//! Driver generic over the controller and the configuration of the panel
+//!
+//! Many panels only differ in their resolution, LUTs and a few init values while sharing
+//! the controller. [`Epd`] implements [`WaveshareDisplay`](crate::prelude::WaveshareDisplay)
+//! once per [`Controller`], so supporting another panel only needs a [`PanelConfig`].
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use epd_waveshare::{
+//!    controllers::{TypeA, TypeAInit},
+//!    generic::{Epd, PanelConfig},
+//!    prelude::*,
+//!};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# const MY_LUT: [u8; 30] = [0; 30];
+//!
+//!struct MyPanel;
+//!
+//!impl PanelConfig<TypeA> for MyPanel {
+//!    type DisplayColor = Color;
+//!    const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+//!    const WIDTH: u32 = 128;
+//!    const HEIGHT: u32 = 296;
+//!    const IS_BUSY_LOW: bool = false;
+//!    const CAPABILITIES: DisplayCapabilities = DisplayCapabilities {
+//!        color: ColorKind::BlackWhite,
+//!        grayscale: false,
+//!        partial_refresh: true,
+//!        quick_refresh: true,
+//!        full_refresh_ms: 2000,
+//!        min_refresh_interval_ms: 0,
+//!    };
+//!    const LUT_FULL: &'static [u8] = &MY_LUT;
+//!    const LUT_QUICK: &'static [u8] = &MY_LUT;
+//!    const INIT: TypeAInit = TypeAInit {
+//!        driver_output_control: [0x27, 0x01, 0x00],
+//!        ..TypeAInit::DEFAULT
+//!    };
+//!}
+//!
+//!let mut epd: Epd<TypeA, MyPanel, _, _, _, _, _, _> =
+//!    Epd::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use core::marker::PhantomData;
+
+use crate::color::{Color, TriColor};
+use crate::controllers::{Controller, TypeA, TypeAInit, Uc8179, Uc8179Init};
+use crate::interface::DisplayInterface;
+use crate::traits::{DisplayCapabilities, RefreshLut};
+use crate::transport::SingleSpi;
+use crate::type_a::constants::{LUT_FULL_UPDATE, LUT_PARTIAL_UPDATE};
+
+/// Everything which differs between panels using the same controller
+pub trait PanelConfig<C: Controller> {
+    /// Color of the pixels, [`Color`] for black/white panels
+    type DisplayColor;
+    /// Background color of a new driver
+    const DEFAULT_BACKGROUND_COLOR: Self::DisplayColor;
+    /// Width of the panel in pixels
+    const WIDTH: u32;
+    /// Height of the panel in pixels
+    const HEIGHT: u32;
+    /// Level of the busy pin while the controller is busy: `true` for low, `false` for high
+    const IS_BUSY_LOW: bool;
+    /// What the panel supports
+    const CAPABILITIES: DisplayCapabilities;
+    /// LUT of the full refresh, empty for controllers using the LUTs of their OTP
+    const LUT_FULL: &'static [u8];
+    /// LUT of the quick refresh
+    const LUT_QUICK: &'static [u8];
+    /// Controller specific values sent during the initialisation
+    const INIT: C::InitConfig;
+}
+
+/// Driver for a panel with controller `C` and configuration `P`
+///
+/// `TRANSPORT` sends the frames, see [`transport`](crate::transport). Only controllers
+/// with more than one data line take other transports than [`SingleSpi`].
+pub struct Epd<C, P, SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT = SingleSpi>
+where
+    C: Controller,
+    P: PanelConfig<C>,
+{
+    pub(crate) interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>,
+    pub(crate) background_color: P::DisplayColor,
+    pub(crate) refresh: RefreshLut,
+    pub(crate) state: C::State,
+    pub(crate) config: PhantomData<(C, P)>,
+}
+
+/// Configuration of the 1.54" panel, the same as [`epd1in54`](crate::epd1in54)
+pub struct Panel1in54;
+
+impl PanelConfig<TypeA> for Panel1in54 {
+    type DisplayColor = Color;
+    const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+    const WIDTH: u32 = crate::epd1in54::WIDTH;
+    const HEIGHT: u32 = crate::epd1in54::HEIGHT;
+    const IS_BUSY_LOW: bool = false;
+    const CAPABILITIES: DisplayCapabilities = crate::epd1in54::CAPABILITIES;
+    const LUT_FULL: &'static [u8] = &LUT_FULL_UPDATE;
+    const LUT_QUICK: &'static [u8] = &LUT_PARTIAL_UPDATE;
+    const INIT: TypeAInit = TypeAInit {
+        driver_output_control: [200, 0x00, 0x00],
+        ..TypeAInit::DEFAULT
+    };
+}
+
+/// Configuration of the 2.9" panel, the same as [`epd2in9`](crate::epd2in9)
+pub struct Panel2in9;
+
+impl PanelConfig<TypeA> for Panel2in9 {
+    type DisplayColor = Color;
+    const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+    const WIDTH: u32 = crate::epd2in9::WIDTH;
+    const HEIGHT: u32 = crate::epd2in9::HEIGHT;
+    const IS_BUSY_LOW: bool = false;
+    const CAPABILITIES: DisplayCapabilities = crate::epd2in9::CAPABILITIES;
+    const LUT_FULL: &'static [u8] = &LUT_FULL_UPDATE;
+    const LUT_QUICK: &'static [u8] = &LUT_PARTIAL_UPDATE;
+    const INIT: TypeAInit = TypeAInit {
+        driver_output_control: [0x27, 0x01, 0x00],
+        ..TypeAInit::DEFAULT
+    };
+}
+
+/// Configuration of the 7.5" V2 panel, the one of [`epd7in5_v2`](crate::epd7in5_v2)
+pub struct Panel7in5V2;
+
+impl PanelConfig<Uc8179> for Panel7in5V2 {
+    type DisplayColor = Color;
+    const DEFAULT_BACKGROUND_COLOR: Color = crate::epd7in5_v2::DEFAULT_BACKGROUND_COLOR;
+    const WIDTH: u32 = crate::epd7in5_v2::WIDTH;
+    const HEIGHT: u32 = crate::epd7in5_v2::HEIGHT;
+    const IS_BUSY_LOW: bool = true;
+    const CAPABILITIES: DisplayCapabilities = crate::epd7in5_v2::CAPABILITIES;
+    const LUT_FULL: &'static [u8] = &[];
+    const LUT_QUICK: &'static [u8] = &[];
+    const INIT: Uc8179Init = Uc8179Init::DEFAULT;
+}
+
+/// Configuration of the 7.5" (B) V2 panel, the one of [`epd7in5b_v2`](crate::epd7in5b_v2)
+pub struct Panel7in5bV2;
+
+impl PanelConfig<Uc8179> for Panel7in5bV2 {
+    type DisplayColor = TriColor;
+    const DEFAULT_BACKGROUND_COLOR: TriColor = crate::epd7in5b_v2::DEFAULT_BACKGROUND_COLOR;
+    const WIDTH: u32 = crate::epd7in5b_v2::WIDTH;
+    const HEIGHT: u32 = crate::epd7in5b_v2::HEIGHT;
+    const IS_BUSY_LOW: bool = true;
+    const CAPABILITIES: DisplayCapabilities = crate::epd7in5b_v2::CAPABILITIES;
+    const LUT_FULL: &'static [u8] = &[];
+    const LUT_QUICK: &'static [u8] = &[];
+    const INIT: Uc8179Init = Uc8179Init {
+        // The C driver waits 200ms before the reset
+        reset_delay_us: 200_000,
+        power_setting: [0x07, 0x07, 0x3F, 0x3F],
+        // Black/white/red mode
+        panel_setting: 0x0F,
+        pll: None,
+        // White border
+        vcom_and_data_interval: [0x11, 0x07],
+        booster_soft_start: None,
+        quick_temperature: None,
+        partial_temperature: None,
+        disable_spi_flash: true,
+        ..Uc8179Init::DEFAULT
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panel_configs() {
+        assert_eq!(Panel1in54::WIDTH, 200);
+        assert_eq!(Panel1in54::HEIGHT, 200);
+        assert_eq!(Panel2in9::WIDTH, 128);
+        assert_eq!(Panel2in9::HEIGHT, 296);
+        // Gate count - 1
+        assert_eq!(
+            Panel2in9::INIT.driver_output_control[0] as u32
+                + ((Panel2in9::INIT.driver_output_control[1] as u32) << 8),
+            Panel2in9::HEIGHT - 1
+        );
+        assert_eq!(Panel7in5V2::WIDTH, 800);
+        assert_eq!(Panel7in5V2::HEIGHT, 480);
+        assert_eq!(Panel7in5V2::INIT.quick_temperature, Some(0x5A));
+        assert_eq!(Panel7in5bV2::INIT.quick_temperature, None);
+    }
+}
@@ -1,13 +1,22 @@
 //! Graphics Support for EPDs
 
-use crate::color::{ColorType, TriColor};
+use crate::color::{BwPlane, ChromaticPlane, Color, ColorType, OctColor, Packing, TriColor};
+use crate::error::Error;
+use crate::traits::{EpdUpdate, Orientation, WaveshareThreeColorDisplay};
 use core::marker::PhantomData;
-use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+use embedded_hal::{
+    blocking::{delay::DelayUs, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
 
 /// Display rotation, only 90° increments supported
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplayRotation {
     /// No rotation
+    #[default]
     Rotate0,
     /// Rotate by 90 degrees clockwise
     Rotate90,
@@ -17,16 +26,45 @@ pub enum DisplayRotation {
     Rotate270,
 }
 
-impl Default for DisplayRotation {
-    fn default() -> Self {
-        DisplayRotation::Rotate0
+impl DisplayRotation {
+    /// Rotation showing the image in `orientation` on a panel of `width` x `height` pixels
+    pub fn for_orientation(orientation: Orientation, width: u32, height: u32) -> DisplayRotation {
+        let native = Orientation::native(width, height).quarter_turns();
+        match (orientation.quarter_turns() + 4 - native) % 4 {
+            0 => DisplayRotation::Rotate0,
+            1 => DisplayRotation::Rotate90,
+            2 => DisplayRotation::Rotate180,
+            _ => DisplayRotation::Rotate270,
+        }
     }
+
+    /// Orientation of the image on a panel of `width` x `height` pixels with this rotation
+    pub fn orientation(self, width: u32, height: u32) -> Orientation {
+        let native = Orientation::native(width, height).quarter_turns();
+        Orientation::from_quarter_turns(native + self as u8)
+    }
+}
+
+/// Order of the pixels inside of a byte of the buffer
+///
+/// The controllers expect [`BitOrder::MsbFirst`]. [`SendFrame`] and the `update_partial_frame`
+/// methods of the displays convert buffers using [`BitOrder::LsbFirst`] while sending them,
+/// a buffer passed to the drivers directly needs to be converted with `convert_bit_order`.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// The leftmost pixel is stored in the most significant bits
+    #[default]
+    MsbFirst,
+    /// The leftmost pixel is stored in the least significant bits
+    LsbFirst,
 }
 
 /// count the number of bytes per line knowing that it may contains padding bits
 const fn line_bytes(width: u32, bits_per_pixel: usize) -> usize {
     // round to upper 8 bit count
-    (width as usize * bits_per_pixel + 7) / 8
+    (width as usize * bits_per_pixel).div_ceil(8)
 }
 
 /// Display bffer used for drawing with embedded graphics
@@ -35,10 +73,10 @@ const fn line_bytes(width: u32, bits_per_pixel: usize) -> usize {
 /// - WIDTH: width in pixel when display is not rotated
 /// - HEIGHT: height in pixel when display is not rotated
 /// - BWRBIT: mandatory value of the B/W when chromatic bit is set, can be any value for non
-///           tricolor epd
+///   tricolor epd
 /// - COLOR: color type used by the target display
 /// - BYTECOUNT: This is redundant with prvious data and should be removed when const generic
-///              expressions are stabilized
+///   expressions are stabilized
 ///
 /// More on BWRBIT:
 ///
@@ -60,6 +98,7 @@ pub struct Display<
 > {
     buffer: [u8; BYTECOUNT],
     rotation: DisplayRotation,
+    bit_order: BitOrder,
     _color: PhantomData<COLOR>,
 }
 
@@ -85,6 +124,7 @@ impl<
             // default color must be 0 for every bit in a pixel to make this work everywere
             buffer: [0u8; BYTECOUNT],
             rotation: DisplayRotation::default(),
+            bit_order: BitOrder::default(),
             _color: PhantomData,
         }
     }
@@ -130,6 +170,20 @@ impl<
     }
 }
 
+/// The buffer, e.g. for [`TiledDisplay::buffers`](crate::tiled_display::TiledDisplay::buffers)
+impl<
+        const WIDTH: u32,
+        const HEIGHT: u32,
+        const BWRBIT: bool,
+        const BYTECOUNT: usize,
+        COLOR: ColorType,
+    > AsRef<[u8]> for Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, COLOR>
+{
+    fn as_ref(&self) -> &[u8] {
+        self.buffer()
+    }
+}
+
 impl<
         const WIDTH: u32,
         const HEIGHT: u32,
@@ -138,6 +192,42 @@ impl<
         COLOR: ColorType,
     > Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, COLOR>
 {
+    /// Width of the display in pixels, without rotation
+    pub const WIDTH: u32 = WIDTH;
+
+    /// Height of the display in pixels, without rotation
+    pub const HEIGHT: u32 = HEIGHT;
+
+    /// Number of bytes of a line of a plane, including the padding at its end
+    pub const ROW_BYTES: usize = line_bytes(WIDTH, COLOR::BITS_PER_PIXEL_PER_BUFFER);
+
+    /// Width in pixels of a line, including the padding at its end
+    pub const PADDED_WIDTH: u32 = (Self::ROW_BYTES * 8 / COLOR::BITS_PER_PIXEL_PER_BUFFER) as u32;
+
+    /// Number of bytes of a plane, tricolor displays have two of them
+    pub const fn plane_len() -> usize {
+        Self::ROW_BYTES * HEIGHT as usize
+    }
+
+    /// Wraps a buffer owned by the caller, e.g. placed in a specific memory region, with the
+    /// characteristics of this display
+    ///
+    /// **This returns a [`VarDisplay`], not a `Display`.** `Display` always owns its array, so
+    /// the aliases of the drivers like `Display2in13` can't wrap a borrowed slice; use the
+    /// returned `VarDisplay` in their place.
+    ///
+    /// Returns [`VarDisplayError::BufferSizeMismatch`] unless `buffer` is exactly `BYTECOUNT`
+    /// bytes long.
+    pub fn from_buffer(buffer: &mut [u8]) -> Result<VarDisplay<'_, COLOR>, VarDisplayError> {
+        if buffer.len() != BYTECOUNT {
+            return Err(VarDisplayError::BufferSizeMismatch {
+                expected: BYTECOUNT,
+                actual: buffer.len(),
+            });
+        }
+        VarDisplay::new(WIDTH, HEIGHT, buffer, BWRBIT)
+    }
+
     /// get internal buffer to use it (to draw in epd)
     pub fn buffer(&self) -> &[u8] {
         &self.buffer
@@ -156,6 +246,40 @@ impl<
         self.rotation
     }
 
+    /// Sets the rotation showing the image in `orientation` on this panel
+    ///
+    /// Like [`Display::set_rotation`], this only concerns future drawing.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.rotation = DisplayRotation::for_orientation(orientation, WIDTH, HEIGHT);
+    }
+
+    /// Orientation of the image given by the current rotation
+    pub fn orientation(&self) -> Orientation {
+        self.rotation.orientation(WIDTH, HEIGHT)
+    }
+
+    /// Set the order of the pixels inside of a byte, e.g. to draw on top of LSB first assets
+    ///
+    /// Like the rotation, this only concerns future drawing, the buffer isn't changed.
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// Get current bit order
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Converts the buffer to `bit_order` and uses it for future drawing
+    ///
+    /// E.g. `convert_bit_order(BitOrder::MsbFirst)` before sending an LSB first buffer.
+    pub fn convert_bit_order(&mut self, bit_order: BitOrder) {
+        if self.bit_order != bit_order {
+            reverse_bit_order::<COLOR>(&mut self.buffer);
+            self.bit_order = bit_order;
+        }
+    }
+
     /// Set a specific pixel color on this display
     pub fn set_pixel(&mut self, pixel: Pixel<COLOR>) {
         set_pixel(
@@ -163,10 +287,29 @@ impl<
             WIDTH,
             HEIGHT,
             self.rotation,
+            self.bit_order,
             BWRBIT,
             pixel,
         );
     }
+
+    /// Get the color of a specific pixel, `None` if it is out of range
+    pub fn get_pixel(&self, point: Point) -> Option<COLOR> {
+        get_pixel(
+            &self.buffer,
+            WIDTH,
+            HEIGHT,
+            self.rotation,
+            self.bit_order,
+            point,
+        )
+    }
+
+    /// Iterate over all pixels, in the coordinates of the current rotation
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel<COLOR>> + '_ {
+        let size = self.size();
+        points(size).filter_map(move |point| Some(Pixel(point, self.get_pixel(point)?)))
+    }
 }
 
 /// Some Tricolor specifics
@@ -174,13 +317,97 @@ impl<const WIDTH: u32, const HEIGHT: u32, const BWRBIT: bool, const BYTECOUNT: u
     Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, TriColor>
 {
     /// get black/white internal buffer to use it (to draw in epd)
-    pub fn bw_buffer(&self) -> &[u8] {
-        &self.buffer[..self.buffer.len() / 2]
+    pub fn bw_buffer(&self) -> BwPlane<'_> {
+        BwPlane(&self.buffer[..self.buffer.len() / 2])
     }
 
     /// get chromatic internal buffer to use it (to draw in epd)
-    pub fn chromatic_buffer(&self) -> &[u8] {
-        &self.buffer[self.buffer.len() / 2..]
+    pub fn chromatic_buffer(&self) -> ChromaticPlane<'_> {
+        ChromaticPlane(&self.buffer[self.buffer.len() / 2..])
+    }
+
+    /// Draws only into the black/white plane, with the rotation and bit order of this display
+    ///
+    /// The chromatic pixels stay as they are, so e.g. monochrome widgets can be drawn on top
+    /// of them with a [`BinaryColorAdapter`].
+    pub fn bw_plane_mut(&mut self) -> VarDisplay<'_, Color> {
+        let plane_len = Self::plane_len();
+        VarDisplay {
+            width: WIDTH,
+            height: HEIGHT,
+            bwrbit: false,
+            buffer: &mut self.buffer[..plane_len],
+            rotation: self.rotation,
+            bit_order: self.bit_order,
+            _color: PhantomData,
+        }
+    }
+}
+
+/// Some Black/White specifics
+impl<const WIDTH: u32, const HEIGHT: u32, const BWRBIT: bool, const BYTECOUNT: usize>
+    Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, Color>
+{
+    /// Copies a frame from a packed [`BinaryColor`] buffer
+    ///
+    /// See [`copy_from_binary`] for the expected layout. The pixels are stored in the
+    /// [`bit_order`](Display::bit_order) of the display, but the
+    /// [`rotation`](Display::rotation) isn't applied: the frame is in the unrotated coordinates
+    /// of the panel.
+    pub fn copy_from_binary(&mut self, data: &[u8]) -> Result<(), VarDisplayError> {
+        copy_from_binary(&mut self.buffer, data)?;
+        if self.bit_order == BitOrder::LsbFirst {
+            reverse_bit_order::<Color>(&mut self.buffer);
+        }
+        Ok(())
+    }
+
+    /// Copies the frame into a packed [`BinaryColor`] buffer
+    ///
+    /// See [`copy_from_binary`] for the layout, the frame is in the unrotated coordinates of
+    /// the panel as well.
+    pub fn copy_to_binary(&self, data: &mut [u8]) -> Result<(), VarDisplayError> {
+        copy_from_binary(data, &self.buffer)?;
+        if self.bit_order == BitOrder::LsbFirst {
+            reverse_bit_order::<Color>(data);
+        }
+        Ok(())
+    }
+
+    /// Transmits the part of the frame covered by `area` with
+    /// [`EpdUpdate::update_partial_frame`]
+    ///
+    /// See [`update_partial_window`] for how `area` and `scratch` are used. Returns the window
+    /// which was sent, in the coordinates of the unrotated display.
+    pub fn update_partial_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        area: Rectangle,
+        scratch: &mut [u8],
+    ) -> Result<Rectangle, Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        update_partial_window(
+            &self.buffer,
+            WIDTH,
+            HEIGHT,
+            self.rotation,
+            self.bit_order,
+            epd,
+            spi,
+            delay,
+            area,
+            scratch,
+        )
     }
 }
 
@@ -193,6 +420,7 @@ pub struct VarDisplay<'a, COLOR: ColorType> {
     bwrbit: bool,
     buffer: &'a mut [u8],
     rotation: DisplayRotation,
+    bit_order: BitOrder,
     _color: PhantomData<COLOR>,
 }
 
@@ -226,13 +454,42 @@ impl<'a, COLOR: ColorType> OriginDimensions for VarDisplay<'a, COLOR> {
     }
 }
 
+/// The buffer, e.g. for [`TiledDisplay::buffers`](crate::tiled_display::TiledDisplay::buffers)
+impl<'a, COLOR: ColorType> AsRef<[u8]> for VarDisplay<'a, COLOR> {
+    fn as_ref(&self) -> &[u8] {
+        self.buffer()
+    }
+}
+
 /// Error found during usage of VarDisplay
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum VarDisplayError {
     /// The provided buffer was too small
     BufferTooSmall,
+    /// The provided buffer doesn't have the size of the frame
+    BufferSizeMismatch {
+        /// Size of the frame in bytes
+        expected: usize,
+        /// Size of the provided buffer in bytes
+        actual: usize,
+    },
 }
 
+impl core::fmt::Display for VarDisplayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            VarDisplayError::BufferTooSmall => write!(f, "Buffer too small for the display"),
+            VarDisplayError::BufferSizeMismatch { expected, actual } => {
+                write!(f, "Buffer of {} bytes given, {} expected", actual, expected)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarDisplayError {}
+
 impl<'a, COLOR: ColorType> VarDisplay<'a, COLOR> {
     /// You must allocate the buffer by yourself, it must be large enough to contain all pixels.
     ///
@@ -250,6 +507,7 @@ impl<'a, COLOR: ColorType> VarDisplay<'a, COLOR> {
             bwrbit,
             buffer,
             rotation: DisplayRotation::default(),
+            bit_order: BitOrder::default(),
             _color: PhantomData,
         };
         // enfore some constraints dynamicly
@@ -268,6 +526,21 @@ impl<'a, COLOR: ColorType> VarDisplay<'a, COLOR> {
             )
     }
 
+    /// Number of bytes of a line of a plane, including the padding at its end
+    pub fn row_bytes(&self) -> usize {
+        line_bytes(self.width, COLOR::BITS_PER_PIXEL_PER_BUFFER)
+    }
+
+    /// Width in pixels of a line, including the padding at its end
+    pub fn padded_width(&self) -> u32 {
+        (self.row_bytes() * 8 / COLOR::BITS_PER_PIXEL_PER_BUFFER) as u32
+    }
+
+    /// Number of bytes of a plane, tricolor displays have two of them
+    pub fn plane_len(&self) -> usize {
+        self.row_bytes() * self.height as usize
+    }
+
     /// get internal buffer to use it (to draw in epd)
     pub fn buffer(&self) -> &[u8] {
         &self.buffer[..self.buffer_size()]
@@ -286,6 +559,35 @@ impl<'a, COLOR: ColorType> VarDisplay<'a, COLOR> {
         self.rotation
     }
 
+    /// Sets the rotation showing the image in `orientation`, see [`Display::set_orientation`]
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.rotation = DisplayRotation::for_orientation(orientation, self.width, self.height);
+    }
+
+    /// Orientation of the image given by the current rotation
+    pub fn orientation(&self) -> Orientation {
+        self.rotation.orientation(self.width, self.height)
+    }
+
+    /// Set the order of the pixels inside of a byte, see [`Display::set_bit_order`]
+    pub fn set_bit_order(&mut self, bit_order: BitOrder) {
+        self.bit_order = bit_order;
+    }
+
+    /// Get current bit order
+    pub fn bit_order(&self) -> BitOrder {
+        self.bit_order
+    }
+
+    /// Converts the buffer to `bit_order`, see [`Display::convert_bit_order`]
+    pub fn convert_bit_order(&mut self, bit_order: BitOrder) {
+        if self.bit_order != bit_order {
+            let size = self.buffer_size();
+            reverse_bit_order::<COLOR>(&mut self.buffer[..size]);
+            self.bit_order = bit_order;
+        }
+    }
+
     /// Set a specific pixel color on this display
     pub fn set_pixel(&mut self, pixel: Pixel<COLOR>) {
         let size = self.buffer_size();
@@ -294,22 +596,485 @@ impl<'a, COLOR: ColorType> VarDisplay<'a, COLOR> {
             self.width,
             self.height,
             self.rotation,
+            self.bit_order,
             self.bwrbit,
             pixel,
         );
     }
+
+    /// Get the color of a specific pixel, `None` if it is out of range
+    pub fn get_pixel(&self, point: Point) -> Option<COLOR> {
+        get_pixel(
+            self.buffer(),
+            self.width,
+            self.height,
+            self.rotation,
+            self.bit_order,
+            point,
+        )
+    }
+
+    /// Iterate over all pixels, in the coordinates of the current rotation
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel<COLOR>> + '_ {
+        let size = self.size();
+        points(size).filter_map(move |point| Some(Pixel(point, self.get_pixel(point)?)))
+    }
 }
 
 /// Some Tricolor specifics
 impl<'a> VarDisplay<'a, TriColor> {
     /// get black/white internal buffer to use it (to draw in epd)
-    pub fn bw_buffer(&self) -> &[u8] {
-        &self.buffer[..self.buffer_size() / 2]
+    pub fn bw_buffer(&self) -> BwPlane<'_> {
+        BwPlane(&self.buffer[..self.buffer_size() / 2])
     }
 
     /// get chromatic internal buffer to use it (to draw in epd)
-    pub fn chromatic_buffer(&self) -> &[u8] {
-        &self.buffer[self.buffer_size() / 2..self.buffer_size()]
+    pub fn chromatic_buffer(&self) -> ChromaticPlane<'_> {
+        ChromaticPlane(&self.buffer[self.buffer_size() / 2..self.buffer_size()])
+    }
+
+    /// Draws only into the black/white plane, see [`Display::bw_plane_mut`]
+    pub fn bw_plane_mut(&mut self) -> VarDisplay<'_, Color> {
+        let plane_len = self.plane_len();
+        VarDisplay {
+            width: self.width,
+            height: self.height,
+            bwrbit: false,
+            buffer: &mut self.buffer[..plane_len],
+            rotation: self.rotation,
+            bit_order: self.bit_order,
+            _color: PhantomData,
+        }
+    }
+}
+
+/// Some Black/White specifics
+impl<'a> VarDisplay<'a, Color> {
+    /// Copies a frame from a packed [`BinaryColor`] buffer
+    ///
+    /// See [`copy_from_binary`] for the expected layout. The pixels are stored in the
+    /// [`bit_order`](VarDisplay::bit_order) of the display, but the
+    /// [`rotation`](VarDisplay::rotation) isn't applied: the frame is in the unrotated
+    /// coordinates of the panel.
+    pub fn copy_from_binary(&mut self, data: &[u8]) -> Result<(), VarDisplayError> {
+        let size = self.buffer_size();
+        copy_from_binary(&mut self.buffer[..size], data)?;
+        if self.bit_order == BitOrder::LsbFirst {
+            reverse_bit_order::<Color>(&mut self.buffer[..size]);
+        }
+        Ok(())
+    }
+
+    /// Copies the frame into a packed [`BinaryColor`] buffer
+    ///
+    /// See [`copy_from_binary`] for the layout, the frame is in the unrotated coordinates of
+    /// the panel as well.
+    pub fn copy_to_binary(&self, data: &mut [u8]) -> Result<(), VarDisplayError> {
+        copy_from_binary(data, self.buffer())?;
+        if self.bit_order == BitOrder::LsbFirst {
+            reverse_bit_order::<Color>(data);
+        }
+        Ok(())
+    }
+
+    /// Transmits the part of the frame covered by `area` with
+    /// [`EpdUpdate::update_partial_frame`]
+    ///
+    /// See [`update_partial_window`] for how `area` and `scratch` are used. Returns the window
+    /// which was sent, in the coordinates of the unrotated display.
+    pub fn update_partial_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        area: Rectangle,
+        scratch: &mut [u8],
+    ) -> Result<Rectangle, Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        update_partial_window(
+            self.buffer(),
+            self.width,
+            self.height,
+            self.rotation,
+            self.bit_order,
+            epd,
+            spi,
+            delay,
+            area,
+            scratch,
+        )
+    }
+}
+
+/// Transmission of the frame of a display buffer to the driver of its panel
+///
+/// Implemented for [`Display`] and [`VarDisplay`] of [`Color`], [`OctColor`] and [`TriColor`].
+/// Three color buffers are sent with both planes by
+/// [`WaveshareThreeColorDisplay::update_color_frame`], all others by
+/// [`EpdUpdate::update_frame`]. Used by
+/// [`WaveshareDisplay::render`](crate::traits::WaveshareDisplay::render).
+///
+/// Buffers using [`BitOrder::LsbFirst`] are converted to the order of the controllers in place
+/// for the transmission and converted back afterwards, so the display keeps its bit order.
+///
+/// Not implemented for [`Gray2`](crate::color::Gray2) buffers, which are sent by the `update_frame_gray2` methods of
+/// the 4-gray drivers instead of a trait method.
+pub trait SendFrame<EPD, SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Transmits the frame to the SRAM of the EPD
+    fn send_frame(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>;
+}
+
+macro_rules! send_frame {
+    ($color:ty, $epd_trait:ident, |$display:ident, $epd:ident, $spi:ident, $delay:ident| $send:expr) => {
+        impl<
+                const WIDTH: u32,
+                const HEIGHT: u32,
+                const BWRBIT: bool,
+                const BYTECOUNT: usize,
+                EPD,
+                SPI,
+                CS,
+                BUSY,
+                DC,
+                RST,
+                DELAY,
+            > SendFrame<EPD, SPI, CS, BUSY, DC, RST, DELAY>
+            for Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, $color>
+        where
+            EPD: $epd_trait<SPI, CS, BUSY, DC, RST, DELAY>,
+            SPI: Write<u8>,
+            CS: OutputPin,
+            BUSY: InputPin,
+            DC: OutputPin,
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+        {
+            fn send_frame(
+                &mut self,
+                $epd: &mut EPD,
+                $spi: &mut SPI,
+                $delay: &mut DELAY,
+            ) -> Result<(), Error<SPI::Error>> {
+                let bit_order = self.bit_order();
+                self.convert_bit_order(BitOrder::MsbFirst);
+                let $display = &*self;
+                let result = $send;
+                self.convert_bit_order(bit_order);
+                result
+            }
+        }
+
+        impl<'a, EPD, SPI, CS, BUSY, DC, RST, DELAY> SendFrame<EPD, SPI, CS, BUSY, DC, RST, DELAY>
+            for VarDisplay<'a, $color>
+        where
+            EPD: $epd_trait<SPI, CS, BUSY, DC, RST, DELAY>,
+            SPI: Write<u8>,
+            CS: OutputPin,
+            BUSY: InputPin,
+            DC: OutputPin,
+            RST: OutputPin,
+            DELAY: DelayUs<u32>,
+        {
+            fn send_frame(
+                &mut self,
+                $epd: &mut EPD,
+                $spi: &mut SPI,
+                $delay: &mut DELAY,
+            ) -> Result<(), Error<SPI::Error>> {
+                let bit_order = self.bit_order();
+                self.convert_bit_order(BitOrder::MsbFirst);
+                let $display = &*self;
+                let result = $send;
+                self.convert_bit_order(bit_order);
+                result
+            }
+        }
+    };
+}
+
+send_frame!(Color, EpdUpdate, |display, epd, spi, delay| {
+    epd.update_frame(spi, display.buffer(), delay)
+});
+send_frame!(OctColor, EpdUpdate, |display, epd, spi, delay| {
+    epd.update_frame(spi, display.buffer(), delay)
+});
+send_frame!(
+    TriColor,
+    WaveshareThreeColorDisplay,
+    |display, epd, spi, delay| {
+        epd.update_color_frame(spi, delay, display.bw_buffer(), display.chromatic_buffer())?;
+        Ok(())
+    }
+);
+
+/// Converts between a black/white display buffer and a packed
+/// [`BinaryColor`] buffer
+///
+/// The packed buffer uses one bit per pixel, row by row with every row padded to a full byte
+/// and the leftmost pixel in the most significant bit. This is the layout of the data of an
+/// embedded-graphics `Framebuffer<BinaryColor, RawU1, BigEndian, ..>` and of an
+/// `ImageRaw<BinaryColor>`, so frames rendered by other libraries can be copied without
+/// going through every pixel. It's the same layout as the buffer of a display, but
+/// `BinaryColor::On` is black, so the bytes are inverted while copying.
+///
+/// The frame is copied in the unrotated coordinates of the panel, the rotation a display
+/// draws with isn't applied.
+///
+/// Returns [`VarDisplayError::BufferSizeMismatch`] if the lengths of `buffer` and `data` differ.
+pub fn copy_from_binary(buffer: &mut [u8], data: &[u8]) -> Result<(), VarDisplayError> {
+    if data.len() != buffer.len() {
+        return Err(VarDisplayError::BufferSizeMismatch {
+            expected: buffer.len(),
+            actual: data.len(),
+        });
+    }
+    for (byte, binary) in buffer.iter_mut().zip(data) {
+        *byte = !binary;
+    }
+    Ok(())
+}
+
+/// Run length encodes a display buffer, e.g. to send a frame rendered elsewhere over a slow
+/// link
+///
+/// `buffer` can be in any of the packed formats, a three color buffer is encoded with both of
+/// its planes. The encoding is a sequence of `(count, byte)` pairs with `count` from 1 to 255,
+/// the drivers can send it with their `update_frame_rle` functions without decoding it first.
+///
+/// Returns the number of bytes written to `encoded`, or `None` if they don't fit, see
+/// [`encoded_rle_len`].
+pub fn encode_rle(buffer: &[u8], encoded: &mut [u8]) -> Option<usize> {
+    let mut i = 0;
+    let mut len = 0;
+    while i < buffer.len() {
+        let run = crate::lut_table::run_len(buffer, i);
+        let pair = encoded.get_mut(len..len + 2)?;
+        pair.copy_from_slice(&[run as u8, buffer[i]]);
+        i += run;
+        len += 2;
+    }
+    Some(len)
+}
+
+/// Number of bytes [`encode_rle`] needs for `buffer`
+pub fn encoded_rle_len(buffer: &[u8]) -> usize {
+    crate::lut_table::compressed_len(buffer)
+}
+
+/// Decodes the output of [`encode_rle`] into a display buffer
+///
+/// Returns the number of bytes written to `buffer`, or `None` if they don't fit. Check it
+/// against the size of the frame if the data isn't trusted.
+pub fn decode_rle(encoded: &[u8], buffer: &mut [u8]) -> Option<usize> {
+    let mut runs = crate::lut_table::Runs::new(encoded);
+    let mut len = 0;
+    while let Some((run, byte)) = runs.take(usize::MAX) {
+        buffer.get_mut(len..len + run)?.fill(byte);
+        len += run;
+    }
+    Some(len)
+}
+
+/// Transmits the part of a black/white display `buffer` covered by `area` with
+/// [`EpdUpdate::update_partial_frame`]
+///
+/// `area` is given in the coordinates of `rotation`, like the drawing. It is clipped to the
+/// display and widened to whole bytes of the unrotated buffer, as the controllers only update
+/// windows starting and ending on a byte. The window is packed into `scratch`, which needs to
+/// hold at least [`buffer_len`](crate::buffer_len) of the widened window; the length of
+/// `buffer` is always enough. The pixels of `buffer` are stored in `bit_order`, the window is
+/// converted to [`BitOrder::MsbFirst`] for the controller while packing it.
+///
+/// Returns the window which was sent, in the coordinates of the unrotated display, or
+/// [`Error::InvalidRegion`] if `area` doesn't overlap the display.
+#[allow(clippy::too_many_arguments)]
+pub fn update_partial_window<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+    buffer: &[u8],
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+    bit_order: BitOrder,
+    epd: &mut EPD,
+    spi: &mut SPI,
+    delay: &mut DELAY,
+    area: Rectangle,
+    scratch: &mut [u8],
+) -> Result<Rectangle, Error<SPI::Error>>
+where
+    EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    let window = byte_window(area, width, height, rotation).ok_or(Error::InvalidRegion)?;
+    let len = copy_window(buffer, width, bit_order, window, scratch)?;
+    epd.update_partial_frame(
+        spi,
+        delay,
+        &scratch[..len],
+        window.top_left.x as u32,
+        window.top_left.y as u32,
+        window.size.width,
+        window.size.height,
+    )?;
+    Ok(window)
+}
+
+// The window of the unrotated buffer covering `area`, clipped to the display and widened to
+// whole bytes, `None` if they don't overlap
+fn byte_window(
+    area: Rectangle,
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+) -> Option<Rectangle> {
+    let size = match rotation {
+        DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(width, height),
+        DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(height, width),
+    };
+    let area = area.intersection(&Rectangle::new(Point::zero(), size));
+    let (x0, y0) = unrotate(area.top_left, width, height, rotation)?;
+    let (x1, y1) = unrotate(area.bottom_right()?, width, height, rotation)?;
+
+    let left = x0.min(x1) / 8 * 8;
+    let right = (x0.max(x1) / 8 + 1) * 8;
+    let (top, bottom) = (y0.min(y1), y0.max(y1));
+    Some(Rectangle::new(
+        Point::new(left, top),
+        Size::new((right - left) as u32, (bottom - top + 1) as u32),
+    ))
+}
+
+// Packs the bytes of `window` of a buffer `width` pixels wide into `scratch` in MSB first
+// order, returns their number
+fn copy_window<E>(
+    buffer: &[u8],
+    width: u32,
+    bit_order: BitOrder,
+    window: Rectangle,
+    scratch: &mut [u8],
+) -> Result<usize, Error<E>> {
+    let row_bytes = line_bytes(width, 1);
+    let window_bytes = window.size.width as usize / 8;
+    let len = window_bytes * window.size.height as usize;
+    if scratch.len() < len {
+        return Err(Error::InvalidBufferSize {
+            expected: len,
+            actual: scratch.len(),
+        });
+    }
+
+    let first = window.top_left.y as usize * row_bytes + window.top_left.x as usize / 8;
+    for (row, window_row) in scratch[..len].chunks_exact_mut(window_bytes).enumerate() {
+        let start = first + row * row_bytes;
+        window_row.copy_from_slice(&buffer[start..start + window_bytes]);
+    }
+    if bit_order == BitOrder::LsbFirst {
+        reverse_bit_order::<Color>(&mut scratch[..len]);
+    }
+    Ok(len)
+}
+
+// Position of a point in the unrotated buffer, `None` if it is out of range
+//
+// The range is checked against the real width and height, so the padding bits at the end of
+// every line are never reached from any rotation.
+fn unrotate(
+    point: Point,
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+) -> Option<(i32, i32)> {
+    let (x, y) = match rotation {
+        // as i32 = never use more than 2 billion pixel per line or per column
+        DisplayRotation::Rotate0 => (point.x, point.y),
+        DisplayRotation::Rotate90 => (width as i32 - 1 - point.y, point.x),
+        DisplayRotation::Rotate180 => (width as i32 - 1 - point.x, height as i32 - 1 - point.y),
+        DisplayRotation::Rotate270 => (point.y, height as i32 - 1 - point.x),
+    };
+
+    if (x < 0) || (x >= width as i32) || (y < 0) || (y >= height as i32) {
+        return None;
+    }
+    Some((x, y))
+}
+
+/// Draws [`BinaryColor`] drawables, e.g.
+/// monochrome widgets and images, on a display of another color
+///
+/// `On` is drawn black and `Off` white. Wrapping [`Display::bw_plane_mut`] leaves the
+/// chromatic pixels of a three color display untouched, wrapping the display itself overwrites
+/// them.
+pub struct BinaryColorAdapter<'a, D> {
+    target: &'a mut D,
+}
+
+impl<'a, D> BinaryColorAdapter<'a, D>
+where
+    D: DrawTarget,
+    D::Color: From<BinaryColor>,
+{
+    /// Wraps `target`
+    pub fn new(target: &'a mut D) -> Self {
+        Self { target }
+    }
+}
+
+impl<D: Dimensions> Dimensions for BinaryColorAdapter<'_, D> {
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for BinaryColorAdapter<'_, D>
+where
+    D: DrawTarget,
+    D::Color: From<BinaryColor>,
+{
+    type Color = BinaryColor;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.target.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, color.into())),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.fill_solid(area, color.into())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.target.clear(color.into())
     }
 }
 
@@ -322,40 +1087,85 @@ fn set_pixel<COLOR: ColorType>(
     width: u32,
     height: u32,
     rotation: DisplayRotation,
+    bit_order: BitOrder,
     bwrbit: bool,
     pixel: Pixel<COLOR>,
 ) {
     let Pixel(point, color) = pixel;
 
-    // final coordinates
-    let (x, y) = match rotation {
-        // as i32 = never use more than 2 billion pixel per line or per column
-        DisplayRotation::Rotate0 => (point.x, point.y),
-        DisplayRotation::Rotate90 => (width as i32 - 1 - point.y, point.x),
-        DisplayRotation::Rotate180 => (width as i32 - 1 - point.x, height as i32 - 1 - point.y),
-        DisplayRotation::Rotate270 => (point.y, height as i32 - 1 - point.x),
+    let (x, y) = match unrotate(point, width, height, rotation) {
+        Some(position) => position,
+        // don't do anything in case of out of range
+        None => return,
     };
 
-    // Out of range check
-    if (x < 0) || (x >= width as i32) || (y < 0) || (y > height as i32) {
-        // don't do anything in case of out of range
-        return;
+    let index = x as usize * COLOR::BITS_PER_PIXEL_PER_BUFFER / 8
+        + y as usize * line_bytes(width, COLOR::BITS_PER_PIXEL_PER_BUFFER);
+    let (mask, bits) = color.bitmask(bwrbit, bit_position::<COLOR>(x as u32, bit_order));
+
+    match COLOR::PACKING {
+        Packing::Packed => buffer[index] = buffer[index] & mask | bits as u8,
+        // split buffer, e.g. for tricolor displays that use 2 buffer for 2 bits per pixel
+        Packing::Planar => {
+            let [first, second] = bits.to_le_bytes();
+            buffer[index] = buffer[index] & mask | first;
+            let index = index + buffer.len() / 2;
+            buffer[index] = buffer[index] & mask | second;
+        }
     }
+}
+
+// The counterpart of `set_pixel`, shared between `Display` and `VarDisplay`
+fn get_pixel<COLOR: ColorType>(
+    buffer: &[u8],
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+    bit_order: BitOrder,
+    point: Point,
+) -> Option<COLOR> {
+    let (x, y) = unrotate(point, width, height, rotation)?;
 
     let index = x as usize * COLOR::BITS_PER_PIXEL_PER_BUFFER / 8
         + y as usize * line_bytes(width, COLOR::BITS_PER_PIXEL_PER_BUFFER);
-    let (mask, bits) = color.bitmask(bwrbit, x as u32);
+    let mut bits = buffer[index] as u16;
+    if COLOR::PACKING == Packing::Planar {
+        bits |= (buffer[index + buffer.len() / 2] as u16) << 8;
+    }
+    COLOR::from_bitmask(bit_position::<COLOR>(x as u32, bit_order), bits)
+}
+
+// Position passed to `ColorType::bitmask` for the pixel `x` of a line
+fn bit_position<COLOR: ColorType>(x: u32, bit_order: BitOrder) -> u32 {
+    match bit_order {
+        BitOrder::MsbFirst => x,
+        BitOrder::LsbFirst => {
+            let pixels_per_byte = (8 / COLOR::BITS_PER_PIXEL_PER_BUFFER) as u32;
+            pixels_per_byte - 1 - x % pixels_per_byte
+        }
+    }
+}
 
-    if COLOR::BUFFER_COUNT == 2 {
-        // split buffer is for tricolor displays that use 2 buffer for 2 bits per pixel
-        buffer[index] = buffer[index] & mask | (bits & 0xFF) as u8;
-        let index = index + buffer.len() / 2;
-        buffer[index] = buffer[index] & mask | (bits >> 8) as u8;
-    } else {
-        buffer[index] = buffer[index] & mask | bits as u8;
+/// Reverses the order of the pixels inside of every byte of `buffer`, converting it between
+/// [`BitOrder::MsbFirst`] and [`BitOrder::LsbFirst`]
+pub fn reverse_bit_order<COLOR: ColorType>(buffer: &mut [u8]) {
+    let bits = COLOR::BITS_PER_PIXEL_PER_BUFFER;
+    let mask = (1u8 << bits) - 1;
+    for byte in buffer.iter_mut() {
+        let mut reversed = 0;
+        for pixel in 0..8 / bits {
+            let value = (*byte >> (pixel * bits)) & mask;
+            reversed |= value << (8 - bits - pixel * bits);
+        }
+        *byte = reversed;
     }
 }
 
+// All points of an area of the given size, row by row
+fn points(size: Size) -> impl Iterator<Item = Point> {
+    (0..size.height as i32).flat_map(move |y| (0..size.width as i32).map(move |x| Point::new(x, y)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -364,6 +1174,35 @@ mod tests {
         prelude::*,
         primitives::{Line, PrimitiveStyle},
     };
+    use proptest::prelude::*;
+
+    #[test]
+    fn orientation() {
+        // a portrait panel, e.g. the 2.9"
+        let mut display = Display::<128, 296, false, { 128 * 296 / 8 }, Color>::default();
+        assert_eq!(display.orientation(), Orientation::Portrait);
+        for (orientation, rotation) in [
+            (Orientation::Portrait, DisplayRotation::Rotate0),
+            (Orientation::Landscape, DisplayRotation::Rotate90),
+            (Orientation::PortraitFlipped, DisplayRotation::Rotate180),
+            (Orientation::LandscapeFlipped, DisplayRotation::Rotate270),
+        ] {
+            display.set_orientation(orientation);
+            assert_eq!(display.rotation(), rotation);
+            assert_eq!(display.orientation(), orientation);
+        }
+
+        // a landscape panel, e.g. the 4.2"
+        let mut buffer = [0u8; 400 * 300 / 8];
+        let mut display = VarDisplay::<Color>::new(400, 300, &mut buffer, false).unwrap();
+        assert_eq!(display.orientation(), Orientation::Landscape);
+        display.set_orientation(Orientation::Portrait);
+        assert_eq!(display.rotation(), DisplayRotation::Rotate270);
+        assert_eq!(display.size(), Size::new(300, 400));
+        display.set_orientation(Orientation::LandscapeFlipped);
+        assert_eq!(display.rotation(), DisplayRotation::Rotate180);
+        assert_eq!(display.orientation(), Orientation::LandscapeFlipped);
+    }
 
     // test buffer length
     #[test]
@@ -455,6 +1294,197 @@ mod tests {
         }
     }
 
+    #[test]
+    fn graphics_binary_interop() {
+        let mut display = Display::<12, 2, false, { 2 * 2 }, Color>::default();
+        // Second row padded to a full byte
+        let binary = [0b1000_0000, 0b0000_0000, 0b0000_0000, 0b0001_0000];
+        display.copy_from_binary(&binary).unwrap();
+
+        let buffer = display.buffer();
+        assert_eq!(buffer, [0b0111_1111, 0xFF, 0xFF, 0b1110_1111]);
+
+        // Same position as drawing on the display
+        let mut drawn = Display::<12, 2, false, { 2 * 2 }, Color>::default();
+        drawn.set_pixel(Pixel(Point::new(11, 1), Color::White));
+        assert_eq!(drawn.buffer()[3], 0b0001_0000);
+
+        let mut copy = [0u8; 4];
+        display.copy_to_binary(&mut copy).unwrap();
+        assert_eq!(copy, binary);
+
+        assert!(matches!(
+            display.copy_from_binary(&binary[..3]),
+            Err(VarDisplayError::BufferSizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+        assert!(display.copy_to_binary(&mut [0u8; 5]).is_err());
+
+        // The same pixels in a buffer with the leftmost pixel in the least significant bit
+        let mut lsb_first = Display::<12, 2, false, { 2 * 2 }, Color>::default();
+        lsb_first.set_bit_order(BitOrder::LsbFirst);
+        lsb_first.copy_from_binary(&binary).unwrap();
+        assert_eq!(lsb_first.buffer(), [0b1111_1110, 0xFF, 0xFF, 0b1111_0111]);
+        assert_eq!(lsb_first.get_pixel(Point::new(0, 0)), Some(Color::Black));
+        assert_eq!(lsb_first.get_pixel(Point::new(11, 1)), Some(Color::Black));
+
+        let mut copy = [0u8; 4];
+        lsb_first.copy_to_binary(&mut copy).unwrap();
+        assert_eq!(copy, binary);
+    }
+
+    #[test]
+    fn graphics_from_buffer() {
+        type Display8x2 = Display<8, 2, false, { 8 * 2 / 8 }, Color>;
+
+        let mut short = [0u8; 1];
+        assert!(matches!(
+            Display8x2::from_buffer(&mut short),
+            Err(VarDisplayError::BufferSizeMismatch {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        let mut long = [0u8; 3];
+        assert!(matches!(
+            Display8x2::from_buffer(&mut long),
+            Err(VarDisplayError::BufferSizeMismatch {
+                expected: 2,
+                actual: 3
+            })
+        ));
+
+        let mut buffer = [0u8; 2];
+        let mut display = Display8x2::from_buffer(&mut buffer).unwrap();
+        display.set_pixel(Pixel(Point::new(0, 1), Color::White));
+        assert_eq!(display.buffer().len(), 2);
+        assert_eq!(buffer, [0x00, 0x80]);
+    }
+
+    #[test]
+    fn graphics_get_pixel() {
+        let mut display = Display::<4, 4, false, { 4 * 2 }, TriColor>::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        display.set_pixel(Pixel(Point::new(1, 0), TriColor::White));
+        display.set_pixel(Pixel(Point::new(2, 3), TriColor::Black));
+
+        assert_eq!(display.get_pixel(Point::new(1, 0)), Some(TriColor::White));
+        assert_eq!(display.get_pixel(Point::new(2, 3)), Some(TriColor::Black));
+        assert_eq!(
+            display.get_pixel(Point::new(0, 0)),
+            Some(TriColor::Chromatic)
+        );
+        assert_eq!(display.get_pixel(Point::new(4, 0)), None);
+
+        assert_eq!(display.pixels().count(), 16);
+        let mut black = display
+            .pixels()
+            .filter(|Pixel(_, color)| *color == TriColor::Black);
+        assert_eq!(black.next(), Some(Pixel(Point::new(2, 3), TriColor::Black)));
+        assert_eq!(black.next(), None);
+
+        let mut display = Display::<4, 2, false, { 4 * 2 * 4 / 8 }, OctColor>::default();
+        display.set_pixel(Pixel(Point::new(3, 1), OctColor::Orange));
+        assert_eq!(display.get_pixel(Point::new(3, 1)), Some(OctColor::Orange));
+        assert_eq!(display.get_pixel(Point::new(2, 1)), Some(OctColor::Black));
+    }
+
+    // 122 pixels wide like the 2.13" panels, so every line ends with 6 padding bits
+    #[test]
+    fn graphics_rotation_unaligned_width() {
+        const WIDTH: u32 = 122;
+        const HEIGHT: u32 = 250;
+        const LINE: usize = 16;
+        type Display2in13 = Display<WIDTH, HEIGHT, false, { LINE * HEIGHT as usize }, Color>;
+
+        // physical corners: top left, top right, bottom left, bottom right
+        let corners = |display: &Display2in13| {
+            let buffer = display.buffer();
+            let last_line = (HEIGHT as usize - 1) * LINE;
+            [
+                buffer[0] & 0x80 != 0,
+                buffer[LINE - 1] & 0x40 != 0,
+                buffer[last_line] & 0x80 != 0,
+                buffer[last_line + LINE - 1] & 0x40 != 0,
+            ]
+        };
+
+        for (rotation, expected) in [
+            (DisplayRotation::Rotate0, [true, false, false, false]),
+            (DisplayRotation::Rotate90, [false, true, false, false]),
+            (DisplayRotation::Rotate180, [false, false, false, true]),
+            (DisplayRotation::Rotate270, [false, false, true, false]),
+        ] {
+            let mut display = Display2in13::default();
+            display.set_rotation(rotation);
+            let size = display.size();
+
+            // origin of the rotated display
+            display.set_pixel(Pixel(Point::new(0, 0), Color::White));
+            assert_eq!(corners(&display), expected);
+
+            // the opposite corner is set without touching the padding
+            display.set_pixel(Pixel(
+                Point::new(size.width as i32 - 1, size.height as i32 - 1),
+                Color::White,
+            ));
+            let set = display.buffer().iter().map(|b| b.count_ones()).sum::<u32>();
+            assert_eq!(set, 2);
+            for line in display.buffer().chunks(LINE) {
+                assert_eq!(line[LINE - 1] & 0x3F, 0);
+            }
+
+            // pixels just outside are ignored
+            display.set_pixel(Pixel(Point::new(size.width as i32, 0), Color::White));
+            display.set_pixel(Pixel(Point::new(0, size.height as i32), Color::White));
+            let set = display.buffer().iter().map(|b| b.count_ones()).sum::<u32>();
+            assert_eq!(set, 2);
+        }
+    }
+
+    #[test]
+    fn graphics_bit_order() {
+        let mut display = Display::<8, 1, false, 1, Color>::default();
+        display.set_bit_order(BitOrder::LsbFirst);
+        display.set_pixel(Pixel(Point::new(0, 0), Color::White));
+        display.set_pixel(Pixel(Point::new(2, 0), Color::White));
+        assert_eq!(display.buffer(), [0b0000_0101]);
+        assert_eq!(display.get_pixel(Point::new(2, 0)), Some(Color::White));
+
+        display.convert_bit_order(BitOrder::MsbFirst);
+        assert_eq!(display.buffer(), [0b1010_0000]);
+        assert_eq!(display.get_pixel(Point::new(2, 0)), Some(Color::White));
+
+        let mut gray = [0b11_10_01_00];
+        reverse_bit_order::<Gray2>(&mut gray);
+        assert_eq!(gray, [0b00_01_10_11]);
+
+        let mut oct = [0x12];
+        reverse_bit_order::<OctColor>(&mut oct);
+        assert_eq!(oct, [0x21]);
+    }
+
+    #[test]
+    fn graphics_stride() {
+        type Display2in13 = Display<122, 250, false, { 16 * 250 }, Color>;
+        assert_eq!(Display2in13::ROW_BYTES, 16);
+        assert_eq!(Display2in13::PADDED_WIDTH, 128);
+        assert_eq!(Display2in13::plane_len(), 4000);
+
+        type Display3x2 = Display<3, 2, false, 4, TriColor>;
+        assert_eq!(Display3x2::ROW_BYTES, 1);
+        assert_eq!(Display3x2::PADDED_WIDTH, 8);
+        assert_eq!(Display3x2::plane_len(), 2);
+
+        let mut buffer = [0u8; 4];
+        let display = VarDisplay::<OctColor>::new(3, 2, &mut buffer, false).unwrap();
+        assert_eq!(display.row_bytes(), 2);
+        assert_eq!(display.padded_width(), 4);
+        assert_eq!(display.plane_len(), 4);
+    }
+
     #[test]
     fn graphics_new() {
         let mut display = Display::<4, 4, false, { 4 * 4 * 2 / 8 }, TriColor>::default();
@@ -467,8 +1497,8 @@ mod tests {
         std::println!("{:?}", bw_buffer);
         std::println!("{:?}", chromatic_buffer);
 
-        assert_eq!(bw_buffer, [64, 0]);
-        assert_eq!(chromatic_buffer, [64, 0]);
+        assert_eq!(*bw_buffer, [64, 0]);
+        assert_eq!(*chromatic_buffer, [64, 0]);
 
         display.set_pixel(Pixel(Point::new(0, 0), TriColor::Chromatic));
 
@@ -478,8 +1508,8 @@ mod tests {
         std::println!("{:?}", bw_buffer);
         std::println!("{:?}", chromatic_buffer);
 
-        assert_eq!(bw_buffer, [192, 0]);
-        assert_eq!(chromatic_buffer, [64, 0]);
+        assert_eq!(*bw_buffer, [192, 0]);
+        assert_eq!(*chromatic_buffer, [64, 0]);
 
         display.set_pixel(Pixel(Point::new(0, 0), TriColor::Black));
 
@@ -489,7 +1519,269 @@ mod tests {
         std::println!("{:?}", bw_buffer);
         std::println!("{:?}", chromatic_buffer);
 
-        assert_eq!(bw_buffer, [64, 0]);
-        assert_eq!(chromatic_buffer, [192, 0]);
+        assert_eq!(*bw_buffer, [64, 0]);
+        assert_eq!(*chromatic_buffer, [192, 0]);
+    }
+
+    #[test]
+    fn binary_color_adapter() {
+        let mut display = Display::<8, 2, false, { 8 * 2 * 2 / 8 }, TriColor>::default();
+        display.clear(TriColor::White).ok();
+        display.set_rotation(DisplayRotation::Rotate180);
+        display.set_pixel(Pixel(Point::new(7, 1), TriColor::Chromatic));
+
+        // monochrome drawing on the black/white plane keeps the chromatic pixel
+        let mut plane = display.bw_plane_mut();
+        let mut target = BinaryColorAdapter::new(&mut plane);
+        let _ = Line::new(Point::new(0, 1), Point::new(7, 1))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(&mut target);
+        assert_eq!(*display.bw_buffer(), [0x00, 0xFF]);
+        assert_eq!(*display.chromatic_buffer(), [0x7F, 0xFF]);
+        assert_eq!(
+            display.get_pixel(Point::new(7, 1)),
+            Some(TriColor::Chromatic)
+        );
+
+        // on the display itself it is overwritten
+        let mut target = BinaryColorAdapter::new(&mut display);
+        assert_eq!(target.bounding_box().size, Size::new(8, 2));
+        target.clear(BinaryColor::Off).ok();
+        assert_eq!(*display.bw_buffer(), [0xFF, 0xFF]);
+        assert_eq!(*display.chromatic_buffer(), [0xFF, 0xFF]);
+    }
+
+    const ROTATIONS: [DisplayRotation; 4] = [
+        DisplayRotation::Rotate0,
+        DisplayRotation::Rotate90,
+        DisplayRotation::Rotate180,
+        DisplayRotation::Rotate270,
+    ];
+
+    proptest! {
+        #[test]
+        fn graphics_pixels_round_trip(
+            (height, quarters, x, y) in (1u32..40, 0usize..4).prop_flat_map(|(height, quarters)| {
+                // a quarter turn swaps the sides of the 122 px wide display
+                let (columns, rows) = if quarters % 2 == 1 { (height, 122) } else { (122, height) };
+                (Just(height), Just(quarters), 0..columns, 0..rows)
+            }),
+            lsb_first in any::<bool>(),
+        ) {
+            let mut buffer = [0u8; 16 * 40];
+            let len = crate::buffer_len(122, height as usize);
+            let mut display =
+                VarDisplay::<Color>::new(122, height, &mut buffer[..len], false).unwrap();
+            display.set_rotation(ROTATIONS[quarters]);
+            if lsb_first {
+                display.set_bit_order(BitOrder::LsbFirst);
+            }
+            let point = Point::new(x as i32, y as i32);
+
+            prop_assert_eq!(display.get_pixel(point), Some(Color::Black));
+            display.set_pixel(Pixel(point, Color::White));
+            prop_assert_eq!(display.get_pixel(point), Some(Color::White));
+            // a pixel is a single bit of the buffer
+            let bits: u32 = display.buffer().iter().map(|byte| byte.count_ones()).sum();
+            prop_assert_eq!(bits, 1);
+
+            display.set_pixel(Pixel(point, Color::Black));
+            prop_assert_eq!(display.get_pixel(point), Some(Color::Black));
+            prop_assert!(display.buffer().iter().all(|&byte| byte == 0));
+        }
+    }
+
+    #[test]
+    fn graphics_rotation_covers_all_pixels() {
+        let mut buffer = [0u8; 16 * 5];
+        for width in (1..=17).chain([122]) {
+            for height in 1..=5 {
+                let len = crate::buffer_len(width as usize, height as usize);
+                for rotation in ROTATIONS {
+                    buffer.fill(0);
+                    let mut display =
+                        VarDisplay::<Color>::new(width, height, &mut buffer[..len], false).unwrap();
+                    display.set_rotation(rotation);
+                    let size = display.size();
+                    if matches!(
+                        rotation,
+                        DisplayRotation::Rotate90 | DisplayRotation::Rotate270
+                    ) {
+                        assert_eq!(size, Size::new(height, width));
+                    } else {
+                        assert_eq!(size, Size::new(width, height));
+                    }
+                    for point in points(size) {
+                        display.set_pixel(Pixel(point, Color::White));
+                    }
+
+                    // every pixel is set once, the padding is never touched
+                    let set: u32 = display.buffer().iter().map(|b| b.count_ones()).sum();
+                    assert_eq!(set, width * height);
+                    let padding = 8 - (width % 8);
+                    if padding < 8 {
+                        let row_bytes = crate::row_bytes(width as usize);
+                        for line in display.buffer().chunks(row_bytes) {
+                            assert_eq!(line[row_bytes - 1] & ((1 << padding) - 1), 0);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lsb_first_frames_are_sent_msb_first() {
+        extern crate std;
+        use crate::epd7in5_v2::{Display7in5, Epd7in5};
+        use crate::traits::EpdNew;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::{vec, vec::Vec};
+
+        let mut msb_first = Display7in5::default();
+        msb_first.set_pixel(Pixel(Point::new(0, 0), Color::White));
+        msb_first.set_pixel(Pixel(Point::new(10, 1), Color::White));
+        let mut display = Display7in5::default();
+        display.set_bit_order(BitOrder::LsbFirst);
+        display.set_pixel(Pixel(Point::new(0, 0), Color::White));
+        display.set_pixel(Pixel(Point::new(10, 1), Color::White));
+        let lsb_first = display.buffer().to_vec();
+        assert!(lsb_first != msb_first.buffer());
+
+        // Linux limits the spi transfers to 4096 bytes
+        let chunk_len = if cfg!(target_os = "linux") {
+            4096
+        } else {
+            msb_first.buffer().len()
+        };
+        let mut expectations = vec![
+            spi::Transaction::write(vec![0x71]),
+            spi::Transaction::write(vec![0x13]),
+        ];
+        expectations.extend(
+            msb_first
+                .buffer()
+                .chunks(chunk_len)
+                .map(|chunk| spi::Transaction::write(chunk.to_vec())),
+        );
+        let mut spi = spi::Mock::new(&expectations);
+        let toggles = (0..3)
+            .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+            .collect::<Vec<_>>();
+        let mut cs = Mock::new(&toggles);
+        let mut dc = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]);
+        let mut busy = Mock::new(&[Transaction::get(State::High)]);
+        let mut epd: Epd7in5<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            Epd7in5::new_uninitialized(cs.clone(), busy.clone(), dc.clone(), Mock::new(&[]), None);
+
+        display
+            .send_frame(&mut epd, &mut spi, &mut MockNoop::new())
+            .unwrap();
+        assert_eq!(display.bit_order(), BitOrder::LsbFirst);
+        assert!(display.buffer() == lsb_first);
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+    }
+
+    #[test]
+    fn partial_window() {
+        let area = Rectangle::new(Point::new(10, 3), Size::new(4, 2));
+        // widened to the bytes 1 of the lines 3 and 4
+        let window = byte_window(area, 122, 250, DisplayRotation::Rotate0).unwrap();
+        assert_eq!(window, Rectangle::new(Point::new(8, 3), Size::new(8, 2)));
+
+        // the lines 3 and 4 of the drawing are the pixels 118 and 117 of the unrotated lines
+        let window = byte_window(area, 122, 250, DisplayRotation::Rotate90).unwrap();
+        assert_eq!(window, Rectangle::new(Point::new(112, 10), Size::new(8, 4)));
+
+        // clipped to the display, the last byte includes the padding
+        let area = Rectangle::new(Point::new(100, 240), Size::new(100, 100));
+        let window = byte_window(area, 122, 250, DisplayRotation::Rotate0).unwrap();
+        assert_eq!(
+            window,
+            Rectangle::new(Point::new(96, 240), Size::new(32, 10))
+        );
+
+        let outside = Rectangle::new(Point::new(122, 0), Size::new(8, 8));
+        assert_eq!(
+            byte_window(outside, 122, 250, DisplayRotation::Rotate0),
+            None
+        );
+    }
+
+    #[test]
+    fn partial_window_bytes() {
+        let mut display = Display::<24, 4, false, { 3 * 4 }, Color>::default();
+        display.set_pixel(Pixel(Point::new(8, 1), Color::White));
+        display.set_pixel(Pixel(Point::new(23, 2), Color::White));
+
+        let window = Rectangle::new(Point::new(8, 1), Size::new(16, 2));
+        let mut scratch = [0xAA; 5];
+        let len = copy_window::<()>(
+            display.buffer(),
+            24,
+            BitOrder::MsbFirst,
+            window,
+            &mut scratch,
+        )
+        .unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(scratch, [0x80, 0x00, 0x00, 0x01, 0xAA]);
+
+        assert!(matches!(
+            copy_window::<()>(
+                display.buffer(),
+                24,
+                BitOrder::MsbFirst,
+                window,
+                &mut scratch[..3]
+            ),
+            Err(Error::InvalidBufferSize {
+                expected: 4,
+                actual: 3
+            })
+        ));
+
+        // sent MSB first, whatever order the display draws in
+        display.convert_bit_order(BitOrder::LsbFirst);
+        let len = copy_window::<()>(
+            display.buffer(),
+            24,
+            BitOrder::LsbFirst,
+            window,
+            &mut scratch,
+        )
+        .unwrap();
+        assert_eq!(len, 4);
+        assert_eq!(scratch, [0x80, 0x00, 0x00, 0x01, 0xAA]);
+    }
+
+    #[test]
+    fn rle_round_trip() {
+        let mut display = Display::<16, 8, true, { 16 * 8 * 2 / 8 }, TriColor>::default();
+        display.set_pixel(Pixel(Point::new(3, 2), TriColor::Black));
+        display.set_pixel(Pixel(Point::new(9, 6), TriColor::Chromatic));
+
+        let mut encoded = [0u8; 32];
+        let len = encoded_rle_len(display.buffer());
+        assert_eq!(encode_rle(display.buffer(), &mut encoded), Some(len));
+        assert!(len < display.buffer().len());
+        assert_eq!(encode_rle(display.buffer(), &mut encoded[..len - 1]), None);
+
+        let mut decoded = [0u8; 32];
+        assert_eq!(decode_rle(&encoded[..len], &mut decoded), Some(32));
+        assert_eq!(decoded, display.buffer());
+        assert_eq!(decode_rle(&encoded[..len], &mut decoded[..31]), None);
     }
 }
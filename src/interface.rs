@@ -1,13 +1,31 @@
-use crate::traits::Command;
+use crate::error::Error;
+use crate::lut_table::Runs;
+use crate::traits::{Command, CommandObserver};
+use crate::transport::{SingleSpi, Transport};
 use core::marker::PhantomData;
 use embedded_hal::{
-    blocking::{delay::*, spi::Write},
+    blocking::{
+        delay::*,
+        spi::{Transfer, Write},
+    },
     digital::v2::*,
 };
 
+/// Time the busy pin may take to release after a reset in `probe`
+pub(crate) const PROBE_TIMEOUT_MS: u32 = 1_000;
+
+/// Hooks of the user called by the interface, see [`EpdHooks`](crate::prelude::EpdHooks)
+#[derive(Default)]
+pub struct Hooks {
+    /// Notified of every command, data transaction and busy wait
+    pub(crate) observer: Option<&'static dyn CommandObserver>,
+    /// Called on every poll of the busy pin
+    pub(crate) poll_hook: Option<&'static dyn Fn()>,
+}
+
 /// The Connection Interface of all (?) Waveshare EPD-Devices
 ///
-pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
+pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT = SingleSpi> {
     /// SPI
     _spi: PhantomData<SPI>,
     /// DELAY
@@ -20,8 +38,15 @@ pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
     dc: DC,
     /// Pin for Resetting
     rst: RST,
+    /// Sends the frames
+    transport: TRANSPORT,
     /// number of ms the idle loop should sleep on
     delay_us: u32,
+    /// Hooks of the user
+    hooks: Hooks,
+    /// Bytes sent since [begin_data()](DisplayInterface::begin_data()), `None` outside of
+    /// data transactions
+    data_len: Option<usize>,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>
@@ -33,47 +58,132 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    /// Creates a new `DisplayInterface` struct
+    /// Creates a new `DisplayInterface` struct sending everything over spi
     ///
     /// If no delay is given, a default delay of 10ms is used.
     pub fn new(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self {
+        Self::with_transport(cs, busy, dc, rst, delay_us, SingleSpi)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+    DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, TRANSPORT>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+    TRANSPORT: Transport<SPI>,
+{
+    /// Creates a new `DisplayInterface` struct sending the data with `transport`
+    ///
+    /// If no delay is given, a default delay of 10ms is used.
+    pub fn with_transport(
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay_us: Option<u32>,
+        transport: TRANSPORT,
+    ) -> Self {
         // default delay of 10ms
         let delay_us = delay_us.unwrap_or(10_000);
         DisplayInterface {
-            _spi: PhantomData::default(),
-            _delay: PhantomData::default(),
+            _spi: PhantomData,
+            _delay: PhantomData,
             cs,
             busy,
             dc,
             rst,
+            transport,
             delay_us,
+            hooks: Hooks::default(),
+            data_len: None,
         }
     }
 
+    /// Returns the hooks of the user
+    pub(crate) fn hooks(&mut self) -> &mut Hooks {
+        &mut self.hooks
+    }
+
+    /// Returns the pins
+    pub(crate) fn release(self) -> (CS, BUSY, DC, RST) {
+        (self.cs, self.busy, self.dc, self.rst)
+    }
+
     /// Basic function for sending [Commands](Command).
     ///
     /// Enables direct interaction with the device with the help of [data()](DisplayInterface::data())
     pub(crate) fn cmd<T: Command>(&mut self, spi: &mut SPI, command: T) -> Result<(), SPI::Error> {
         // low for commands
         let _ = self.dc.set_low();
+        // activate spi with cs low
+        let _ = self.cs.set_low();
+
+        if let Some(observer) = self.hooks.observer {
+            observer.on_command(command.address());
+        }
 
         // Transfer the command over spi
-        self.write(spi, &[command.address()])
+        let result = write_spi(spi, &[command.address()]);
+        self.end();
+        result
     }
 
     /// Basic function for sending an array of u8-values of data over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](Epd4in2::command())
     pub(crate) fn data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+        self.begin_data();
+        let result = self.write(spi, data);
+        self.end();
+        result
+    }
+
+    /// Starts sending data, CS stays asserted until [end()](DisplayInterface::end())
+    ///
+    /// Everything passed to [write()](DisplayInterface::write()) in between is sent as one
+    /// transaction, e.g. a whole plane.
+    pub(crate) fn begin_data(&mut self) {
         // high for data
         let _ = self.dc.set_high();
+        // activate spi with cs low
+        let _ = self.cs.set_low();
+        self.data_len = Some(0);
+    }
 
-        for val in data.iter().copied() {
-            // Transfer data one u8 at a time over spi
-            self.write(spi, &[val])?;
+    /// Sends data of the transaction started with [begin_data()](DisplayInterface::begin_data())
+    pub(crate) fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+        if let Some(len) = self.data_len.as_mut() {
+            *len += data.len();
         }
+        write_spi(spi, data)
+    }
 
-        Ok(())
+    /// Ends the transaction started with [begin_data()](DisplayInterface::begin_data())
+    pub(crate) fn end(&mut self) {
+        // deactivate spi with cs high
+        let _ = self.cs.set_high();
+        if let (Some(len), Some(observer)) = (self.data_len.take(), self.hooks.observer) {
+            observer.on_data_len(len);
+        }
+    }
+
+    /// Calls the poll hook of the user, while waiting for the busy pin
+    fn poll_hook(&self) {
+        if let Some(hook) = self.hooks.poll_hook {
+            hook();
+        }
+    }
+
+    /// Reports a busy wait of `polls` times the poll delay to the observer
+    fn observe_busy_wait(&self, polls: u32, delay_us: u32) {
+        if let Some(observer) = self.hooks.observer {
+            observer.on_busy_wait(polls.saturating_mul(delay_us));
+        }
     }
 
     /// Basic function for sending [Commands](Command) and the data belonging to it.
@@ -89,6 +199,27 @@ where
         self.data(spi, data)
     }
 
+    /// Sends a [Command](Command) followed by a frame, which is sent with the transport of
+    /// the interface
+    pub(crate) fn cmd_with_frame<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        frame: &[u8],
+    ) -> Result<(), SPI::Error> {
+        self.cmd(spi, command)?;
+        self.begin_data();
+        self.data_len = Some(frame.len());
+        let result = self.transport.write_data(spi, frame);
+        self.end();
+        result
+    }
+
+    /// Number of data lines used by the transport for the frames
+    pub(crate) fn frame_data_lines(&self) -> u8 {
+        self.transport.data_lines()
+    }
+
     /// Basic function for sending the same byte of data (one u8) multiple times over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](ConnectionInterface::command())
@@ -98,35 +229,152 @@ where
         val: u8,
         repetitions: u32,
     ) -> Result<(), SPI::Error> {
+        let chunk = [val; 64];
+        let mut remaining = repetitions as usize;
+        self.begin_data();
+        let mut result = Ok(());
+        while remaining > 0 && result.is_ok() {
+            let len = remaining.min(chunk.len());
+            result = self.write(spi, &chunk[..len]);
+            remaining -= len;
+        }
+        self.end();
+        result
+    }
+
+    /// Sends the next `len` bytes of a run length encoded buffer
+    ///
+    /// The runs are expanded in small chunks, so the expanded buffer is never kept in memory.
+    /// Less bytes are sent if the runs end before.
+    pub(crate) fn data_rle(
+        &mut self,
+        spi: &mut SPI,
+        runs: &mut Runs<'_>,
+        len: usize,
+    ) -> Result<(), SPI::Error> {
+        let mut chunk = [0u8; 64];
+        let mut remaining = len;
+        self.begin_data();
+        let mut result = Ok(());
+        while remaining > 0 && result.is_ok() {
+            let size = remaining.min(chunk.len());
+            let mut filled = 0;
+            while let Some((run, byte)) = runs.take(size - filled) {
+                chunk[filled..filled + run].fill(byte);
+                filled += run;
+                if filled == size {
+                    break;
+                }
+            }
+            if filled == 0 {
+                break;
+            }
+            result = self.write(spi, &chunk[..filled]);
+            remaining -= filled;
+        }
+        self.end();
+        result
+    }
+
+    /// Sends one bit of every pixel of a buffer with 2 bits per pixel
+    ///
+    /// The buffer holds 4 pixels per byte, the first pixel in the upper bits.
+    /// `bit` selects the bit of the pixel values which is sent (0 for the lower one),
+    /// packed as 8 pixels per byte like a black/white buffer.
+    pub(crate) fn data_gray2_plane(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        bit: u8,
+    ) -> Result<(), SPI::Error> {
+        let mut chunk = [0u8; 64];
+        self.begin_data();
+        let mut result = Ok(());
+        for pixels in buffer.chunks(2 * chunk.len()) {
+            let len = pixels.len().div_ceil(2);
+            for (byte, pixels) in chunk.iter_mut().zip(pixels.chunks(2)) {
+                *byte = gray2_plane_byte(pixels, bit);
+            }
+            result = self.write(spi, &chunk[..len]);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.end();
+        result
+    }
+
+    /// Basic function for sending a [Command](Command) and reading back the data the
+    /// controller answers with
+    ///
+    /// This only works if the data line of the display is also connected to the MISO
+    /// line of the spi bus.
+    pub(crate) fn cmd_read<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        buffer: &mut [u8],
+    ) -> Result<(), <SPI as Write<u8>>::Error>
+    where
+        SPI: Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    {
+        self.cmd(spi, command)?;
+
         // high for data
         let _ = self.dc.set_high();
-        // Transfer data (u8) over spi
-        for _ in 0..repetitions {
-            self.write(spi, &[val])?;
-        }
-        Ok(())
+        // activate spi with cs low
+        let _ = self.cs.set_low();
+
+        buffer.iter_mut().for_each(|b| *b = 0x00);
+        let result = spi.transfer(buffer).map(|_| ());
+
+        // deactivate spi with cs high
+        let _ = self.cs.set_high();
+
+        result
     }
 
-    // spi write helper/abstraction function
-    fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    /// Sends a [Command](Command) and compares the data the controller answers with to
+    /// `expected`, after skipping `dummy` bytes
+    ///
+    /// The data is read in small chunks, so no buffer of the size of `expected` is needed.
+    /// This only works if the data line of the display is also connected to the MISO
+    /// line of the spi bus.
+    pub(crate) fn cmd_verify<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+        dummy: usize,
+        expected: &[u8],
+    ) -> Result<bool, <SPI as Write<u8>>::Error>
+    where
+        SPI: Transfer<u8, Error = <SPI as Write<u8>>::Error>,
+    {
+        self.cmd(spi, command)?;
+
+        // high for data
+        let _ = self.dc.set_high();
         // activate spi with cs low
         let _ = self.cs.set_low();
 
-        // transfer spi data
-        // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
-        // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
-        if cfg!(target_os = "linux") {
-            for data_chunk in data.chunks(4096) {
-                spi.write(data_chunk)?;
+        let result = (|| {
+            let mut chunk = [0u8; 32];
+            for _ in 0..dummy {
+                spi.transfer(&mut chunk[..1])?;
             }
-        } else {
-            spi.write(data)?;
-        }
+            let mut matches = true;
+            for expected in expected.chunks(chunk.len()) {
+                let read = &mut chunk[..expected.len()];
+                read.fill(0x00);
+                matches &= spi.transfer(read)? == expected;
+            }
+            Ok(matches)
+        })();
 
         // deactivate spi with cs high
         let _ = self.cs.set_high();
 
-        Ok(())
+        result
     }
 
     /// Waits until device isn't busy anymore (busy == HIGH)
@@ -142,7 +390,23 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     pub(crate) fn wait_until_idle(&mut self, delay: &mut DELAY, is_busy_low: bool) {
+        self.poll_until_idle(delay, is_busy_low, &mut || {})
+    }
+
+    /// Same as `wait_until_idle`, calling `on_poll` on every poll of the busy pin
+    ///
+    /// Like all the waits, this also calls the poll hook of the user.
+    pub(crate) fn poll_until_idle(
+        &mut self,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+        on_poll: &mut dyn FnMut(),
+    ) {
+        let mut polls = 0;
         while self.is_busy(is_busy_low) {
+            polls += 1;
+            self.poll_hook();
+            on_poll();
             // This has been removed and added many time :
             // - it is faster to not have it
             // - it is complicated to pass the delay everywhere all the time
@@ -153,29 +417,86 @@ where
                 delay.delay_us(self.delay_us);
             }
         }
+        self.observe_busy_wait(polls, self.delay_us);
     }
 
-    /// Same as `wait_until_idle` for device needing a command to probe Busy pin
-    pub(crate) fn wait_until_idle_with_cmd<T: Command>(
+    /// Same as `poll_until_idle` for device needing a command to probe Busy pin
+    pub(crate) fn poll_until_idle_with_cmd<T: Command>(
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
         is_busy_low: bool,
         status_command: T,
+        on_poll: &mut dyn FnMut(),
     ) -> Result<(), SPI::Error> {
         self.cmd(spi, status_command)?;
         if self.delay_us > 0 {
             delay.delay_us(self.delay_us);
         }
+        let mut polls = 1;
         while self.is_busy(is_busy_low) {
+            polls += 1;
+            self.poll_hook();
+            on_poll();
             self.cmd(spi, status_command)?;
             if self.delay_us > 0 {
                 delay.delay_us(self.delay_us);
             }
         }
+        self.observe_busy_wait(polls, self.delay_us);
         Ok(())
     }
 
+    /// Same as `wait_until_idle`, but gives up with [`Error::BusyStuck`] after `timeout_ms`
+    ///
+    /// The busy pin is polled every millisecond.
+    pub(crate) fn wait_until_idle_timeout(
+        &mut self,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        let mut waited_ms = 0;
+        while self.is_busy(is_busy_low) {
+            if waited_ms >= timeout_ms {
+                self.observe_busy_wait(waited_ms, 1_000);
+                return Err(Error::BusyStuck);
+            }
+            self.poll_hook();
+            delay.delay_us(1_000);
+            waited_ms += 1;
+        }
+        self.observe_busy_wait(waited_ms, 1_000);
+        Ok(())
+    }
+
+    /// Same as `poll_until_idle_with_cmd`, but gives up with [`Error::BusyStuck`] after
+    /// `timeout_ms`
+    pub(crate) fn wait_until_idle_with_cmd_timeout<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+        status_command: T,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>> {
+        let mut waited_ms = 0;
+        loop {
+            self.cmd(spi, status_command).map_err(Error::Spi)?;
+            delay.delay_us(1_000);
+            if !self.is_busy(is_busy_low) {
+                self.observe_busy_wait(waited_ms + 1, 1_000);
+                return Ok(());
+            }
+            if waited_ms >= timeout_ms {
+                self.observe_busy_wait(waited_ms + 1, 1_000);
+                return Err(Error::BusyStuck);
+            }
+            self.poll_hook();
+            waited_ms += 1;
+        }
+    }
+
     /// Checks if device is still busy
     ///
     /// This is normally handled by the more complicated commands themselves,
@@ -213,3 +534,367 @@ where
         delay.delay_us(200_000);
     }
 }
+
+/// Writes `data` over spi
+pub(crate) fn write_spi<SPI: Write<u8>>(spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
+    // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
+    if cfg!(target_os = "linux") {
+        for data_chunk in data.chunks(4096) {
+            spi.write(data_chunk)?;
+        }
+    } else {
+        spi.write(data)?;
+    }
+    Ok(())
+}
+
+/// Extracts the bit `bit` of the (up to) 8 pixels of two bytes of a 2 bits per pixel buffer
+pub(crate) fn gray2_plane_byte(pixels: &[u8], bit: u8) -> u8 {
+    let mut byte = 0;
+    for (i, &value) in pixels.iter().take(2).enumerate() {
+        for j in 0..4 {
+            if (value >> (6 - 2 * j as u8 + bit)) & 0x01 == 0x01 {
+                byte |= 0x80 >> (i * 4 + j as usize);
+            }
+        }
+    }
+    byte
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gray2_planes() {
+        // white, light gray, dark gray, black, black, dark gray, light gray, white
+        let pixels = [0b1110_0100, 0b0001_1011];
+        assert_eq!(gray2_plane_byte(&pixels, 0), 0b1010_0101);
+        assert_eq!(gray2_plane_byte(&pixels, 1), 0b1100_0011);
+
+        assert_eq!(gray2_plane_byte(&[0xFF], 0), 0xF0);
+    }
+
+    #[test]
+    fn data_is_sent_under_one_cs_assertion() {
+        extern crate std;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![1, 2, 3]),
+            spi::Transaction::write(vec![0xFF; 64]),
+            spi::Transaction::write(vec![0xFF; 6]),
+        ]);
+        let mut cs = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]);
+        let mut dc = Mock::new(&[Transaction::set(State::High), Transaction::set(State::High)]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(cs.clone(), Mock::new(&[]), dc.clone(), Mock::new(&[]), None);
+
+        interface.data(&mut spi, &[1, 2, 3]).unwrap();
+        interface.data_x_times(&mut spi, 0xFF, 70).unwrap();
+
+        spi.done();
+        cs.done();
+        dc.done();
+    }
+
+    #[test]
+    fn gray2_plane_is_packed_in_chunks() {
+        extern crate std;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        // 150 bytes of dark gray pixels followed by one byte of white pixels
+        let mut buffer = vec![0b0101_0101; 150];
+        buffer.push(0xFF);
+        let mut last_chunk = vec![0xFF; 11];
+        last_chunk.push(0xF0);
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![0xFF; 64]),
+            spi::Transaction::write(last_chunk),
+        ]);
+        let mut cs = Mock::new(&[Transaction::set(State::Low), Transaction::set(State::High)]);
+        let mut dc = Mock::new(&[Transaction::set(State::High)]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(cs.clone(), Mock::new(&[]), dc.clone(), Mock::new(&[]), None);
+
+        interface.data_gray2_plane(&mut spi, &buffer, 0).unwrap();
+
+        spi.done();
+        cs.done();
+        dc.done();
+    }
+
+    #[test]
+    fn verify_reads_in_chunks() {
+        extern crate std;
+        use crate::epd2in13_v2::command::Command;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        let expected = [0xAA; 40];
+        let read = |corrupted: u8| {
+            [
+                spi::Transaction::write(vec![0x27]),
+                spi::Transaction::transfer(vec![0], vec![0xFF]),
+                spi::Transaction::transfer(vec![0; 32], vec![0xAA; 32]),
+                spi::Transaction::transfer(
+                    vec![0; 8],
+                    vec![0xAA, 0xAA, corrupted, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA],
+                ),
+            ]
+        };
+        let mut spi = spi::Mock::new(&[read(0xAA), read(0x2A)].concat());
+        // low and high again, `n` times
+        let toggles = |n| {
+            (0..n)
+                .flat_map(|_| [Transaction::set(State::Low), Transaction::set(State::High)])
+                .collect::<vec::Vec<_>>()
+        };
+        let mut cs = Mock::new(&toggles(4));
+        let mut dc = Mock::new(&toggles(2));
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(cs.clone(), Mock::new(&[]), dc.clone(), Mock::new(&[]), None);
+
+        assert_eq!(
+            interface.cmd_verify(&mut spi, Command::ReadRam, 1, &expected),
+            Ok(true)
+        );
+        assert_eq!(
+            interface.cmd_verify(&mut spi, Command::ReadRam, 1, &expected),
+            Ok(false)
+        );
+        spi.done();
+        cs.done();
+        dc.done();
+    }
+
+    #[test]
+    fn rle_data_is_expanded_in_chunks() {
+        extern crate std;
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        let encoded = [60, 0xFF, 10, 0x00, 5, 0x11];
+        let mut first_chunk = vec![0xFF; 60];
+        first_chunk.extend_from_slice(&[0x00; 4]);
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(first_chunk),
+            spi::Transaction::write(vec![0x00; 6]),
+            spi::Transaction::write(vec![0x11; 5]),
+        ]);
+        let mut cs = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]);
+        let mut dc = Mock::new(&[Transaction::set(State::High), Transaction::set(State::High)]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(cs.clone(), Mock::new(&[]), dc.clone(), Mock::new(&[]), None);
+
+        // e.g. the two planes of a three color frame
+        let mut runs = Runs::new(&encoded);
+        interface.data_rle(&mut spi, &mut runs, 70).unwrap();
+        interface.data_rle(&mut spi, &mut runs, 5).unwrap();
+
+        spi.done();
+        cs.done();
+        dc.done();
+    }
+
+    #[test]
+    fn busy_polarity() {
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+
+        let mut busy = Mock::new(&[Transaction::get(State::Low), Transaction::get(State::Low)]);
+        let interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(
+                Mock::new(&[]),
+                busy.clone(),
+                Mock::new(&[]),
+                Mock::new(&[]),
+                None,
+            );
+
+        assert!(interface.is_busy(true));
+        assert!(!interface.is_busy(false));
+        busy.done();
+    }
+
+    #[test]
+    fn on_poll_is_called_while_waiting() {
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+
+        let mut busy = Mock::new(&[
+            Transaction::get(State::High),
+            Transaction::get(State::High),
+            Transaction::get(State::Low),
+        ]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(
+                Mock::new(&[]),
+                busy.clone(),
+                Mock::new(&[]),
+                Mock::new(&[]),
+                None,
+            );
+
+        let mut polls = 0;
+        interface.poll_until_idle(&mut MockNoop::new(), false, &mut || polls += 1);
+        assert_eq!(polls, 2);
+        busy.done();
+    }
+
+    #[test]
+    fn observer_sees_the_traffic() {
+        extern crate std;
+        use crate::epd2in13_v2::command::Command;
+        use core::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+        use std::vec;
+
+        struct Counter {
+            command: AtomicU8,
+            data_len: AtomicUsize,
+            busy_us: AtomicU32,
+        }
+
+        impl CommandObserver for Counter {
+            fn on_command(&self, command: u8) {
+                self.command.store(command, Ordering::Relaxed);
+            }
+
+            fn on_data_len(&self, len: usize) {
+                self.data_len.fetch_add(len, Ordering::Relaxed);
+            }
+
+            fn on_busy_wait(&self, duration_us: u32) {
+                self.busy_us.fetch_add(duration_us, Ordering::Relaxed);
+            }
+        }
+
+        static COUNTER: Counter = Counter {
+            command: AtomicU8::new(0),
+            data_len: AtomicUsize::new(0),
+            busy_us: AtomicU32::new(0),
+        };
+
+        let mut spi = spi::Mock::new(&[
+            spi::Transaction::write(vec![0x24]),
+            spi::Transaction::write(vec![1, 2, 3]),
+        ]);
+        let mut cs = Mock::new(&[
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+            Transaction::set(State::Low),
+            Transaction::set(State::High),
+        ]);
+        let mut dc = Mock::new(&[Transaction::set(State::Low), Transaction::set(State::High)]);
+        let mut busy = Mock::new(&[
+            Transaction::get(State::High),
+            Transaction::get(State::High),
+            Transaction::get(State::Low),
+        ]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(
+                cs.clone(),
+                busy.clone(),
+                dc.clone(),
+                Mock::new(&[]),
+                Some(5),
+            );
+        interface.hooks().observer = Some(&COUNTER);
+
+        interface
+            .cmd_with_data(&mut spi, Command::WriteRam, &[1, 2, 3])
+            .unwrap();
+        interface.wait_until_idle(&mut MockNoop::new(), false);
+        assert_eq!(COUNTER.command.load(Ordering::Relaxed), 0x24);
+        assert_eq!(COUNTER.data_len.load(Ordering::Relaxed), 3);
+        assert_eq!(COUNTER.busy_us.load(Ordering::Relaxed), 10);
+
+        spi.done();
+        cs.done();
+        dc.done();
+        busy.done();
+    }
+
+    #[test]
+    fn busy_timeout() {
+        use embedded_hal_mock::{
+            delay::MockNoop,
+            pin::{Mock, State, Transaction},
+            spi,
+        };
+
+        // busy for the first poll and the three after waiting 1ms each
+        let mut busy = Mock::new(&core::array::from_fn::<_, 4, _>(|_| {
+            Transaction::get(State::High)
+        }));
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(
+                Mock::new(&[]),
+                busy.clone(),
+                Mock::new(&[]),
+                Mock::new(&[]),
+                None,
+            );
+
+        assert_eq!(
+            interface.wait_until_idle_timeout(&mut MockNoop::new(), false, 3),
+            Err(Error::BusyStuck)
+        );
+        busy.done();
+
+        let mut busy = Mock::new(&[Transaction::get(State::High), Transaction::get(State::Low)]);
+        let mut interface: DisplayInterface<spi::Mock, Mock, Mock, Mock, Mock, MockNoop> =
+            DisplayInterface::new(
+                Mock::new(&[]),
+                busy.clone(),
+                Mock::new(&[]),
+                Mock::new(&[]),
+                None,
+            );
+        assert_eq!(
+            interface.wait_until_idle_timeout(&mut MockNoop::new(), false, 3),
+            Ok(())
+        );
+        busy.done();
+    }
+}
@@ -12,7 +12,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
@@ -56,13 +56,29 @@
 //!
 //! ### SPI
 //!
-//! MISO is not connected/available. SPI_MODE_0 is used (CPHL = 0, CPOL = 0) with 8 bits per word, MSB first.
+//! MISO is not needed, only a few optional functions like reading back the RAM of the
+//! 2.13" V2 use it, with the DIN line of the display connected to it. SPI_MODE_0 is used (CPHL = 0, CPOL = 0) with 8 bits per word, MSB first.
 //!
 //! Maximum speed tested by myself was 8Mhz but more should be possible (Ben Krasnow used 18Mhz with his implemenation)
 //!
+//! The drivers only need [`Write<u8>`](embedded_hal::blocking::spi::Write) from the bus, so any
+//! peripheral implementing it can be plugged in, e.g. a QSPI peripheral used in single line mode.
+//! Drivers of controllers with a multi line input mode can send the frames over more data
+//! lines with a [`Transport`](transport::Transport).
+//!
+//! ### Delay
+//!
+//! Every driver, the interface and the helpers like [`refresh_guard`] only need
+//! [`DelayUs<u32>`](embedded_hal::blocking::delay::DelayUs) from the delay, even the waits of
+//! several hundred milliseconds are given in microseconds. A single delay of the HAL can be
+//! passed everywhere, no `DelayMs` implementation is needed.
+//!
 #![no_std]
 #![deny(missing_docs)]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
@@ -70,6 +86,49 @@ mod traits;
 
 pub mod color;
 
+pub mod error;
+
+pub mod refresh_guard;
+
+pub mod animator;
+
+pub mod frame_store;
+
+pub mod dyn_epd;
+
+pub mod controllers;
+
+pub mod generic;
+
+pub mod epd_array;
+
+pub mod transport;
+
+#[cfg(feature = "graphics")]
+pub mod tiled_display;
+
+#[cfg(any(
+    feature = "any_epd1in54",
+    feature = "any_epd1in54_v2",
+    feature = "any_epd1in54b",
+    feature = "any_epd1in54c",
+    feature = "any_epd2in13_v2",
+    feature = "any_epd2in7b",
+    feature = "any_epd2in9",
+    feature = "any_epd2in9_v2",
+    feature = "any_epd2in9bc",
+    feature = "any_epd3in7",
+    feature = "any_epd4in2",
+    feature = "any_epd5in83b_v2",
+    feature = "any_epd7in5",
+    feature = "any_epd7in5_hd",
+    feature = "any_epd7in5_v2",
+))]
+pub mod any_epd;
+
+#[cfg(all(feature = "linux", unix))]
+pub mod linux;
+
 /// Interface for the physical connection between display and the controlling device
 mod interface;
 
@@ -96,17 +155,61 @@ pub mod epd7in5b_v2;
 
 pub(crate) mod type_a;
 
+pub(crate) mod lut_table;
+
+#[cfg(test)]
+mod timing;
+
 /// Includes everything important besides the chosen Display
+///
+/// The drivers and `Display` types of the panels enabled with their `any_epd*` feature are
+/// included as well, named like the variants of `any_epd::AnyEpd` (e.g.
+/// `Epd2in9V2` and `Display2in9V2`). Their dimensions are available as `Display2in9V2::WIDTH`
+/// and `Display2in9V2::HEIGHT`.
 pub mod prelude {
-    pub use crate::color::{Color, OctColor, TriColor};
+    pub use crate::color::{
+        BwPlane, ChromaticPlane, Color, Gray2, OctColor, PlaneBackgrounds, TriColor, TriColorKind,
+    };
+    pub use crate::error::Error;
     pub use crate::traits::{
-        QuickRefresh, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+        ColorKind, CommandObserver, DisplayCapabilities, EpdHooks, EpdInit, EpdNew, EpdPower,
+        EpdRefresh, EpdUpdate, LutSource, Orientation, OtpDiagnostics, PanelHealth, QuickRefresh,
+        RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
     };
 
-    pub use crate::SPI_MODE;
+    pub use crate::{buffer_len, SPI_MODE};
 
     #[cfg(feature = "graphics")]
-    pub use crate::graphics::{Display, DisplayRotation};
+    pub use crate::graphics::{BitOrder, Display, DisplayRotation, VarDisplay};
+
+    macro_rules! panels {
+        ($($feature:literal, $module:ident, $epd:ident as $epd_name:ident, $display:ident as $display_name:ident;)*) => {
+            $(
+                #[cfg(feature = $feature)]
+                pub use crate::$module::$epd as $epd_name;
+                #[cfg(all(feature = $feature, feature = "graphics"))]
+                pub use crate::$module::$display as $display_name;
+            )*
+        };
+    }
+
+    panels! {
+        "any_epd1in54", epd1in54, Epd1in54 as Epd1in54, Display1in54 as Display1in54;
+        "any_epd1in54_v2", epd1in54_v2, Epd1in54 as Epd1in54V2, Display1in54 as Display1in54V2;
+        "any_epd1in54b", epd1in54b, Epd1in54b as Epd1in54b, Display1in54b as Display1in54b;
+        "any_epd1in54c", epd1in54c, Epd1in54c as Epd1in54c, Display1in54c as Display1in54c;
+        "any_epd2in13_v2", epd2in13_v2, Epd2in13 as Epd2in13V2, Display2in13 as Display2in13V2;
+        "any_epd2in7b", epd2in7b, Epd2in7b as Epd2in7b, Display2in7b as Display2in7b;
+        "any_epd2in9", epd2in9, Epd2in9 as Epd2in9, Display2in9 as Display2in9;
+        "any_epd2in9_v2", epd2in9_v2, Epd2in9 as Epd2in9V2, Display2in9 as Display2in9V2;
+        "any_epd2in9bc", epd2in9bc, Epd2in9bc as Epd2in9bc, Display2in9bc as Display2in9bc;
+        "any_epd3in7", epd3in7, EPD3in7 as Epd3in7, Display3in7 as Display3in7;
+        "any_epd4in2", epd4in2, Epd4in2 as Epd4in2, Display4in2 as Display4in2;
+        "any_epd5in83b_v2", epd5in83b_v2, Epd5in83 as Epd5in83bV2, Display5in83 as Display5in83bV2;
+        "any_epd7in5", epd7in5, Epd7in5 as Epd7in5, Display7in5 as Display7in5;
+        "any_epd7in5_hd", epd7in5_hd, Epd7in5 as Epd7in5Hd, Display7in5 as Display7in5Hd;
+        "any_epd7in5_v2", epd7in5_v2, Epd7in5 as Epd7in5V2, Display7in5 as Display7in5V2;
+    }
 }
 
 /// Computes the needed buffer length. Takes care of rounding up in case width
@@ -119,7 +222,79 @@ pub mod prelude {
 /// \[XXXXX210\]\[76543210\]...\[76543210\] | height
 /// \[XXXXX210\]\[76543210\]...\[76543210\] v
 pub const fn buffer_len(width: usize, height: usize) -> usize {
-    (width + 7) / 8 * height
+    row_bytes(width) * height
+}
+
+/// Number of bytes of a line of `width` pixels with one bit per pixel, including the padding
+pub const fn row_bytes(width: usize) -> usize {
+    width.div_ceil(8)
+}
+
+/// Width in pixels of a line with one bit per pixel, including the padding
+pub const fn padded_width(width: usize) -> usize {
+    row_bytes(width) * 8
+}
+
+/// Checks that `buffer` holds exactly `expected` bytes
+pub(crate) fn check_buffer_len<E>(buffer: &[u8], expected: usize) -> Result<(), error::Error<E>> {
+    if buffer.len() != expected {
+        return Err(error::Error::InvalidBufferSize {
+            expected,
+            actual: buffer.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Checks that the run length encoded `encoded` expands to exactly `expected` bytes
+pub(crate) fn check_rle_len<E>(encoded: &[u8], expected: usize) -> Result<(), error::Error<E>> {
+    let actual = lut_table::expanded_len(encoded);
+    if actual != expected {
+        return Err(error::Error::InvalidBufferSize { expected, actual });
+    }
+    Ok(())
+}
+
+/// Checks the window of a partial update on a display of `display_width` x `display_height`
+///
+/// `x` and `width` need to be multiples of 8, as the controllers address their RAM in whole
+/// bytes, and the buffer needs to hold exactly the window.
+pub(crate) fn check_partial_window<E>(
+    buffer: &[u8],
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    display_width: u32,
+    display_height: u32,
+) -> Result<(), error::Error<E>> {
+    check_window(x, y, width, height, display_width, display_height)?;
+    if buffer.len() != buffer_len(width as usize, height as usize) {
+        return Err(error::Error::InvalidRegion);
+    }
+    Ok(())
+}
+
+/// Checks a window like [`check_partial_window`], without a buffer
+pub(crate) fn check_window<E>(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    display_width: u32,
+    display_height: u32,
+) -> Result<(), error::Error<E>> {
+    let fits = |start: u32, length: u32, end: u32| {
+        length > 0 && start.checked_add(length).is_some_and(|stop| stop <= end)
+    };
+    if !x.is_multiple_of(8)
+        || !width.is_multiple_of(8)
+        || !fits(x, width, padded_width(display_width as usize) as u32)
+        || !fits(y, height, display_height)
+    {
+        return Err(error::Error::InvalidRegion);
+    }
+    Ok(())
 }
 
 use embedded_hal::spi::{Mode, Phase, Polarity};
@@ -130,3 +305,111 @@ pub const SPI_MODE: Mode = Mode {
     phase: Phase::CaptureOnFirstTransition,
     polarity: Polarity::IdleLow,
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use proptest::prelude::*;
+
+    #[test]
+    fn buffer_len_properties() {
+        for width in 0..=300 {
+            let bytes = row_bytes(width);
+            // whole bytes, with less than one byte of padding
+            assert!(bytes * 8 >= width && bytes * 8 < width + 8);
+            assert_eq!(padded_width(width), bytes * 8);
+            for height in 0..=4 {
+                assert_eq!(buffer_len(width, height), bytes * height);
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn accepted_windows_stay_in_the_buffer(
+            display_width in 1u32..130,
+            display_height in 1u32..300,
+            x in 0u32..140,
+            y in 0u32..310,
+            width in 0u32..140,
+            height in 0u32..310,
+        ) {
+            if check_window::<()>(x, y, width, height, display_width, display_height).is_ok() {
+                let row = row_bytes(display_width as usize);
+                let first = y as usize * row + x as usize / 8;
+                let last = (y + height - 1) as usize * row + (x + width - 1) as usize / 8;
+                prop_assert!(first <= last);
+                prop_assert!(last < buffer_len(display_width as usize, display_height as usize));
+                // no line of the window wraps into the next line of the display
+                prop_assert!((x + width - 1) as usize / 8 < row);
+            }
+        }
+
+        #[test]
+        fn accepted_buffers_fill_the_window(
+            x in 0u32..140,
+            y in 0u32..260,
+            width in 0u32..140,
+            height in 0u32..260,
+            len in 0usize..600,
+        ) {
+            let buffer = [0u8; 600];
+            if check_partial_window::<()>(&buffer[..len], x, y, width, height, 122, 250).is_ok() {
+                prop_assert_eq!(len * 8, width as usize * height as usize);
+            }
+        }
+
+        #[test]
+        fn whole_display_is_accepted(display_width in 1u32..130, display_height in 1u32..300) {
+            let width = padded_width(display_width as usize) as u32;
+            prop_assert_eq!(
+                check_window::<()>(0, 0, width, display_height, display_width, display_height),
+                Ok(())
+            );
+        }
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn display_dimensions() {
+        use crate::epd4in2::Display4in2;
+        assert_eq!(
+            (Display4in2::WIDTH, Display4in2::HEIGHT),
+            (crate::epd4in2::WIDTH, crate::epd4in2::HEIGHT)
+        );
+    }
+
+    #[test]
+    fn buffer_size() {
+        assert_eq!(check_buffer_len::<()>(&[0; 4], 4), Ok(()));
+        assert_eq!(
+            check_buffer_len::<()>(&[0; 3], 4),
+            Err(Error::InvalidBufferSize {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn partial_window() {
+        let check = |len, x, y, width, height| {
+            check_partial_window::<()>(&[0; 64][..len], x, y, width, height, 122, 250)
+        };
+        assert_eq!(check(4, 8, 0, 16, 2), Ok(()));
+        // the padding of the last byte of a line can be written
+        assert_eq!(check(4, 112, 248, 16, 2), Ok(()));
+
+        assert_eq!(check(4, 4, 0, 16, 2), Err(Error::InvalidRegion));
+        assert_eq!(check(3, 0, 0, 12, 2), Err(Error::InvalidRegion));
+        assert_eq!(check(4, 120, 0, 16, 2), Err(Error::InvalidRegion));
+        assert_eq!(check(4, 0, 249, 16, 2), Err(Error::InvalidRegion));
+        assert_eq!(check(3, 0, 0, 16, 2), Err(Error::InvalidRegion));
+        assert_eq!(check(0, 0, 0, 0, 0), Err(Error::InvalidRegion));
+        assert_eq!(
+            check_partial_window::<()>(&[], u32::MAX - 7, 0, 8, 0, 122, 250),
+            Err(Error::InvalidRegion)
+        );
+    }
+}
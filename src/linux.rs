@@ -0,0 +1,184 @@
+//! Convenience constructors for the Waveshare e-Paper HATs on a Raspberry Pi
+//!
+//! Opens the spidev device and the GPIO lines at the pin numbers used by the HATs
+//! and returns an initialised driver together with the bus and the delay.
+//!
+//! Needs spi to be activated (e.g. in raspi-config) and access to the sysfs gpio interface.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!use epd_waveshare::{epd2in13_v2::*, linux::*, prelude::*};
+//!
+//!# fn main() -> Result<(), HatError> {
+//!let mut hat: Hat<Epd2in13<_, _, _, _, _, _>> = Hat::open_default()?;
+//!
+//!let display = Display2in13::default();
+//!hat.epd
+//!    .update_and_display_frame(&mut hat.spi, display.buffer(), &mut hat.delay)?;
+//!hat.epd.sleep(&mut hat.spi, &mut hat.delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use std::{thread, time::Duration};
+
+use linux_embedded_hal::{
+    spidev::{self, SpidevOptions},
+    sysfs_gpio::{self, Direction},
+    Delay, Pin, Spidev,
+};
+
+use crate::error::Error;
+use crate::traits::WaveshareDisplay;
+
+/// Spidev, pin numbers (BCM) and bus speed used to talk to the HAT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HatConfig<'a> {
+    /// Path of the spidev device
+    pub spidev: &'a str,
+    /// Maximum spi clock speed
+    pub max_speed_hz: u32,
+    /// Chip select pin, driven by the driver
+    ///
+    /// Must not be the CE0 pin (GPIO 8) of the spidev device, the kernel driver owns it.
+    pub cs: u64,
+    /// Busy pin
+    pub busy: u64,
+    /// Data/Command pin
+    pub dc: u64,
+    /// Reset pin
+    pub rst: u64,
+}
+
+impl Default for HatConfig<'_> {
+    /// The pinout of the Waveshare e-Paper HATs
+    fn default() -> Self {
+        HatConfig {
+            spidev: "/dev/spidev0.0",
+            max_speed_hz: 4_000_000,
+            cs: 26,
+            busy: 24,
+            dc: 25,
+            rst: 17,
+        }
+    }
+}
+
+/// Errors while opening or talking to the HAT
+#[derive(Debug)]
+pub enum HatError {
+    /// Error of the spidev device
+    Spi(std::io::Error),
+    /// Error of the sysfs gpio interface
+    Gpio(sysfs_gpio::Error),
+    /// Error of the display which isn't a spi error
+    Epd(Error<std::io::Error>),
+    /// The pin didn't show up in sysfs after being exported
+    ExportTimeout(u64),
+}
+
+impl core::fmt::Display for HatError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HatError::Spi(e) => write!(f, "Spi error: {}", e),
+            HatError::Gpio(e) => write!(f, "Gpio error: {}", e),
+            HatError::Epd(e) => write!(f, "Display error: {}", e),
+            HatError::ExportTimeout(pin) => write!(f, "Gpio {} wasn't exported in time", pin),
+        }
+    }
+}
+
+impl std::error::Error for HatError {}
+
+impl From<std::io::Error> for HatError {
+    fn from(e: std::io::Error) -> Self {
+        HatError::Spi(e)
+    }
+}
+
+impl From<Error<std::io::Error>> for HatError {
+    fn from(e: Error<std::io::Error>) -> Self {
+        match e {
+            Error::Spi(e) => HatError::Spi(e),
+            e => HatError::Epd(e),
+        }
+    }
+}
+
+impl From<sysfs_gpio::Error> for HatError {
+    fn from(e: sysfs_gpio::Error) -> Self {
+        HatError::Gpio(e)
+    }
+}
+
+/// An initialised driver together with the bus and delay it needs
+pub struct Hat<EPD> {
+    /// The display driver
+    pub epd: EPD,
+    /// The opened spidev device
+    pub spi: Spidev,
+    /// The delay used by the driver
+    pub delay: Delay,
+}
+
+impl<EPD> Hat<EPD>
+where
+    EPD: WaveshareDisplay<Spidev, Pin, Pin, Pin, Pin, Delay>,
+{
+    /// Opens the HAT with the default Waveshare pinout
+    pub fn open_default() -> Result<Self, HatError> {
+        Self::open(&HatConfig::default())
+    }
+
+    /// Opens the spidev device and the pins of `config` and initialises the display
+    pub fn open(config: &HatConfig) -> Result<Self, HatError> {
+        let mut spi = Spidev::open(config.spidev)?;
+        let options = SpidevOptions::new()
+            .bits_per_word(8)
+            .max_speed_hz(config.max_speed_hz)
+            .mode(spidev::SpiModeFlags::SPI_MODE_0)
+            .build();
+        spi.configure(&options)?;
+
+        let cs = output_pin(config.cs)?;
+        let busy = input_pin(config.busy)?;
+        let dc = output_pin(config.dc)?;
+        let rst = output_pin(config.rst)?;
+
+        let mut delay = Delay {};
+        let epd = EPD::new(&mut spi, cs, busy, dc, rst, &mut delay, None)?;
+
+        Ok(Hat { epd, spi, delay })
+    }
+}
+
+// How often and how long to wait for udev to set up an exported pin
+const EXPORT_RETRIES: u32 = 100;
+const EXPORT_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+fn export(number: u64) -> Result<Pin, HatError> {
+    let pin = Pin::new(number);
+    pin.export()?;
+    for _ in 0..EXPORT_RETRIES {
+        if pin.is_exported() {
+            return Ok(pin);
+        }
+        thread::sleep(EXPORT_RETRY_INTERVAL);
+    }
+    Err(HatError::ExportTimeout(number))
+}
+
+fn output_pin(pin: u64) -> Result<Pin, HatError> {
+    let pin = export(pin)?;
+    pin.set_direction(Direction::Out)?;
+    pin.set_value(1)?;
+    Ok(pin)
+}
+
+fn input_pin(pin: u64) -> Result<Pin, HatError> {
+    let pin = export(pin)?;
+    pin.set_direction(Direction::In)?;
+    Ok(pin)
+}
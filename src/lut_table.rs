@@ -0,0 +1,188 @@
+//! Constant tables, e.g. waveforms, which can be stored run length encoded
+//!
+//! With the `compressed_luts` feature the tables declared with [`lut_table!`] are compressed
+//! at compile time and only the compressed bytes end up in flash. [`Table::get`] expands them
+//! into a buffer on the stack when they are sent to the controller. Without the feature the
+//! tables are stored as they are and `get` only copies them.
+//!
+//! The compressed format is a sequence of `(count, byte)` pairs, with `count` from 1 to 255.
+//! Frames compressed with the `encode_rle` helper of the graphics module use it as well and
+//! are expanded with [`Runs`] while they are sent.
+
+/// A constant table of `N` bytes
+pub(crate) enum Table<const N: usize> {
+    /// Stored as it is
+    #[cfg_attr(feature = "compressed_luts", allow(dead_code))]
+    Raw(&'static [u8; N]),
+    /// Stored run length encoded
+    #[cfg_attr(not(feature = "compressed_luts"), allow(dead_code))]
+    Rle(&'static [u8]),
+}
+
+impl<const N: usize> Table<N> {
+    /// Returns the table, expanding it if it is compressed
+    pub(crate) fn get(&self) -> [u8; N] {
+        match self {
+            Table::Raw(table) => **table,
+            Table::Rle(compressed) => expand(compressed),
+        }
+    }
+}
+
+/// Declares a [`Table`], which is compressed with the `compressed_luts` feature
+macro_rules! lut_table {
+    ($(#[$attr:meta])* $vis:vis $name:ident: [u8; $len:expr] = $table:expr;) => {
+        $(#[$attr])*
+        $vis const $name: crate::lut_table::Table<$len> = {
+            const TABLE: [u8; $len] = $table;
+            #[cfg(not(feature = "compressed_luts"))]
+            let table = crate::lut_table::Table::Raw(&TABLE);
+            #[cfg(feature = "compressed_luts")]
+            let table = {
+                const COMPRESSED: [u8; crate::lut_table::compressed_len(&TABLE)] =
+                    crate::lut_table::compress(&TABLE);
+                crate::lut_table::Table::Rle(&COMPRESSED)
+            };
+            table
+        };
+    };
+}
+pub(crate) use lut_table;
+
+/// Length of the longest run of equal bytes at the start of `table[start..]`
+#[cfg(any(test, feature = "compressed_luts", feature = "graphics"))]
+pub(crate) const fn run_len(table: &[u8], start: usize) -> usize {
+    let mut end = start + 1;
+    while end < table.len() && end - start < 255 && table[end] == table[start] {
+        end += 1;
+    }
+    end - start
+}
+
+/// Number of bytes of the compressed `table`
+#[cfg(any(test, feature = "compressed_luts", feature = "graphics"))]
+pub(crate) const fn compressed_len(table: &[u8]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < table.len() {
+        i += run_len(table, i);
+        len += 2;
+    }
+    len
+}
+
+/// Compresses `table` into the `N = compressed_len(table)` bytes
+#[cfg(any(test, feature = "compressed_luts"))]
+pub(crate) const fn compress<const N: usize>(table: &[u8]) -> [u8; N] {
+    let mut compressed = [0; N];
+    let mut i = 0;
+    let mut j = 0;
+    while i < table.len() {
+        let run = run_len(table, i);
+        compressed[j] = run as u8;
+        compressed[j + 1] = table[i];
+        i += run;
+        j += 2;
+    }
+    compressed
+}
+
+/// Expands the compressed table, bytes exceeding `N` are ignored
+pub(crate) fn expand<const N: usize>(compressed: &[u8]) -> [u8; N] {
+    let mut table = [0; N];
+    let mut i = 0;
+    for run in compressed.chunks_exact(2) {
+        let end = N.min(i + run[0] as usize);
+        table[i..end].fill(run[1]);
+        i = end;
+    }
+    table
+}
+
+/// Number of bytes of the expanded table, a trailing byte without count is ignored
+pub(crate) fn expanded_len(compressed: &[u8]) -> usize {
+    compressed
+        .chunks_exact(2)
+        .map(|run| usize::from(run[0]))
+        .sum()
+}
+
+/// Hands out the bytes of a compressed table run by run
+pub(crate) struct Runs<'a> {
+    compressed: &'a [u8],
+    /// Bytes left of the current run, and their value
+    left: usize,
+    byte: u8,
+}
+
+impl<'a> Runs<'a> {
+    pub(crate) fn new(compressed: &'a [u8]) -> Self {
+        Runs {
+            compressed,
+            left: 0,
+            byte: 0,
+        }
+    }
+
+    /// Takes up to `max` bytes of the current run, returns their count and value
+    ///
+    /// Returns `None` at the end of the table.
+    pub(crate) fn take(&mut self, max: usize) -> Option<(usize, u8)> {
+        while self.left == 0 {
+            let [count, byte, rest @ ..] = self.compressed else {
+                return None;
+            };
+            self.left = usize::from(*count);
+            self.byte = *byte;
+            self.compressed = rest;
+        }
+        let len = self.left.min(max);
+        self.left -= len;
+        Some((len, self.byte))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut table = [0u8; 600];
+        table[..3].copy_from_slice(&[0x2A, 0x05, 0x05]);
+        table[590] = 0x22;
+        const LEN: usize = 2 * 7;
+        assert_eq!(compressed_len(&table), LEN);
+        let compressed = compress::<LEN>(&table);
+        // runs longer than 255 bytes are split
+        assert_eq!(
+            compressed,
+            [1, 0x2A, 2, 0x05, 255, 0, 255, 0, 77, 0, 1, 0x22, 9, 0]
+        );
+        assert_eq!(expand::<600>(&compressed), table);
+        assert_eq!(expand::<4>(&compressed), [0x2A, 0x05, 0x05, 0]);
+    }
+
+    #[test]
+    fn runs() {
+        let compressed = [3, 0xAA, 0, 0x11, 1, 0xBB, 7];
+        assert_eq!(expanded_len(&compressed), 4);
+        let mut runs = Runs::new(&compressed);
+        assert_eq!(runs.take(2), Some((2, 0xAA)));
+        assert_eq!(runs.take(2), Some((1, 0xAA)));
+        // empty runs and the trailing byte are skipped
+        assert_eq!(runs.take(2), Some((1, 0xBB)));
+        assert_eq!(runs.take(2), None);
+    }
+
+    lut_table! {
+        TABLE: [u8; 6] = [1, 1, 1, 1, 0, 2];
+    }
+
+    #[test]
+    fn declared_table() {
+        assert_eq!(TABLE.get(), [1, 1, 1, 1, 0, 2]);
+        #[cfg(feature = "compressed_luts")]
+        assert!(matches!(TABLE, Table::Rle(compressed) if compressed.len() == 6));
+    }
+}
@@ -0,0 +1,224 @@
+//! Opt-in enforcement of a minimum interval between two refreshes
+//!
+//! Waveshare specifies a minimum interval between two full refreshes for most panels,
+//! often 180s for the tri-color ones. Refreshing them more often can damage the panel.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use epd_waveshare::{epd2in13bc::*, prelude::*, refresh_guard::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# struct Rtc;
+//!# impl Clock for Rtc { fn now_ms(&mut self) -> u64 { 0 } }
+//!# let rtc = Rtc;
+//!
+//!let mut epd = Epd2in13bc::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+//!let mut guard = RefreshGuard::new(rtc, 180_000, IntervalPolicy::Error);
+//!
+//!// Fails with `Error::TooSoon` if the last refresh was less than 180s ago
+//!guard.display_frame(&mut epd, &mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::error::Error;
+use crate::traits::{EpdRefresh, EpdUpdate};
+
+/// Monotonic clock provided by the user
+pub trait Clock {
+    /// Returns the current time in milliseconds
+    fn now_ms(&mut self) -> u64;
+}
+
+/// What to do when a refresh is requested before the minimum interval elapsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalPolicy {
+    /// Block with the delay until the refresh is allowed
+    Delay,
+    /// Return [`Error::TooSoon`]
+    Error,
+}
+
+/// Tracks the time of the last refresh and enforces a minimum interval between refreshes
+pub struct RefreshGuard<CLOCK> {
+    clock: CLOCK,
+    min_interval_ms: u64,
+    policy: IntervalPolicy,
+    last_refresh_ms: Option<u64>,
+}
+
+impl<CLOCK: Clock> RefreshGuard<CLOCK> {
+    /// Creates a new guard. The first refresh is always allowed.
+    pub fn new(clock: CLOCK, min_interval_ms: u64, policy: IntervalPolicy) -> Self {
+        RefreshGuard {
+            clock,
+            min_interval_ms,
+            policy,
+            last_refresh_ms: None,
+        }
+    }
+
+    /// Milliseconds until the next refresh is allowed, 0 if it is allowed right now
+    pub fn remaining_ms(&mut self) -> u64 {
+        match self.last_refresh_ms {
+            Some(last) => {
+                let elapsed = self.clock.now_ms().saturating_sub(last);
+                self.min_interval_ms.saturating_sub(elapsed)
+            }
+            None => 0,
+        }
+    }
+
+    /// Waits or fails according to the policy if a refresh isn't allowed yet
+    pub fn check<DELAY: DelayUs<u32>, E>(&mut self, delay: &mut DELAY) -> Result<(), Error<E>> {
+        let remaining_ms = self.remaining_ms();
+        if remaining_ms == 0 {
+            return Ok(());
+        }
+
+        match self.policy {
+            IntervalPolicy::Error => Err(Error::TooSoon { remaining_ms }),
+            IntervalPolicy::Delay => {
+                // Delay in chunks of 1s to not overflow the u32 microseconds
+                let mut remaining_ms = remaining_ms;
+                while remaining_ms > 0 {
+                    let chunk = remaining_ms.min(1_000);
+                    delay.delay_us(chunk as u32 * 1_000);
+                    remaining_ms -= chunk;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Records that a refresh happened right now
+    ///
+    /// Only needed when refreshing the display without going through the guard.
+    pub fn mark_refreshed(&mut self) {
+        self.last_refresh_ms = Some(self.clock.now_ms());
+    }
+
+    /// Forgets about the last refresh, so the next one is allowed immediately
+    pub fn reset(&mut self) {
+        self.last_refresh_ms = None;
+    }
+
+    /// Same as [`EpdRefresh::display_frame`],
+    /// but enforcing the minimum interval
+    pub fn display_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        self.check(delay)?;
+        epd.display_frame(spi, delay)?;
+        self.mark_refreshed();
+        Ok(())
+    }
+
+    /// Same as [`EpdRefresh::update_and_display_frame`],
+    /// but enforcing the minimum interval. The frame is only transmitted if the refresh is allowed.
+    pub fn update_and_display_frame<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        self.check(delay)?;
+        epd.update_and_display_frame(spi, buffer, delay)?;
+        self.mark_refreshed();
+        Ok(())
+    }
+
+    /// Returns the clock
+    pub fn release(self) -> CLOCK {
+        self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use embedded_hal_mock::delay::MockNoop;
+
+    struct TestClock<'a>(&'a Cell<u64>);
+
+    impl Clock for TestClock<'_> {
+        fn now_ms(&mut self) -> u64 {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn too_soon() {
+        let now = Cell::new(1_000);
+        let mut guard = RefreshGuard::new(TestClock(&now), 180_000, IntervalPolicy::Error);
+        let mut delay = MockNoop::new();
+
+        assert_eq!(guard.check::<_, ()>(&mut delay), Ok(()));
+        guard.mark_refreshed();
+
+        now.set(61_000);
+        assert_eq!(guard.remaining_ms(), 120_000);
+        assert_eq!(
+            guard.check::<_, ()>(&mut delay),
+            Err(Error::TooSoon {
+                remaining_ms: 120_000
+            })
+        );
+
+        now.set(181_000);
+        assert_eq!(guard.check::<_, ()>(&mut delay), Ok(()));
+    }
+
+    #[test]
+    fn delay_policy() {
+        let now = Cell::new(0);
+        let mut guard = RefreshGuard::new(TestClock(&now), 180_000, IntervalPolicy::Delay);
+        let mut delay = MockNoop::new();
+
+        guard.mark_refreshed();
+        assert_eq!(guard.check::<_, ()>(&mut delay), Ok(()));
+
+        guard.reset();
+        assert_eq!(guard.remaining_ms(), 0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Several panels arranged in a grid, drawn on as a single display
+//!
+//! [`TiledDisplay`] implements [`DrawTarget`] for the whole grid and splits every drawing
+//! operation onto the buffers of the panels. The buffers are then sent with an
+//! [`EpdArray`], which refreshes all panels at once.
+//!
+//! All tiles need to have the same size, the tiles are given row by row starting at the
+//! top left.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use embedded_graphics::{prelude::*, primitives::{Line, PrimitiveStyle}};
+//!use epd_waveshare::{epd7in5_v2::*, epd_array::EpdArray, prelude::*, tiled_display::TiledDisplay};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let pin = || pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let left = Epd7in5::new(&mut spi, pin(), pin(), pin(), pin(), &mut delay, None)?;
+//!let right = Epd7in5::new(&mut spi, pin(), pin(), pin(), pin(), &mut delay, None)?;
+//!let mut panels = EpdArray::new([left, right]);
+//!
+//!// Two panels side by side, 1600x480 pixels in total
+//!let mut display = TiledDisplay::new([Display7in5::default(), Display7in5::default()], 2).unwrap();
+//!let _ = Line::new(Point::new(0, 240), Point::new(1599, 240))
+//!    .into_styled(PrimitiveStyle::with_stroke(Color::Black, 1))
+//!    .draw(&mut display);
+//!
+//!display.update_and_display_frames(&mut panels, &mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_graphics_core::{prelude::*, primitives::Rectangle};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::epd_array::EpdArray;
+use crate::error::Error;
+use crate::traits::{EpdRefresh, EpdUpdate};
+
+/// `N` displays of the same size arranged in a grid
+pub struct TiledDisplay<D, const N: usize> {
+    tiles: [D; N],
+    columns: usize,
+}
+
+impl<D: OriginDimensions, const N: usize> TiledDisplay<D, N> {
+    /// Arranges the tiles row by row in a grid with `columns` tiles per row
+    ///
+    /// Returns `None` if `N` isn't a multiple of `columns`, as the tiles wouldn't fill all rows.
+    pub fn new(tiles: [D; N], columns: usize) -> Option<Self> {
+        if columns == 0 || !N.is_multiple_of(columns) {
+            return None;
+        }
+        Some(TiledDisplay { tiles, columns })
+    }
+
+    /// Number of tiles per row
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Number of rows of tiles
+    pub fn rows(&self) -> usize {
+        N / self.columns
+    }
+
+    /// Returns the tiles, e.g. to set their rotation
+    pub fn tiles(&mut self) -> &mut [D; N] {
+        &mut self.tiles
+    }
+
+    /// Returns the tiles
+    pub fn release(self) -> [D; N] {
+        self.tiles
+    }
+
+    fn tile_size(&self) -> Size {
+        self.tiles.first().map_or(Size::zero(), |tile| tile.size())
+    }
+
+    /// Position of the top left corner of tile `index` in the grid
+    fn tile_offset(&self, index: usize) -> Point {
+        let size = self.tile_size();
+        Point::new(
+            (index % self.columns) as i32 * size.width as i32,
+            (index / self.columns) as i32 * size.height as i32,
+        )
+    }
+
+    /// The tile containing `point` and the position of it on the tile
+    fn locate(&self, point: Point) -> Option<(usize, Point)> {
+        let size = self.tile_size();
+        if point.x < 0 || point.y < 0 || size.width == 0 || size.height == 0 {
+            return None;
+        }
+        let column = point.x as usize / size.width as usize;
+        let row = point.y as usize / size.height as usize;
+        if column >= self.columns || row >= self.rows() {
+            return None;
+        }
+        let index = row * self.columns + column;
+        Some((index, point - self.tile_offset(index)))
+    }
+}
+
+impl<D: AsRef<[u8]>, const N: usize> TiledDisplay<D, N> {
+    /// The buffers of the tiles, in the order of the panels
+    pub fn buffers(&self) -> [&[u8]; N] {
+        self.tiles.each_ref().map(|tile| tile.as_ref())
+    }
+
+    /// Transmits the buffer of every tile to its panel
+    pub fn update_frames<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &self,
+        epds: &mut EpdArray<EPD, N>,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        epds.update_frames(spi, self.buffers(), delay)
+    }
+
+    /// Transmits the buffer of every tile to its panel and refreshes all panels, see
+    /// [`EpdArray::update_and_display_frames`]
+    pub fn update_and_display_frames<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+        &self,
+        epds: &mut EpdArray<EPD, N>,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        EPD: EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY> + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayUs<u32>,
+    {
+        epds.update_and_display_frames(spi, self.buffers(), delay)
+    }
+}
+
+/// For use with embedded_grahics
+impl<D: DrawTarget + OriginDimensions, const N: usize> DrawTarget for TiledDisplay<D, N> {
+    type Color = D::Color;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            if let Some((index, point)) = self.locate(point) {
+                self.tiles[index].draw_iter(core::iter::once(Pixel(point, color)))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let size = self.tile_size();
+        for index in 0..N {
+            let offset = self.tile_offset(index);
+            let part = area.intersection(&Rectangle::new(offset, size));
+            if !part.is_zero_sized() {
+                self.tiles[index]
+                    .fill_solid(&Rectangle::new(part.top_left - offset, part.size), color)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for tile in self.tiles.iter_mut() {
+            tile.clear(color)?;
+        }
+        Ok(())
+    }
+}
+
+/// For use with embedded_grahics
+impl<D: OriginDimensions, const N: usize> OriginDimensions for TiledDisplay<D, N> {
+    fn size(&self) -> Size {
+        let size = self.tile_size();
+        Size::new(
+            size.width * self.columns as u32,
+            size.height * self.rows() as u32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::graphics::Display;
+
+    type Tile = Display<16, 8, false, { crate::buffer_len(16, 8) }, Color>;
+
+    #[test]
+    fn tiles_fill_all_rows() {
+        let tiles = || [Tile::default(), Tile::default(), Tile::default()];
+        assert!(TiledDisplay::new(tiles(), 2).is_none());
+        assert!(TiledDisplay::new(tiles(), 0).is_none());
+        assert_eq!(TiledDisplay::new(tiles(), 1).unwrap().rows(), 3);
+        assert_eq!(TiledDisplay::new(tiles(), 3).unwrap().rows(), 1);
+    }
+
+    #[test]
+    fn tiled_display_splits_drawing() {
+        // 2x2 tiles, 32x16 pixels
+        let mut display = TiledDisplay::new(
+            [
+                Tile::default(),
+                Tile::default(),
+                Tile::default(),
+                Tile::default(),
+            ],
+            2,
+        )
+        .unwrap();
+        assert_eq!(display.size(), Size::new(32, 16));
+        display.clear(Color::White).unwrap();
+
+        Pixel(Point::new(20, 3), Color::Black)
+            .draw(&mut display)
+            .unwrap();
+        Pixel(Point::new(32, 3), Color::Black)
+            .draw(&mut display)
+            .unwrap();
+        let tiles = display.tiles();
+        assert_eq!(tiles[1].get_pixel(Point::new(4, 3)), Some(Color::Black));
+        assert_eq!(tiles[0].get_pixel(Point::new(4, 3)), Some(Color::White));
+
+        // a square on the corners of all four tiles
+        display
+            .fill_solid(
+                &Rectangle::new(Point::new(15, 7), Size::new(2, 2)),
+                Color::Black,
+            )
+            .unwrap();
+        let tiles = display.tiles();
+        assert_eq!(tiles[0].get_pixel(Point::new(15, 7)), Some(Color::Black));
+        assert_eq!(tiles[1].get_pixel(Point::new(0, 7)), Some(Color::Black));
+        assert_eq!(tiles[2].get_pixel(Point::new(15, 0)), Some(Color::Black));
+        assert_eq!(tiles[3].get_pixel(Point::new(0, 0)), Some(Color::Black));
+        assert_eq!(tiles[3].get_pixel(Point::new(1, 1)), Some(Color::White));
+
+        let buffers = display.buffers();
+        assert_eq!(buffers[3][0], 0x7F);
+        assert!(buffers[3][1..].iter().all(|&byte| byte == 0xFF));
+    }
+}
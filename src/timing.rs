@@ -0,0 +1,283 @@
+//! Checks the timings of all drivers with a recording delay
+//!
+//! The pins, the bus and the delay of a driver share one timeline, so the time between two
+//! events is the sum of the delays recorded in between. The BUSY pin is always idle, which
+//! leaves only the fixed waits of the drivers.
+
+extern crate std;
+
+use crate::traits::EpdNew;
+use core::cell::{Cell, RefCell};
+use core::convert::Infallible;
+use embedded_hal::{
+    blocking::{delay::DelayUs, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+use std::{rc::Rc, vec::Vec};
+
+/// The Waveshare reference drivers hold the reset line low for at least 2ms
+const MIN_RESET_PULSE_US: u32 = 2_000;
+/// and give the controller at least 10ms after the reset before sending the first command
+const MIN_AFTER_RESET_US: u32 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Event {
+    Reset(bool),
+    Command(u8),
+    Delay(u32),
+}
+
+#[derive(Default)]
+struct Timeline {
+    events: RefCell<Vec<Event>>,
+    data: Cell<bool>,
+}
+
+impl Timeline {
+    fn push(&self, event: Event) {
+        self.events.borrow_mut().push(event);
+    }
+}
+
+struct Spi(Rc<Timeline>);
+
+impl Write<u8> for Spi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+        if !self.0.data.get() {
+            for &word in words {
+                self.0.push(Event::Command(word));
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Delay(Rc<Timeline>);
+
+impl DelayUs<u32> for Delay {
+    fn delay_us(&mut self, us: u32) {
+        self.0.push(Event::Delay(us));
+    }
+}
+
+struct Rst(Rc<Timeline>);
+
+impl OutputPin for Rst {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.push(Event::Reset(false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.push(Event::Reset(true));
+        Ok(())
+    }
+}
+
+struct Dc(Rc<Timeline>);
+
+impl OutputPin for Dc {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        self.0.data.set(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        self.0.data.set(true);
+        Ok(())
+    }
+}
+
+struct Cs;
+
+impl OutputPin for Cs {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        Ok(())
+    }
+}
+
+/// Always reads the idle level
+struct Busy {
+    is_busy_low: bool,
+}
+
+impl InputPin for Busy {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(self.is_busy_low)
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        Ok(!self.is_busy_low)
+    }
+}
+
+/// Records the creation, and with it the initialisation, of a driver
+fn record<EPD>(is_busy_low: bool) -> Vec<Event>
+where
+    EPD: EpdNew<Spi, Cs, Busy, Dc, Rst, Delay>,
+{
+    let timeline = Rc::new(Timeline::default());
+    let mut spi = Spi(timeline.clone());
+    let mut delay = Delay(timeline.clone());
+    EPD::new(
+        &mut spi,
+        Cs,
+        Busy { is_busy_low },
+        Dc(timeline.clone()),
+        Rst(timeline.clone()),
+        &mut delay,
+        None,
+    )
+    .unwrap();
+    timeline.events.take()
+}
+
+/// Time passing between the events at `start` and the first one after it matching `end`
+fn time_until(events: &[Event], start: usize, end: impl Fn(&Event) -> bool) -> u32 {
+    events[start + 1..]
+        .iter()
+        .take_while(|event| !end(event))
+        .map(|event| match event {
+            Event::Delay(us) => *us,
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Checks every reset pulse and the wait after it
+fn check_resets(name: &str, events: &[Event]) {
+    let mut resets = 0;
+    for (i, event) in events.iter().enumerate() {
+        if *event != Event::Reset(false) {
+            continue;
+        }
+        resets += 1;
+        let pulse = time_until(events, i, |event| *event == Event::Reset(true));
+        assert!(
+            pulse >= MIN_RESET_PULSE_US,
+            "{}: reset pulse of {}us",
+            name,
+            pulse
+        );
+
+        let release = i + events[i..]
+            .iter()
+            .position(|event| *event == Event::Reset(true))
+            .unwrap();
+        let wait = time_until(events, release, |event| matches!(event, Event::Command(_)));
+        assert!(
+            wait >= MIN_AFTER_RESET_US,
+            "{}: first command {}us after the reset",
+            name,
+            wait
+        );
+    }
+    assert!(resets > 0, "{}: no reset", name);
+}
+
+/// Checks that at least `min_us` pass between `command` and the next command
+fn check_wait_after(name: &str, events: &[Event], command: u8, min_us: u32) {
+    let i = events
+        .iter()
+        .position(|event| *event == Event::Command(command))
+        .unwrap_or_else(|| panic!("{}: command {:#04x} not sent", name, command));
+    let wait = time_until(events, i, |event| matches!(event, Event::Command(_)));
+    assert!(
+        wait >= min_us,
+        "{}: only {}us after command {:#04x}",
+        name,
+        wait,
+        command
+    );
+}
+
+#[test]
+fn reset_timing() {
+    macro_rules! check {
+        ($name:literal, $epd:ty, $is_busy_low:expr) => {
+            check_resets($name, &record::<$epd>($is_busy_low))
+        };
+    }
+
+    check!("epd1in54", crate::epd1in54::Epd1in54<_, _, _, _, _, _>, false);
+    check!("epd1in54_v2", crate::epd1in54_v2::Epd1in54<_, _, _, _, _, _>, false);
+    check!("epd1in54b", crate::epd1in54b::Epd1in54b<_, _, _, _, _, _>, true);
+    check!("epd1in54c", crate::epd1in54c::Epd1in54c<_, _, _, _, _, _>, true);
+    check!("epd2in13_v2", crate::epd2in13_v2::Epd2in13<_, _, _, _, _, _>, false);
+    check!("epd2in13b_v4", crate::epd2in13b_v4::Epd2in13b<_, _, _, _, _, _>, false);
+    check!("epd2in13bc", crate::epd2in13bc::Epd2in13bc<_, _, _, _, _, _>, true);
+    check!("epd2in7b", crate::epd2in7b::Epd2in7b<_, _, _, _, _, _>, true);
+    check!("epd2in9", crate::epd2in9::Epd2in9<_, _, _, _, _, _>, false);
+    check!("epd2in9_v2", crate::epd2in9_v2::Epd2in9<_, _, _, _, _, _>, false);
+    check!("epd2in9bc", crate::epd2in9bc::Epd2in9bc<_, _, _, _, _, _>, true);
+    check!("epd3in7", crate::epd3in7::EPD3in7<_, _, _, _, _, _>, false);
+    check!("epd4in2", crate::epd4in2::Epd4in2<_, _, _, _, _, _>, true);
+    check!("epd5in65f", crate::epd5in65f::Epd5in65f<_, _, _, _, _, _>, true);
+    check!("epd5in83b_v2", crate::epd5in83b_v2::Epd5in83<_, _, _, _, _, _>, true);
+    check!("epd7in5", crate::epd7in5::Epd7in5<_, _, _, _, _, _>, true);
+    check!("epd7in5_hd", crate::epd7in5_hd::Epd7in5<_, _, _, _, _, _>, false);
+    check!("epd7in5_v2", crate::epd7in5_v2::Epd7in5<_, _, _, _, _, _>, true);
+    check!("epd7in5_v3", crate::epd7in5_v3::Epd7in5<_, _, _, _, _, _>, true);
+    check!("epd7in5b_v2", crate::epd7in5b_v2::Epd7in5<_, _, _, _, _, _>, true);
+}
+
+#[test]
+fn command_timing() {
+    // PowerOn of the UC81xx controllers, the drivers wait before polling BUSY
+    const POWER_ON: u8 = 0x04;
+    for (name, events) in [
+        (
+            "epd1in54b",
+            record::<crate::epd1in54b::Epd1in54b<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd1in54c",
+            record::<crate::epd1in54c::Epd1in54c<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd2in13bc",
+            record::<crate::epd2in13bc::Epd2in13bc<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd2in7b",
+            record::<crate::epd2in7b::Epd2in7b<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd2in9bc",
+            record::<crate::epd2in9bc::Epd2in9bc<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd4in2",
+            record::<crate::epd4in2::Epd4in2<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd5in83b_v2",
+            record::<crate::epd5in83b_v2::Epd5in83<_, _, _, _, _, _>>(true),
+        ),
+        (
+            "epd7in5",
+            record::<crate::epd7in5::Epd7in5<_, _, _, _, _, _>>(true),
+        ),
+    ] {
+        check_wait_after(name, &events, POWER_ON, 5_000);
+    }
+
+    // like the reference driver, wait for the software reset to finish
+    const SW_RESET: u8 = 0x12;
+    let events = record::<crate::epd3in7::EPD3in7<_, _, _, _, _, _>>(false);
+    check_wait_after("epd3in7", &events, SW_RESET, 300_000);
+}
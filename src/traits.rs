@@ -4,6 +4,9 @@ use embedded_hal::{
     digital::v2::*,
 };
 
+use crate::color::{BwPlane, ChromaticPlane, PlaneBackgrounds, TriColorKind};
+use crate::error::Error;
+
 /// All commands need to have this trait which gives the address of the command
 /// which needs to be send via SPI with activated CommandsPin (Data/Command Pin in CommandMode)
 pub(crate) trait Command: Copy {
@@ -11,41 +14,206 @@ pub(crate) trait Command: Copy {
 }
 
 /// Seperates the different LUT for the Display Refresh process
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RefreshLut {
     /// The "normal" full Lookuptable for the Refresh-Sequence
+    #[default]
     Full,
     /// The quick LUT where not the full refresh sequence is followed.
     /// This might lead to some
     Quick,
 }
 
-impl Default for RefreshLut {
-    fn default() -> Self {
-        RefreshLut::Full
+/// Where the waveform of a refresh comes from
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LutSource {
+    /// The waveform programmed into the OTP of the controller by the panel vendor
+    Otp,
+    /// A LUT written into the registers of the controller by the driver
+    Register,
+}
+
+/// Orientation of the image as seen by the user, independent of the panel
+///
+/// The orientations follow each other clockwise by a quarter turn. The graphics `Display`
+/// maps them to the rotation needed by the panel, whose native orientation is given by
+/// [`EpdUpdate::native_orientation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Orientation {
+    /// Taller than wide
+    #[default]
+    Portrait,
+    /// Wider than tall, a quarter turn clockwise from [`Orientation::Portrait`]
+    Landscape,
+    /// Upside down portrait
+    PortraitFlipped,
+    /// Upside down landscape
+    LandscapeFlipped,
+}
+
+impl Orientation {
+    /// Orientation of a panel of `width` x `height` pixels without any rotation
+    pub fn native(width: u32, height: u32) -> Orientation {
+        if width <= height {
+            Orientation::Portrait
+        } else {
+            Orientation::Landscape
+        }
+    }
+
+    /// Number of clockwise quarter turns from [`Orientation::Portrait`]
+    pub(crate) fn quarter_turns(self) -> u8 {
+        self as u8
+    }
+
+    pub(crate) fn from_quarter_turns(turns: u8) -> Orientation {
+        match turns % 4 {
+            0 => Orientation::Portrait,
+            1 => Orientation::Landscape,
+            2 => Orientation::PortraitFlipped,
+            _ => Orientation::LandscapeFlipped,
+        }
     }
 }
 
-pub(crate) trait InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayUs<u32>,
-{
-    /// This initialises the EPD and powers it up
+/// Health flags reported by the status registers of the controller
+///
+/// Returned by the `health()` function of the drivers whose controller can report them.
+/// The data line of the display must be readable for this, see the driver documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PanelHealth {
+    /// The panel glass check failed, the panel is most likely cracked or disconnected
     ///
-    /// This function is already called from
-    ///  - [new()](WaveshareDisplay::new())
-    ///  - [`wake_up`]
+    /// `None` if the controller has no panel-break detection
+    pub panel_break: Option<bool>,
+    /// The supply voltage is below the detection level of the controller
+    pub low_voltage: bool,
+}
+
+impl PanelHealth {
+    /// Returns true if no problem was reported by the controller
+    pub fn is_ok(&self) -> bool {
+        self.panel_break != Some(true) && !self.low_voltage
+    }
+}
+
+/// Values stored in the OTP of the controller by the panel manufacturer
+///
+/// Returned by the `otp_diagnostics()` function of the drivers whose controller can read
+/// them back, e.g. to log which waveform a panel shipped with. The data line of the display
+/// must be readable for this, see the driver documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OtpDiagnostics {
+    /// Version of the waveform, in the format of the controller
+    ///
+    /// `None` if the controller doesn't store a version
+    pub waveform_version: Option<u32>,
+    /// The VCOM register value, in the format of the controller
+    ///
+    /// `None` if the controller doesn't store a VCOM value
+    pub vcom: Option<u8>,
+}
+
+/// Instrumentation notified of the traffic between a driver and its controller
+///
+/// Registered with [`EpdHooks::set_command_observer`], e.g. to count the refreshes of a
+/// panel for wear tracking or to forward the commands to a debugging channel. All methods
+/// do nothing by default. They are called from within the driver, so they should return
+/// quickly; state can be kept with atomics or a `Cell`.
+pub trait CommandObserver {
+    /// A command with the given address is sent
+    fn on_command(&self, _command: u8) {}
+
+    /// A data transaction of `len` bytes is finished
+    fn on_data_len(&self, _len: usize) {}
+
+    /// A wait for the BUSY pin is finished
+    ///
+    /// The duration is estimated from the delays between the polls in µs, so it is a
+    /// lower bound of the time actually waited.
+    fn on_busy_wait(&self, _duration_us: u32) {}
+}
+
+pub(crate) mod sealed {
+    use crate::interface::Hooks;
+
+    /// Gives access to the hooks kept by the interface of a driver
+    pub trait HasHooks {
+        fn hooks(&mut self) -> &mut Hooks;
+    }
+}
+
+/// Hooks of the user called from within a driver
+///
+/// The hooks are kept by the interface to the controller, so they see the traffic of all the
+/// methods of the driver. Implemented by every driver.
+pub trait EpdHooks: sealed::HasHooks {
+    /// Registers an observer which is notified of every command, data transaction and
+    /// busy wait of the driver
     ///
+    /// See [`CommandObserver`]. `None` removes the observer again. The drivers don't borrow
+    /// anything, so the observer is a `static`, e.g. with atomic counters.
+    fn set_command_observer(&mut self, observer: Option<&'static dyn CommandObserver>) {
+        self.hooks().observer = observer;
+    }
+
+    /// Registers a function called on every poll of the BUSY pin, by all the waits of the
+    /// driver
     ///
-    /// This function calls [reset](WaveshareDisplay::reset),
-    /// so you don't need to call reset your self when trying to wake your device up
-    /// after setting it to sleep.
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    /// Useful to feed a watchdog or to service other peripherals during the long waits of a
+    /// refresh, including the waits inside e.g. [`display_frame`](EpdRefresh::display_frame).
+    /// `None` removes the hook again.
+    fn set_poll_hook(&mut self, hook: Option<&'static dyn Fn()>) {
+        self.hooks().poll_hook = hook;
+    }
+}
+
+impl<T: sealed::HasHooks> EpdHooks for T {}
+
+/// The colors a panel can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorKind {
+    /// Black and white, see [`Color`](crate::color::Color)
+    BlackWhite,
+    /// Black, white and red or yellow, see [`TriColor`](crate::color::TriColor)
+    TriColor,
+    /// Seven colors, see [`OctColor`](crate::color::OctColor)
+    OctColor,
+}
+
+/// What a driver supports, to adapt the behaviour to the panel at runtime
+///
+/// Returned by [`EpdUpdate::capabilities`] and available as `CAPABILITIES`
+/// constant in every driver module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayCapabilities {
+    /// The colors of the panel
+    pub color: ColorKind,
+    /// Has a 4-gray mode taking a [`Gray2`](crate::color::Gray2) buffer
+    pub grayscale: bool,
+    /// [`EpdUpdate::update_partial_frame`] is supported
+    pub partial_refresh: bool,
+    /// [`RefreshLut::Quick`] or [`QuickRefresh`] is supported
+    pub quick_refresh: bool,
+    /// Typical duration of a full refresh in milliseconds, as given by Waveshare
+    pub full_refresh_ms: u32,
+    /// Minimum interval between two full refreshes in milliseconds recommended by Waveshare,
+    /// 0 if there is none
+    pub min_refresh_interval_ms: u32,
 }
 
 /// Functions to interact with three color panels
@@ -66,9 +234,9 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error>;
+        black: BwPlane<'_>,
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Update only the black/white data of the display.
     ///
@@ -77,8 +245,8 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        black: &[u8],
-    ) -> Result<(), SPI::Error>;
+        black: BwPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Update only the chromatic data of the display.
     ///
@@ -88,55 +256,30 @@ where
         &mut self,
         spi: &mut SPI,
         delay: &mut DELAY,
-        chromatic: &[u8],
-    ) -> Result<(), SPI::Error>;
+        chromatic: ChromaticPlane<'_>,
+    ) -> Result<(), Error<SPI::Error>>;
+
+    /// Sets the backgrounds of the two planes used by `clear_frame` separately
+    ///
+    /// [`set_background_color`](EpdUpdate::set_background_color) sets both planes from a
+    /// single color, this e.g. allows a chromatic background around white content.
+    fn set_plane_backgrounds(&mut self, backgrounds: PlaneBackgrounds);
+
+    /// The color the panel shows for [`TriColor::Chromatic`](crate::color::TriColor::Chromatic)
+    ///
+    /// Used with [`TriColor::from_rgb`](crate::color::TriColor::from_rgb) to convert colors
+    /// for the right panel. Red unless the driver knows better.
+    fn chromatic_kind(&self) -> TriColorKind {
+        TriColorKind::Red
+    }
 }
 
-/// All the functions to interact with the EPDs
-///
-/// This trait includes all public functions to use the EPDs
-///
-/// # Example
+/// Creation of a driver
 ///
-///```rust, no_run
-///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
-///use embedded_graphics::{
-///    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
-///};
-///use epd_waveshare::{epd4in2::*, prelude::*};
-///#
-///# let expectations = [];
-///# let mut spi = spi::Mock::new(&expectations);
-///# let expectations = [];
-///# let cs_pin = pin::Mock::new(&expectations);
-///# let busy_in = pin::Mock::new(&expectations);
-///# let dc = pin::Mock::new(&expectations);
-///# let rst = pin::Mock::new(&expectations);
-///# let mut delay = delay::MockNoop::new();
-///
-///// Setup EPD
-///let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
-///
-///// Use display graphics from embedded-graphics
-///let mut display = Display4in2::default();
-///
-///// Use embedded graphics for drawing a line
-///
-///let _ = Line::new(Point::new(0, 120), Point::new(0, 295))
-///    .into_styled(PrimitiveStyle::with_stroke(Color::Black, 1))
-///    .draw(&mut display);
-///
-///    // Display updated frame
-///epd.update_frame(&mut spi, &display.buffer(), &mut delay)?;
-///epd.display_frame(&mut spi, &mut delay)?;
-///
-///// Set the EPD to sleep
-///epd.sleep(&mut spi, &mut delay)?;
-///# Ok(())
-///# }
-///```
-pub trait WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+/// Separate from [`EpdInit`] as only drivers of a single, known panel can be created from
+/// the pins alone, e.g. `AnyEpd` of the `any_epd` module is created with
+/// `AnyEpd::with_panel` instead.
+pub trait EpdNew<SPI, CS, BUSY, DC, RST, DELAY>: EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
 where
     SPI: Write<u8>,
     CS: OutputPin,
@@ -145,8 +288,6 @@ where
     RST: OutputPin,
     DELAY: DelayUs<u32>,
 {
-    /// The Color Type used by the Display
-    type DisplayColor;
     /// Creates a new driver from a SPI peripheral, CS Pin, Busy InputPin, DC
     ///
     /// `delay_us` is the number of us the idle loop should sleep on.
@@ -162,21 +303,73 @@ where
         rst: RST,
         delay: &mut DELAY,
         delay_us: Option<u32>,
-    ) -> Result<Self, SPI::Error>
+    ) -> Result<Self, Error<SPI::Error>>
+    where
+        Self: Sized,
+    {
+        let mut epd = Self::new_uninitialized(cs, busy, dc, rst, delay_us);
+        epd.init(spi, delay)?;
+        Ok(epd)
+    }
+
+    /// Creates a new driver like [`new`](EpdNew::new), but without talking to the device
+    ///
+    /// E.g. for panels which are powered down at boot. [`init`](EpdInit::init) needs to be
+    /// called once the panel is powered.
+    fn new_uninitialized(cs: CS, busy: BUSY, dc: DC, rst: RST, delay_us: Option<u32>) -> Self
     where
         Self: Sized;
+}
 
-    /// Let the device enter deep-sleep mode to save power.
+/// Initialisation and release of a driver
+pub trait EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// This initialises the EPD and powers it up
     ///
-    /// The deep sleep mode returns to standby with a hardware reset.
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    /// This function is already called from
+    ///  - [new()](EpdNew::new())
+    ///  - [`wake_up`](EpdPower::wake_up)
+    ///
+    /// This function resets the device, so you don't need to call reset your self
+    /// when trying to wake your device up after setting it to sleep.
+    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
 
-    /// Wakes the device up from sleep
+    /// Checks the BUSY connection and initialises the device again
     ///
-    /// Also reintialises the device if necessary.
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    /// Does a hardware reset and returns [`Error::BusyStuck`] if the BUSY pin doesn't release
+    /// within a second, e.g. because of a broken flex cable or a miswired pin. A BUSY pin
+    /// which always reads as idle can't be detected this way.
+    fn probe(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
+
+    /// Releases the driver and returns its pins, e.g. to hand them to another peripheral
+    ///
+    /// The EPD is left in its current state, call [`sleep`](EpdPower::sleep) before if needed.
+    fn free(self) -> (CS, BUSY, DC, RST)
+    where
+        Self: Sized;
+}
 
-    /// Sets the backgroundcolor for various commands like [clear_frame](WaveshareDisplay::clear_frame)
+/// Transmitting frames to the SRAM of the EPD
+pub trait EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// The Color Type used by the Display
+    type DisplayColor;
+
+    /// Sets the backgroundcolor for various commands like [clear_frame](EpdUpdate::clear_frame)
     fn set_background_color(&mut self, color: Self::DisplayColor);
 
     /// Get current background color
@@ -188,19 +381,45 @@ where
     /// Get the height of the display
     fn height(&self) -> u32;
 
+    /// Get what the driver supports
+    fn capabilities(&self) -> DisplayCapabilities;
+
+    /// Orientation of the panel when the frame isn't rotated
+    fn native_orientation(&self) -> Orientation {
+        Orientation::native(self.width(), self.height())
+    }
+
+    /// Number of bytes of a line of a frame, including the padding at its end
+    fn row_bytes(&self) -> usize {
+        crate::row_bytes(self.width() as usize)
+    }
+
+    /// Width in pixels of a line of a frame, including the padding at its end
+    fn padded_width(&self) -> usize {
+        crate::padded_width(self.width() as usize)
+    }
+
+    /// Number of bytes of a frame, for three color displays of one of the two planes
+    fn plane_len(&self) -> usize {
+        self.row_bytes() * self.height() as usize
+    }
+
     /// Transmit a full frame to the SRAM of the EPD
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Transmits partial data to the SRAM of the EPD
     ///
     /// (x,y) is the top left corner
     ///
     /// BUFFER needs to be of size: width / 8 * height !
+    ///
+    /// Returns [`Error::InvalidRegion`] if `x` or `width` aren't multiples of 8, if the window
+    /// exceeds the display or if the buffer doesn't match its size.
     #[allow(clippy::too_many_arguments)]
     fn update_partial_frame(
         &mut self,
@@ -211,12 +430,28 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
+
+    /// Clears the frame buffer on the EPD with the declared background color
+    ///
+    /// The background color can be changed with [`EpdUpdate::set_background_color`]
+    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
+}
 
+/// Refreshing the EPD with the data of its SRAM
+pub trait EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
     /// Displays the frame data from SRAM
     ///
     /// This function waits until the device isn`t busy anymore
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
 
     /// Provide a combined update&display and save some time (skipping a busy check in between)
     fn update_and_display_frame(
@@ -224,12 +459,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
-
-    /// Clears the frame buffer on the EPD with the declared background color
-    ///
-    /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Trait for using various Waveforms from different LUTs
     /// E.g. for partial refreshes
@@ -244,12 +474,225 @@ where
         spi: &mut SPI,
         delay: &mut DELAY,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
+
+    /// The refresh mode the next [`display_frame`](EpdRefresh::display_frame) uses
+    ///
+    /// Always [`RefreshLut::Full`] for drivers without a quick refresh mode.
+    fn refresh_mode(&self) -> RefreshLut;
+
+    /// Where the waveform of the next [`display_frame`](EpdRefresh::display_frame) comes from
+    fn lut_source(&self) -> LutSource;
+
+    /// Checks if the display is still processing data, without blocking
+    ///
+    /// Reads the BUSY pin with the polarity of the display. Useful to skip a frame
+    /// instead of stalling in `wait_until_idle`.
+    fn is_busy(&self) -> bool;
 
     /// Wait until the display has stopped processing data
     ///
     /// You can call this to make sure a frame is displayed before goin further
-    fn wait_until_idle(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn wait_until_idle(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>> {
+        self.wait_until_idle_with(spi, delay, &mut || {})
+    }
+
+    /// Same as [`wait_until_idle`](EpdRefresh::wait_until_idle), calling `on_poll` on every
+    /// poll of the BUSY pin
+    ///
+    /// Useful to run something during a single wait. To run something during all the waits of
+    /// the driver, including the ones inside the other methods, register it with
+    /// [`EpdHooks::set_poll_hook`] instead.
+    fn wait_until_idle_with(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        on_poll: &mut dyn FnMut(),
+    ) -> Result<(), Error<SPI::Error>>;
+
+    /// Same as [`wait_until_idle`](EpdRefresh::wait_until_idle), but returns
+    /// [`Error::BusyStuck`] if the display is still busy after `timeout_ms`
+    fn wait_until_idle_timeout(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        timeout_ms: u32,
+    ) -> Result<(), Error<SPI::Error>>;
+}
+
+/// Power management of the EPD
+pub trait EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Let the device enter deep-sleep mode to save power.
+    ///
+    /// The deep sleep mode returns to standby with a hardware reset.
+    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
+
+    /// Wakes the device up from sleep
+    ///
+    /// Also reintialises the device if necessary.
+    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>>;
+}
+
+/// All the functions to interact with the EPDs
+///
+/// This trait includes all public functions to use the EPDs. It is implemented for every
+/// type implementing [`EpdNew`], [`EpdInit`], [`EpdUpdate`], [`EpdRefresh`] and [`EpdPower`],
+/// generic code only needing some of them can use these instead. Drivers which can't be
+/// created from the pins alone, like `AnyEpd` of the `any_epd` module, only implement the
+/// individual traits.
+///
+/// # Example
+///
+///```rust, no_run
+///# use embedded_hal_mock::*;
+///# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+///use embedded_graphics::{
+///    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
+///};
+///use epd_waveshare::{epd4in2::*, prelude::*};
+///#
+///# let expectations = [];
+///# let mut spi = spi::Mock::new(&expectations);
+///# let expectations = [];
+///# let cs_pin = pin::Mock::new(&expectations);
+///# let busy_in = pin::Mock::new(&expectations);
+///# let dc = pin::Mock::new(&expectations);
+///# let rst = pin::Mock::new(&expectations);
+///# let mut delay = delay::MockNoop::new();
+///
+///// Setup EPD
+///let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+///
+///// Use display graphics from embedded-graphics
+///let mut display = Display4in2::default();
+///
+///// Use embedded graphics for drawing a line
+///
+///let _ = Line::new(Point::new(0, 120), Point::new(0, 295))
+///    .into_styled(PrimitiveStyle::with_stroke(Color::Black, 1))
+///    .draw(&mut display);
+///
+///    // Display updated frame
+///epd.update_frame(&mut spi, &display.buffer(), &mut delay)?;
+///epd.display_frame(&mut spi, &mut delay)?;
+///
+///// Set the EPD to sleep
+///epd.sleep(&mut spi, &mut delay)?;
+///# Ok(())
+///# }
+///```
+pub trait WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>:
+    EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+    + EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+    + EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    + EpdPower<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
+    /// Brings the display back into a known state after an interrupted or failed refresh
+    ///
+    /// E.g. after a brown out or a reset of the MCU in the middle of a refresh. Does a hardware
+    /// reset, initialises the display again and refreshes it with a cleared frame in the
+    /// background color, waiting until the refresh is done.
+    ///
+    /// The current refresh mode is kept, switch to [`RefreshLut::Full`] with
+    /// [`set_lut`](EpdRefresh::set_lut) first to also get rid of ghosting.
+    fn recover(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), Error<SPI::Error>> {
+        self.init(spi, delay)?;
+        self.clear_frame(spi, delay)?;
+        self.display_frame(spi, delay)?;
+        self.wait_until_idle(spi, delay)
+    }
+
+    /// Draws a frame into `display` with `draw`, transmits it and refreshes the display
+    ///
+    /// Three color frames are transmitted with both planes and LSB first buffers in the bit
+    /// order of the controller, see [`SendFrame`](crate::graphics::SendFrame). `draw` is also
+    /// responsible for clearing the previous content of `display` if needed.
+    ///
+    /// 4-gray displays can't be rendered this way, their frames are sent with the
+    /// `update_frame_gray2` methods of the drivers and need the LUT selected by them.
+    ///
+    ///```rust, no_run
+    ///# use embedded_hal_mock::*;
+    ///# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+    ///use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}};
+    ///use epd_waveshare::{epd2in13b_v4::*, prelude::*};
+    ///#
+    ///# let expectations = [];
+    ///# let mut spi = spi::Mock::new(&expectations);
+    ///# let expectations = [];
+    ///# let cs_pin = pin::Mock::new(&expectations);
+    ///# let busy_in = pin::Mock::new(&expectations);
+    ///# let dc = pin::Mock::new(&expectations);
+    ///# let rst = pin::Mock::new(&expectations);
+    ///# let mut delay = delay::MockNoop::new();
+    ///
+    ///let mut epd = Epd2in13b::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay, None)?;
+    ///let mut display = Display2in13b::default();
+    ///
+    ///// Sends both planes with `update_color_frame`
+    ///epd.render(&mut spi, &mut delay, &mut display, |display| {
+    ///    display.clear(TriColor::White).ok();
+    ///    let _ = Circle::new(Point::new(20, 20), 40)
+    ///        .into_styled(PrimitiveStyle::with_fill(TriColor::Chromatic))
+    ///        .draw(display);
+    ///})?;
+    ///# Ok(())
+    ///# }
+    ///```
+    #[cfg(feature = "graphics")]
+    fn render<D, F>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        display: &mut D,
+        draw: F,
+    ) -> Result<(), Error<SPI::Error>>
+    where
+        Self: Sized,
+        D: crate::graphics::SendFrame<Self, SPI, CS, BUSY, DC, RST, DELAY>,
+        F: FnOnce(&mut D),
+    {
+        draw(display);
+        display.send_frame(self, spi, delay)?;
+        self.display_frame(spi, delay)?;
+        Ok(())
+    }
+}
+
+impl<T, SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY> for T
+where
+    T: EpdNew<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdInit<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+        + EpdPower<SPI, CS, BUSY, DC, RST, DELAY>,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayUs<u32>,
+{
 }
 
 /// Allows quick refresh support for displays that support it; lets you send both
@@ -263,7 +706,7 @@ where
 /// Example:
 ///```rust, no_run
 ///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
+///# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
 ///# use embedded_graphics::{
 ///#   pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 ///# };
@@ -312,7 +755,7 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Updates the new frame.
     fn update_new_frame(
@@ -320,10 +763,14 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Displays the new frame
-    fn display_new_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Updates and displays the new frame.
     fn update_and_display_new_frame(
@@ -331,9 +778,11 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Updates the old frame for a portion of the display.
+    ///
+    /// Validates the window like [`EpdUpdate::update_partial_frame`].
     #[allow(clippy::too_many_arguments)]
     fn update_partial_old_frame(
         &mut self,
@@ -344,9 +793,11 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Updates the new frame for a portion of the display.
+    ///
+    /// Validates the window like [`EpdUpdate::update_partial_frame`].
     #[allow(clippy::too_many_arguments)]
     fn update_partial_new_frame(
         &mut self,
@@ -357,10 +808,10 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 
     /// Clears the partial frame buffer on the EPD with the declared background color
-    /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
+    /// The background color can be changed with [`EpdUpdate::set_background_color`]
     fn clear_partial_frame(
         &mut self,
         spi: &mut SPI,
@@ -369,5 +820,5 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), Error<SPI::Error>>;
 }
@@ -0,0 +1,92 @@
+//! Transports for the frames sent to the controller
+//!
+//! Commands and their parameters are always sent over the spi bus with a single data line.
+//! The frames, by far the largest part of the traffic, go through a [`Transport`] instead.
+//! [`SingleSpi`] sends them like the commands, which is what every driver does by default.
+//!
+//! Controllers with a multi line input mode can take the frames over more of the data lines
+//! of a QSPI peripheral, which cuts the time needed to transfer a frame. The transport is
+//! given the same bus the commands are sent over, so it can switch the peripheral to the
+//! matching mode for the frames. See
+//! [`Epd7in5::with_transport`](crate::epd7in5_v2::Epd7in5::with_transport) for a panel
+//! supporting this.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::Error<MockError>> {
+//!use embedded_hal::blocking::spi::Write;
+//!use epd_waveshare::{epd7in5_v2::Epd7in5, prelude::*, transport::Transport};
+//!
+//!/// A QSPI peripheral, using one line for `Write` and two for `write_dual`
+//!struct Qspi;
+//!
+//!impl Write<u8> for Qspi {
+//!    type Error = MockError;
+//!
+//!    fn write(&mut self, _words: &[u8]) -> Result<(), MockError> {
+//!        Ok(())
+//!    }
+//!}
+//!
+//!impl Qspi {
+//!    fn write_dual(&mut self, _words: &[u8]) -> Result<(), MockError> {
+//!        Ok(())
+//!    }
+//!}
+//!
+//!struct Dual;
+//!
+//!impl Transport<Qspi> for Dual {
+//!    fn data_lines(&self) -> u8 {
+//!        2
+//!    }
+//!
+//!    fn write_data(&mut self, qspi: &mut Qspi, data: &[u8]) -> Result<(), MockError> {
+//!        qspi.write_dual(data)
+//!    }
+//!}
+//!#
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut qspi = Qspi;
+//!let mut epd = Epd7in5::with_transport(&mut qspi, cs_pin, busy_in, dc, rst, &mut delay, None, Dual)?;
+//!
+//!let buffer = [0xFF; epd_waveshare::buffer_len(800, 480)];
+//!epd.update_and_display_frame(&mut qspi, &buffer, &mut delay).ok();
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::blocking::spi::Write;
+
+/// Sends the frames of the drivers to the controller
+pub trait Transport<SPI: Write<u8>> {
+    /// Number of data lines the frames are sent over
+    ///
+    /// The driver switches the controller to the matching input mode.
+    fn data_lines(&self) -> u8 {
+        1
+    }
+
+    /// Sends the frame data `data` over `spi`, or over the data lines connected to it
+    ///
+    /// CS and DC are already set by the driver.
+    fn write_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error>;
+}
+
+/// Sends the frames over the spi bus, like the commands
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SingleSpi;
+
+impl<SPI: Write<u8>> Transport<SPI> for SingleSpi {
+    fn write_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+        crate::interface::write_spi(spi, data)
+    }
+}
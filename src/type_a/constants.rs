@@ -1,3 +1,5 @@
+use crate::lut_table::lut_table;
+
 #[cfg(not(any(feature = "type_a_alternative_faster_lut")))]
 #[rustfmt::skip]
 // Original Waveforms from Waveshare
@@ -23,3 +25,31 @@ pub(crate) const LUT_FULL_UPDATE: [u8; 30] =[
     0x50, 0xAA, 0x55, 0xAA, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
 ];
+
+#[rustfmt::skip]
+// 4 gray levels waveform from the Waveshare 4-gray demos for the SSD168x controllers
+// LUT0 to LUT3 drive black, dark gray, light gray and white, the 153 bytes of LUT are
+// followed by 0x3F, the gate voltage, the source voltages and VCOM
+lut_table! {
+    pub(crate) LUT_GRAY_UPDATE: [u8; 159] = [
+        0x00, 0x60, 0x10, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x20, 0x60, 0x10, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x28, 0x60, 0x14, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x2A, 0x60, 0x15, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x00, 0x90, 0x00, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x00, 0x02, 0x00, 0x05, 0x14, 0x0, 0x0, 
+        0x1E, 0x1E, 0x00, 0x00, 0x00, 0x0, 0x01, 
+        0x00, 0x02, 0x00, 0x05, 0x14, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 
+        0x24, 0x22, 0x22, 0x22, 0x23, 0x32, 0x0, 0x0, 0x0, 
+        0x22, 0x17, 0x41, 0xAE, 0x32, 0x28, 
+    ];
+}